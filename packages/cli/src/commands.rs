@@ -10,12 +10,14 @@ mod checkin;
 mod checkout;
 mod checksum;
 mod clean;
+mod deprecate;
 mod doc;
 mod env;
 mod exec;
 mod fmt;
 mod get;
 mod init;
+mod key;
 mod log;
 mod login;
 mod lsp;
@@ -24,13 +26,16 @@ mod outdated;
 mod publish;
 mod pull;
 mod push;
+mod repl;
 mod run;
 mod search;
+mod secret;
 mod server;
 mod test;
 mod tree;
 mod update;
 mod upgrade;
+mod yank;
 
 #[derive(Debug, clap::Parser)]
 #[command(
@@ -55,12 +60,14 @@ pub enum Command {
 	Checkout(self::checkout::Args),
 	Checksum(self::checksum::Args),
 	Clean(self::clean::Args),
+	Deprecate(self::deprecate::Args),
 	Doc(self::doc::Args),
 	Env(self::env::Args),
 	Exec(self::exec::Args),
 	Fmt(self::fmt::Args),
 	Get(self::get::Args),
 	Init(self::init::Args),
+	Key(self::key::Args),
 	Log(self::log::Args),
 	Login(self::login::Args),
 	Lsp(self::lsp::Args),
@@ -69,13 +76,16 @@ pub enum Command {
 	Publish(self::publish::Args),
 	Pull(self::pull::Args),
 	Push(self::push::Args),
+	Repl(self::repl::Args),
 	Run(self::run::Args),
 	Search(self::search::Args),
+	Secret(self::secret::Args),
 	Server(self::server::Args),
 	Test(self::test::Args),
 	Tree(self::tree::Args),
 	Update(self::update::Args),
 	Upgrade(self::upgrade::Args),
+	Yank(self::yank::Args),
 }
 
 #[derive(Debug, clap::Args)]
@@ -107,12 +117,14 @@ impl Cli {
 			Command::Checkout(args) => self.command_checkout(args).boxed(),
 			Command::Checksum(args) => self.command_checksum(args).boxed(),
 			Command::Clean(args) => self.command_clean(args).boxed(),
+			Command::Deprecate(args) => self.command_deprecate(args).boxed(),
 			Command::Doc(args) => self.command_doc(args).boxed(),
 			Command::Env(args) => self.command_env(args).boxed(),
 			Command::Exec(args) => self.command_exec(args).boxed(),
 			Command::Fmt(args) => self.command_fmt(args).boxed(),
 			Command::Get(args) => self.command_get(args).boxed(),
 			Command::Init(args) => self.command_init(args).boxed(),
+			Command::Key(args) => self.command_key(args).boxed(),
 			Command::Log(args) => self.command_log(args).boxed(),
 			Command::Login(args) => self.command_login(args).boxed(),
 			Command::Lsp(args) => self.command_lsp(args).boxed(),
@@ -121,13 +133,16 @@ impl Cli {
 			Command::Publish(args) => self.command_publish(args).boxed(),
 			Command::Pull(args) => self.command_pull(args).boxed(),
 			Command::Push(args) => self.command_push(args).boxed(),
+			Command::Repl(args) => self.command_repl(args).boxed(),
 			Command::Run(args) => self.command_run(args).boxed(),
 			Command::Search(args) => self.command_search(args).boxed(),
+			Command::Secret(args) => self.command_secret(args).boxed(),
 			Command::Server(args) => self.command_server(args).boxed(),
 			Command::Test(args) => self.command_test(args).boxed(),
 			Command::Tree(args) => self.command_tree(args).boxed(),
 			Command::Update(args) => self.command_update(args).boxed(),
 			Command::Upgrade(args) => self.command_upgrade(args).boxed(),
+			Command::Yank(args) => self.command_yank(args).boxed(),
 		}
 		.await?;
 		Ok(())
@@ -3,7 +3,10 @@ use crate::{
 	tui::{self, Tui},
 	Cli,
 };
-use std::path::PathBuf;
+use std::{
+	net::SocketAddr,
+	path::{Path, PathBuf},
+};
 use tangram_client as tg;
 use tangram_error::{Result, WrapErr};
 
@@ -15,6 +18,21 @@ pub struct Args {
 	#[arg(short, long)]
 	pub detach: bool,
 
+	/// Record a heap snapshot at the end of the build and write it to this path, which defaults to `<build id>.heapsnapshot`. This implies `--profile`.
+	#[allow(clippy::option_option)]
+	#[arg(long, value_name = "PATH", num_args = 0..=1, require_equals = true)]
+	pub heap_snapshot: Option<Option<PathBuf>>,
+
+	/// Run the target with an inspector that a debugger such as Chrome can connect to. The build pauses until a debugger connects, and again before the target's function is called. The address must be a loopback address, and the server must be reached over its UNIX socket.
+	#[arg(
+		long,
+		value_name = "ADDR",
+		num_args = 0..=1,
+		require_equals = true,
+		default_missing_value = "127.0.0.1:9229"
+	)]
+	pub inspect: Option<SocketAddr>,
+
 	/// Disable the TUI.
 	#[arg(long, default_value = "false")]
 	pub no_tui: bool,
@@ -30,6 +48,11 @@ pub struct Args {
 	#[command(flatten)]
 	pub package_args: PackageArgs,
 
+	/// Record a CPU profile of the build and write it to this path, which defaults to `<build id>.cpuprofile`. The profile can be opened in Chrome's developer tools or in speedscope.
+	#[allow(clippy::option_option)]
+	#[arg(long, value_name = "PATH", num_args = 0..=1, require_equals = true)]
+	pub profile: Option<Option<PathBuf>>,
+
 	/// The retry strategy to use.
 	#[arg(long, default_value_t)]
 	pub retry: tg::build::Retry,
@@ -79,8 +102,27 @@ impl Cli {
 		// Print the target ID.
 		eprintln!("{}", target.id(tg).await?);
 
+		// Get the profile options.
+		let profile = (args.profile.is_some() || args.heap_snapshot.is_some()).then(|| {
+			tg::build::ProfileOptions {
+				heap_snapshot: args.heap_snapshot.is_some(),
+			}
+		});
+
 		// Build the target.
-		let build = target.build(tg, None, 0, args.retry).await?;
+		let target_id = target.id(tg).await?;
+		let build_id = tg
+			.get_or_create_build_for_target(None, target_id, 0, args.retry, args.inspect, profile)
+			.await?;
+		let build = tg::Build::with_id(build_id);
+
+		// If the inspector is enabled, then print where the debugger should connect.
+		if let Some(addr) = args.inspect {
+			eprintln!(
+				"Waiting for a debugger at ws://{addr}/{}. Open chrome://inspect to connect.",
+				build.id()
+			);
+		}
 
 		// If the detach flag is set, then exit.
 		if args.detach {
@@ -111,6 +153,27 @@ impl Cli {
 		// Handle for an error that occurred while waiting for the build's outcome.
 		let outcome = outcome.wrap_err("Failed to get the build outcome.")?;
 
+		// Write the profile if one was requested and recorded.
+		if profile.is_some() {
+			let object = build.object(tg).await?;
+			if let Some(profile) = object.profile.as_ref() {
+				let path = args
+					.profile
+					.flatten()
+					.unwrap_or_else(|| format!("{}.cpuprofile", build.id()).into());
+				write_blob(tg, &profile.cpu, &path).await?;
+				eprintln!("Wrote the CPU profile to {}.", path.display());
+				if let Some(heap_snapshot) = profile.heap_snapshot.as_ref() {
+					let path = args
+						.heap_snapshot
+						.flatten()
+						.unwrap_or_else(|| format!("{}.heapsnapshot", build.id()).into());
+					write_blob(tg, heap_snapshot, &path).await?;
+					eprintln!("Wrote the heap snapshot to {}.", path.display());
+				}
+			}
+		}
+
 		// Handle a failed build.
 		let output = outcome.into_result().wrap_err("The build failed.")?;
 
@@ -130,3 +193,12 @@ impl Cli {
 		Ok(())
 	}
 }
+
+/// Write a blob's contents to a file.
+async fn write_blob(tg: &dyn tg::Handle, blob: &tg::Blob, path: &Path) -> Result<()> {
+	let bytes = blob.bytes(tg).await?;
+	tokio::fs::write(path, bytes)
+		.await
+		.wrap_err_with(|| format!(r#"Failed to write the file at "{}"."#, path.display()))?;
+	Ok(())
+}
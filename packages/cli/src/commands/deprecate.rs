@@ -0,0 +1,43 @@
+use crate::Cli;
+use tangram_client as tg;
+use tangram_error::{return_error, Result, WrapErr};
+
+/// Deprecate a published package.
+///
+/// The message is shown to users of the package by `tg update` and the language server.
+#[derive(Debug, clap::Args)]
+#[command(verbatim_doc_comment)]
+pub struct Args {
+	/// The name of the package to deprecate.
+	pub package: tg::Dependency,
+
+	/// The message that explains why the package is deprecated.
+	#[arg(long, required_unless_present = "undo")]
+	pub message: Option<String>,
+
+	/// Undo a previous deprecation.
+	#[arg(long, conflicts_with = "message")]
+	pub undo: bool,
+}
+
+impl Cli {
+	pub async fn command_deprecate(&self, args: Args) -> Result<()> {
+		let tg = self.handle().await?;
+		let tg = tg.as_ref();
+
+		// Get the user.
+		let user = self.user().await?;
+
+		// Verify that the dependency has a name.
+		if args.package.name.is_none() {
+			return_error!("Expected a package name.");
+		}
+
+		// Deprecate the package.
+		tg.deprecate_package(user.as_ref(), &args.package, args.message.as_deref())
+			.await
+			.wrap_err("Failed to deprecate the package.")?;
+
+		Ok(())
+	}
+}
@@ -0,0 +1,141 @@
+use crate::Cli;
+use std::collections::BTreeSet;
+use tangram_client as tg;
+use tangram_error::{return_error, Result, WrapErr};
+use tokio::io::AsyncWriteExt;
+
+/// Manage the key that signs published packages and the keys that are trusted to sign dependencies.
+#[derive(Debug, clap::Args)]
+#[command(verbatim_doc_comment)]
+pub struct Args {
+	#[command(subcommand)]
+	pub command: Command,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum Command {
+	/// Generate a signing key and print its public key.
+	Generate {
+		/// Replace an existing signing key.
+		#[arg(long)]
+		force: bool,
+	},
+
+	/// Print the public key of the signing key.
+	Show,
+
+	/// Trust a public key to sign dependencies.
+	Trust {
+		/// The hex encoded public key.
+		public_key: String,
+	},
+
+	/// Stop trusting a public key.
+	Untrust {
+		/// The hex encoded public key.
+		public_key: String,
+	},
+
+	/// List the trusted public keys.
+	List,
+}
+
+impl Cli {
+	pub async fn command_key(&self, args: Args) -> Result<()> {
+		match args.command {
+			Command::Generate { force } => {
+				if !force && self.signing_key().await?.is_some() {
+					return_error!("A signing key already exists. Pass --force to replace it.");
+				}
+				let signing_key = tg::package::SigningKey::generate();
+				self.save_signing_key(&signing_key).await?;
+				println!("{}", signing_key.public_key());
+			},
+			Command::Show => {
+				let Some(signing_key) = self.signing_key().await? else {
+					return_error!("There is no signing key. Run `tg key generate` to create one.");
+				};
+				println!("{}", signing_key.public_key());
+			},
+			Command::Trust { public_key } => {
+				// Signatures encode public keys in lowercase hex.
+				let public_key = public_key.to_lowercase();
+				let valid = hex::decode(&public_key).is_ok_and(|bytes| bytes.len() == 32);
+				if !valid {
+					return_error!(r#"The public key "{public_key}" is not valid."#);
+				}
+				let mut trusted_keys = self.trusted_keys().await?;
+				trusted_keys.insert(public_key);
+				self.save_trusted_keys(&trusted_keys).await?;
+			},
+			Command::Untrust { public_key } => {
+				let public_key = public_key.to_lowercase();
+				let mut trusted_keys = self.trusted_keys().await?;
+				if !trusted_keys.remove(&public_key) {
+					return_error!(r#"The public key "{public_key}" is not trusted."#);
+				}
+				self.save_trusted_keys(&trusted_keys).await?;
+			},
+			Command::List => {
+				for public_key in self.trusted_keys().await? {
+					println!("{public_key}");
+				}
+			},
+		}
+		Ok(())
+	}
+
+	pub(crate) async fn signing_key(&self) -> Result<Option<tg::package::SigningKey>> {
+		let path = self.path.join("signing_key");
+		let exists = tokio::fs::try_exists(&path)
+			.await
+			.wrap_err("Failed to check if the signing key file exists.")?;
+		if !exists {
+			return Ok(None);
+		}
+		let signing_key = tokio::fs::read_to_string(path)
+			.await
+			.wrap_err("Failed to read the signing key file.")?;
+		Ok(Some(signing_key.parse()?))
+	}
+
+	async fn save_signing_key(&self, signing_key: &tg::package::SigningKey) -> Result<()> {
+		let path = self.path.join("signing_key");
+		tokio::fs::OpenOptions::new()
+			.write(true)
+			.create(true)
+			.truncate(true)
+			.mode(0o600)
+			.open(path)
+			.await
+			.wrap_err("Failed to open the signing key file.")?
+			.write_all(signing_key.to_string().as_bytes())
+			.await
+			.wrap_err("Failed to save the signing key.")?;
+		Ok(())
+	}
+
+	async fn trusted_keys(&self) -> Result<BTreeSet<String>> {
+		let path = self.path.join("trusted_keys.json");
+		let exists = tokio::fs::try_exists(&path)
+			.await
+			.wrap_err("Failed to check if the trusted keys file exists.")?;
+		if !exists {
+			return Ok(BTreeSet::new());
+		}
+		let trusted_keys = tokio::fs::read_to_string(path)
+			.await
+			.wrap_err("Failed to read the trusted keys file.")?;
+		serde_json::from_str(&trusted_keys).wrap_err("Failed to deserialize the trusted keys.")
+	}
+
+	async fn save_trusted_keys(&self, trusted_keys: &BTreeSet<String>) -> Result<()> {
+		let path = self.path.join("trusted_keys.json");
+		let trusted_keys = serde_json::to_string_pretty(trusted_keys)
+			.wrap_err("Failed to serialize the trusted keys.")?;
+		tokio::fs::write(path, trusted_keys)
+			.await
+			.wrap_err("Failed to save the trusted keys.")?;
+		Ok(())
+	}
+}
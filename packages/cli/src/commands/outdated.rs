@@ -1,14 +1,160 @@
 use crate::Cli;
-use tangram_error::{return_error, Result};
+use tangram_client as tg;
+use tangram_error::{return_error, Result, WrapErr};
 
 /// Check for outdated dependencies.
+///
+/// For each of the package's direct dependencies, print the locked version, the newest version that satisfies the dependency's version requirement, and the newest version overall. Only the package's direct dependencies are checked, not the dependencies of its dependencies, and path dependencies are skipped. The command fails if any dependency is outdated.
 #[derive(Debug, clap::Args)]
 #[command(verbatim_doc_comment)]
-pub struct Args {}
+pub struct Args {
+	#[arg(default_value = ".")]
+	pub package: tg::Dependency,
+
+	/// The output format.
+	#[arg(long, value_enum, default_value = "text")]
+	pub format: Format,
+}
+
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+pub enum Format {
+	Text,
+	Json,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct Entry {
+	name: String,
+
+	/// The version in the lock.
+	current: String,
+
+	/// The newest version that satisfies the dependency's version requirement.
+	compatible: Option<String>,
+
+	/// The newest version.
+	latest: String,
+}
 
 impl Cli {
-	#[allow(clippy::unused_async)]
-	pub async fn command_outdated(&self, _args: Args) -> Result<()> {
-		return_error!("This command is not yet implemented.");
+	pub async fn command_outdated(&self, args: Args) -> Result<()> {
+		let tg = self.handle().await?;
+		let tg = tg.as_ref();
+
+		// Get the package and its lock.
+		let (_, lock) = tg::package::get_with_lock(tg, &args.package).await?;
+
+		// Compare each direct dependency's locked version against the published versions.
+		let mut entries = Vec::new();
+		for (dependency, entry) in lock.dependencies(tg).await? {
+			// Skip path dependencies.
+			if dependency.path.is_some() {
+				continue;
+			}
+			let Some(name) = dependency.name.clone() else {
+				continue;
+			};
+
+			// Get the locked version.
+			let id = entry.package.id(tg).await?.clone();
+			let metadata = tg
+				.get_package_metadata(&tg::Dependency::with_id(id))
+				.await?;
+			let current: semver::Version = metadata
+				.version
+				.wrap_err_with(|| {
+					format!(r#"The locked package "{name}" does not have a version."#)
+				})?
+				.parse()
+				.wrap_err_with(|| format!(r#"Failed to parse the locked version of "{name}"."#))?;
+
+			// Get the published versions. Versions that are not valid semver are ignored.
+			let versions: Vec<semver::Version> = tg
+				.get_package_versions(&tg::Dependency::with_name(name.clone()))
+				.await?
+				.iter()
+				.filter_map(|version| version.parse().ok())
+				.collect();
+
+			// Get the newest compatible version and the newest version.
+			let req: Option<semver::VersionReq> = dependency
+				.version
+				.as_ref()
+				.map(|version| version.parse())
+				.transpose()
+				.wrap_err_with(|| format!(r#"Failed to parse the version of "{dependency}"."#))?;
+			let compatible = versions
+				.iter()
+				.filter(|version| req.as_ref().map_or(true, |req| req.matches(version)))
+				.max()
+				.cloned();
+			let latest = versions.iter().max().cloned().unwrap_or(current.clone());
+
+			// Add the entry if there is a newer version.
+			let outdated = latest > current
+				|| compatible
+					.as_ref()
+					.is_some_and(|compatible| compatible > &current);
+			if outdated {
+				entries.push(Entry {
+					name,
+					current: current.to_string(),
+					compatible: compatible.as_ref().map(ToString::to_string),
+					latest: latest.to_string(),
+				});
+			}
+		}
+
+		// Print the entries.
+		match args.format {
+			Format::Text => {
+				if entries.is_empty() {
+					eprintln!("All dependencies are up to date.");
+				} else {
+					let rows = entries
+						.iter()
+						.map(|entry| {
+							[
+								entry.name.clone(),
+								entry.current.clone(),
+								entry.compatible.clone().unwrap_or_else(|| "-".to_owned()),
+								entry.latest.clone(),
+							]
+						})
+						.collect::<Vec<_>>();
+					let header = ["Name", "Current", "Compatible", "Latest"].map(ToOwned::to_owned);
+					let widths: Vec<usize> = (0..header.len())
+						.map(|column| {
+							std::iter::once(&header)
+								.chain(&rows)
+								.map(|row| row[column].len())
+								.max()
+								.unwrap()
+						})
+						.collect();
+					for row in std::iter::once(&header).chain(&rows) {
+						let line = row
+							.iter()
+							.zip(&widths)
+							.map(|(cell, width)| format!("{cell:width$}"))
+							.collect::<Vec<_>>()
+							.join("  ");
+						println!("{}", line.trim_end());
+					}
+				}
+			},
+			Format::Json => {
+				let output = serde_json::to_string_pretty(&entries)
+					.wrap_err("Failed to serialize the outdated dependencies.")?;
+				println!("{output}");
+			},
+		}
+
+		// Fail if any dependency is outdated.
+		if !entries.is_empty() {
+			return_error!("Some dependencies are outdated.");
+		}
+
+		Ok(())
 	}
 }
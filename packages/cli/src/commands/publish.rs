@@ -1,35 +1,193 @@
 use crate::Cli;
+use std::collections::{BTreeSet, VecDeque};
 use tangram_client as tg;
-use tangram_error::{Result, WrapErr};
+use tangram_error::{return_error, Result, WrapErr};
+use tg::package::Ext;
 
 /// Publish a package.
+///
+/// Before publishing, the package is validated. Its metadata must have a name and a semver version that has not been published, it must not have path dependencies outside the package, all its other dependencies must resolve from the registry, and it must type check.
 #[derive(Debug, clap::Args)]
 #[command(verbatim_doc_comment)]
 pub struct Args {
 	#[arg(short, long, default_value = ".")]
 	pub package: tg::Dependency,
+
+	/// Validate the package and print what would be published without publishing it.
+	#[arg(long)]
+	pub dry_run: bool,
 }
 
 impl Cli {
-	#[allow(clippy::unused_async)]
 	pub async fn command_publish(&self, args: Args) -> Result<()> {
 		let tg = self.handle().await?;
 		let tg = tg.as_ref();
 
-		// Get the user.
-		let user = self.user().await?;
+		// Canonicalize the path.
+		let mut package = args.package;
+		if let Some(path) = package.path.as_mut() {
+			*path = tokio::fs::canonicalize(&path)
+				.await
+				.wrap_err("Failed to canonicalize the path.")?
+				.try_into()?;
+		}
 
 		// Create the package.
-		let (package, _) = tg::package::get_with_lock(tg, &args.package).await?;
+		let (package, lock) = tg::package::get_with_lock(tg, &package).await?;
 
 		// Get the package ID.
-		let id = package.id(tg).await?;
+		let id = package.id(tg).await?.clone();
+
+		// Validate the package's metadata.
+		let metadata = package.metadata(tg).await?;
+		let Some(name) = metadata.name else {
+			return_error!("The package's metadata must have a name.");
+		};
+		let Some(version) = metadata.version else {
+			return_error!("The package's metadata must have a version.");
+		};
+		version.parse::<semver::Version>().wrap_err_with(|| {
+			format!(r#"The package's version "{version}" is not a valid semver version."#)
+		})?;
+
+		// Verify that the version has not been published.
+		let versions = tg
+			.try_get_package_versions(&tg::Dependency::with_name(name.clone()))
+			.await?
+			.unwrap_or_default();
+		if versions.contains(&version) {
+			return_error!(r#"Version "{version}" of "{name}" has already been published."#);
+		}
+
+		// Validate the package's dependencies.
+		for dependency in package.dependencies(tg).await? {
+			if let Some(path) = dependency.path.as_ref() {
+				// Verify that the path dependency is inside the package.
+				if is_outside_package(path) {
+					return_error!(r#"The path dependency "{dependency}" is outside the package."#);
+				}
+			} else {
+				// Verify that the dependency resolves from the registry.
+				let resolved = tg.try_get_package(&dependency).await?.is_some();
+				if !resolved {
+					return_error!(
+						r#"The dependency "{dependency}" does not resolve from the registry."#
+					);
+				}
+			}
+		}
+
+		// Check the package for diagnostics.
+		let server = tangram_language::Server::new(tg, tokio::runtime::Handle::current());
+		let diagnostics = server
+			.check(vec![tangram_language::Module::Normal(
+				tangram_language::module::Normal {
+					package: id.clone(),
+					lock: lock.id(tg).await?.clone(),
+					path: tg::package::ROOT_MODULE_FILE_NAME.parse().unwrap(),
+				},
+			)])
+			.await?;
+		for diagnostic in &diagnostics {
+			let tangram_language::Diagnostic {
+				location, message, ..
+			} = diagnostic;
+			if let Some(location) = location {
+				eprintln!("{location}");
+			}
+			eprintln!("{message}");
+			eprintln!();
+		}
+		if !diagnostics.is_empty() {
+			return_error!("Type checking failed.");
+		}
+
+		// Get the signing key.
+		let signing_key = self.signing_key().await?;
+
+		// If this is a dry run, then print what would be published.
+		if args.dry_run {
+			let (size, count) = object_size_and_count(tg, &id.clone().into()).await?;
+			println!("{name}@{version} {id}");
+			println!("{count} objects, {size} bytes");
+			if let Some(signing_key) = signing_key.as_ref() {
+				println!("signed by {}", signing_key.public_key());
+			} else {
+				println!("unsigned");
+			}
+			eprintln!("The package was not published because this was a dry run.");
+			return Ok(());
+		}
 
 		// Publish the package.
-		tg.publish_package(user.as_ref(), id)
+		let user = self.user().await?;
+		tg.publish_package(user.as_ref(), &id)
 			.await
 			.wrap_err("Failed to publish the package.")?;
 
+		// Sign the package.
+		if let Some(signing_key) = signing_key {
+			let signature = signing_key.sign(&name, &version, &id);
+			let dependency = tg::Dependency::with_name_and_version(name, version);
+			tg.add_package_signature(user.as_ref(), &dependency, &signature)
+				.await
+				.wrap_err_with(|| {
+					format!(
+						r#"The package "{dependency}" was published, but signing it failed, so it is unsigned."#
+					)
+				})?;
+		} else {
+			eprintln!("The package was not signed. Run `tg key generate` to create a signing key.");
+		}
+
 		Ok(())
 	}
 }
+
+/// Determine if a path dependency is outside the package, which is the case if it is absolute or if it has a parent component after it is normalized.
+fn is_outside_package(path: &tg::Path) -> bool {
+	path.is_absolute()
+		|| path
+			.clone()
+			.normalize()
+			.components()
+			.iter()
+			.any(|component| *component == tg::path::Component::Parent)
+}
+
+/// Get the total size in bytes and the number of the objects reachable from an object.
+async fn object_size_and_count(tg: &dyn tg::Handle, id: &tg::object::Id) -> Result<(u64, u64)> {
+	let mut size = 0;
+	let mut count = 0;
+	let mut visited: BTreeSet<tg::Id> = BTreeSet::new();
+	let mut queue = VecDeque::from([id.clone()]);
+	while let Some(id) = queue.pop_front() {
+		if !visited.insert(id.clone().into()) {
+			continue;
+		}
+		let bytes = tg
+			.try_get_object(&id)
+			.await?
+			.wrap_err_with(|| format!(r#"Failed to get the object "{id}"."#))?;
+		size += bytes.len() as u64;
+		count += 1;
+		let data = tg::object::Data::deserialize(id.kind(), &bytes)?;
+		queue.extend(data.children());
+	}
+	Ok((size, count))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::is_outside_package;
+
+	#[test]
+	fn path_dependencies_outside_the_package() {
+		for path in ["/x", "../x", "./../x", "sub/../../x", "./sub/../.."] {
+			assert!(is_outside_package(&path.parse().unwrap()), "{path}");
+		}
+		for path in ["./x", "x", "sub/../x", "./sub/./x"] {
+			assert!(!is_outside_package(&path.parse().unwrap()), "{path}");
+		}
+	}
+}
@@ -0,0 +1,87 @@
+use crate::Cli;
+use std::io::{BufRead, Write};
+use tangram_client as tg;
+use tangram_error::{Result, WrapErr};
+use tangram_runtime::js::{Evaluation, Repl};
+
+/// Start a REPL that evaluates JavaScript with the tg global.
+///
+/// Code may use top-level await, and its declarations are available to later code. The last value is available as `_` and the last error as `_error`. Import a package with `await import("./path/to/package")` or `await import("name@version")`. Enter `.exit` or press Ctrl-D to exit.
+#[derive(Debug, clap::Args)]
+#[command(verbatim_doc_comment)]
+pub struct Args {}
+
+impl Cli {
+	pub async fn command_repl(&self, _args: Args) -> Result<()> {
+		let tg = self.handle().await?;
+
+		// Run the REPL on its own thread because its isolate is not `Send`. Futures that must run on the main runtime are spawned on its handle.
+		let main_runtime_handle = tokio::runtime::Handle::current();
+		let (sender, receiver) = tokio::sync::oneshot::channel();
+		std::thread::spawn(move || {
+			let result = tokio::runtime::Builder::new_current_thread()
+				.enable_all()
+				.build()
+				.wrap_err("Failed to create the REPL's runtime.")
+				.and_then(|runtime| runtime.block_on(repl(tg.as_ref(), main_runtime_handle)));
+			sender.send(result).ok();
+		});
+		receiver.await.wrap_err("The REPL panicked.")??;
+
+		Ok(())
+	}
+}
+
+/// Read code from stdin, evaluate it, and print the result until stdin is closed.
+async fn repl(tg: &dyn tg::Handle, main_runtime_handle: tokio::runtime::Handle) -> Result<()> {
+	// Create the REPL.
+	let mut repl = Repl::new(tg, main_runtime_handle)?;
+
+	let mut code = String::new();
+	loop {
+		// Print the prompt, which indicates whether the code so far is incomplete.
+		let prompt = if code.is_empty() { "> " } else { "... " };
+		let mut stdout = std::io::stdout();
+		stdout
+			.write_all(prompt.as_bytes())
+			.and_then(|()| stdout.flush())
+			.wrap_err("Failed to write the prompt.")?;
+
+		// Read a line. If stdin is closed, then exit.
+		let mut line = String::new();
+		let read = std::io::stdin()
+			.lock()
+			.read_line(&mut line)
+			.wrap_err("Failed to read from stdin.")?;
+		if read == 0 {
+			println!();
+			break;
+		}
+		if code.is_empty() && line.trim() == ".exit" {
+			break;
+		}
+
+		// Add the line to the code, skipping it if the code is empty.
+		code.push_str(&line);
+		if code.trim().is_empty() {
+			code.clear();
+			continue;
+		}
+
+		// Evaluate the code and print the result. If the code is incomplete, then read another line.
+		match repl.evaluate(&code).await {
+			Ok(Evaluation::Value(value)) => {
+				println!("{value}");
+			},
+			Ok(Evaluation::Incomplete) => {
+				continue;
+			},
+			Err(error) => {
+				eprintln!("{}", error.trace());
+			},
+		}
+		code.clear();
+	}
+
+	Ok(())
+}
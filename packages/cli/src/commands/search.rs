@@ -1,11 +1,23 @@
 use crate::Cli;
-use tangram_error::Result;
+use tangram_error::{Result, WrapErr};
 
 /// Search for packages.
+///
+/// Results are ranked by how closely the package's name matches the query, followed by matches in its keywords and description.
 #[derive(Debug, clap::Args)]
 #[command(verbatim_doc_comment)]
 pub struct Args {
 	pub query: String,
+
+	/// The output format.
+	#[arg(long, value_enum, default_value = "text")]
+	pub format: Format,
+}
+
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+pub enum Format {
+	Text,
+	Json,
 }
 
 impl Cli {
@@ -16,12 +28,49 @@ impl Cli {
 		// Perform the search.
 		let packages = tg.search_packages(&args.query).await?;
 
-		// Print the package names.
-		if packages.is_empty() {
-			println!("No packages matched your query.");
-		}
-		for package in packages {
-			println!("{package}");
+		// Print the results.
+		match args.format {
+			Format::Text => {
+				if packages.is_empty() {
+					println!("No packages matched your query.");
+					return Ok(());
+				}
+				let rows = packages
+					.iter()
+					.map(|package| {
+						[
+							package.name.clone(),
+							package.version.clone().unwrap_or_else(|| "-".to_owned()),
+							package.keywords.join(", "),
+							package.description.clone().unwrap_or_default(),
+						]
+					})
+					.collect::<Vec<_>>();
+				let header = ["Name", "Version", "Keywords", "Description"].map(ToOwned::to_owned);
+				let widths: Vec<usize> = (0..header.len())
+					.map(|column| {
+						std::iter::once(&header)
+							.chain(&rows)
+							.map(|row| row[column].chars().count())
+							.max()
+							.unwrap()
+					})
+					.collect();
+				for row in std::iter::once(&header).chain(&rows) {
+					let line = row
+						.iter()
+						.zip(&widths)
+						.map(|(cell, width)| format!("{cell:width$}"))
+						.collect::<Vec<_>>()
+						.join("  ");
+					println!("{}", line.trim_end());
+				}
+			},
+			Format::Json => {
+				let output = serde_json::to_string_pretty(&packages)
+					.wrap_err("Failed to serialize the search results.")?;
+				println!("{output}");
+			},
 		}
 
 		Ok(())
@@ -0,0 +1,65 @@
+use crate::Cli;
+use tangram_error::{return_error, Result, WrapErr};
+use tokio::io::AsyncReadExt;
+
+/// Manage the secrets that are provided to builds.
+#[derive(Debug, clap::Args)]
+#[command(verbatim_doc_comment)]
+pub struct Args {
+	#[command(subcommand)]
+	pub command: Command,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum Command {
+	/// Set a secret. If no value is provided, the value is read from stdin.
+	Set {
+		/// The name of the secret.
+		name: String,
+
+		/// The value of the secret.
+		value: Option<String>,
+	},
+
+	/// Remove a secret.
+	Remove {
+		/// The name of the secret.
+		name: String,
+	},
+
+	/// List the names of the secrets.
+	List,
+}
+
+impl Cli {
+	pub async fn command_secret(&self, args: Args) -> Result<()> {
+		let tg = self.handle().await?;
+		let tg = tg.as_ref();
+		match args.command {
+			Command::Set { name, value } => {
+				let value = if let Some(value) = value {
+					value
+				} else {
+					let mut value = String::new();
+					tokio::io::stdin()
+						.read_to_string(&mut value)
+						.await
+						.wrap_err("Failed to read the value from stdin.")?;
+					value.trim_end_matches(['\r', '\n']).to_owned()
+				};
+				tg.set_secret(&name, &value).await?;
+			},
+			Command::Remove { name } => {
+				if !tg.remove_secret(&name).await? {
+					return_error!(r#"The secret "{name}" was not found."#);
+				}
+			},
+			Command::List => {
+				for name in tg.list_secrets().await? {
+					println!("{name}");
+				}
+			},
+		}
+		Ok(())
+	}
+}
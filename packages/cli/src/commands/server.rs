@@ -1,5 +1,5 @@
 use crate::{Cli, API_URL};
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Duration};
 use tangram_client as tg;
 use tangram_error::{Result, WrapErr};
 use tg::client::Addr;
@@ -50,6 +50,14 @@ pub struct RunArgs {
 	#[arg(long)]
 	pub remote: Option<Url>,
 
+	/// Allow dependencies from the remote that are not signed by a trusted key.
+	#[arg(long, default_value = "false")]
+	pub allow_unsigned: bool,
+
+	/// Serve and accept published packages from this server's database.
+	#[arg(long, default_value = "false")]
+	pub registry: bool,
+
 	/// The Builder settings.
 	#[command(flatten)]
 	pub builder: Option<BuilderArgs>,
@@ -110,6 +118,28 @@ impl Cli {
 		let user = self.user().await?;
 
 		// Create the build options.
+		let js = config
+			.as_ref()
+			.and_then(|config| config.build.as_ref())
+			.and_then(|build| build.js.clone())
+			.map(|js| {
+				let execution_time_limit = js
+					.execution_time_limit
+					.map(Duration::try_from_secs_f64)
+					.transpose()
+					.wrap_err("Invalid execution time limit.")?;
+				let time_limit = js
+					.time_limit
+					.map(Duration::try_from_secs_f64)
+					.transpose()
+					.wrap_err("Invalid time limit.")?;
+				Ok::<_, tangram_error::Error>(tangram_server::JsBuildOptions {
+					heap_limit: js.heap_limit,
+					execution_time_limit,
+					time_limit,
+				})
+			})
+			.transpose()?;
 		let remote = config
 			.as_ref()
 			.and_then(|config| config.build.as_ref())
@@ -118,7 +148,27 @@ impl Cli {
 				enable: build.enable,
 				hosts: build.hosts,
 			});
-		let build = Some(tangram_server::BuildOptions { remote });
+		let build = Some(tangram_server::BuildOptions { js, remote });
+
+		// Create the package options.
+		let allow_unsigned = args.allow_unsigned
+			|| config
+				.as_ref()
+				.and_then(|config| config.package.as_ref())
+				.is_some_and(|package| package.allow_unsigned);
+		let package = Some(tangram_server::PackageOptions { allow_unsigned });
+
+		// Create the registry options.
+		let registry_config = config.as_ref().and_then(|config| config.registry.as_ref());
+		let registry = if args.registry || registry_config.is_some_and(|registry| registry.enable) {
+			let upstream = registry_config.is_some_and(|registry| registry.upstream);
+			let users = registry_config
+				.map(|registry| registry.users.clone())
+				.unwrap_or_default();
+			Some(tangram_server::RegistryOptions { upstream, users })
+		} else {
+			None
+		};
 
 		// Create the remote options.
 		let url = args
@@ -144,7 +194,9 @@ impl Cli {
 		let options = tangram_server::Options {
 			addr,
 			build,
+			package,
 			path,
+			registry,
 			remote,
 			version,
 		};
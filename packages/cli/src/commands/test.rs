@@ -29,9 +29,12 @@ impl Cli {
 		let args = super::build::Args {
 			no_tui: false,
 			detach: args.detach,
+			heap_snapshot: None,
+			inspect: None,
 			output: None,
 			package: args.package,
 			package_args: args.package_args,
+			profile: None,
 			retry: args.retry,
 			target: "test".to_owned(),
 		};
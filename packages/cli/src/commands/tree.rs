@@ -1,6 +1,10 @@
 use crate::Cli;
+use std::{
+	collections::{BTreeMap, BTreeSet, VecDeque},
+	fmt::Write,
+};
 use tangram_client as tg;
-use tangram_error::{return_error, Result};
+use tangram_error::{return_error, Result, WrapErr};
 
 /// Print the dependency tree of a package.
 #[derive(Debug, clap::Args)]
@@ -8,11 +12,268 @@ use tangram_error::{return_error, Result};
 pub struct Args {
 	#[arg(default_value = ".")]
 	pub package: tg::Dependency,
+
+	/// The maximum depth of the tree to print.
+	#[arg(long)]
+	pub depth: Option<usize>,
+
+	/// Print the packages that depend on the package with this name.
+	#[arg(long)]
+	pub invert: Option<String>,
+
+	/// The output format.
+	#[arg(long, value_enum, default_value = "text")]
+	pub format: Format,
+}
+
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+pub enum Format {
+	Text,
+	Json,
+}
+
+/// A package and its lock. The same package may appear in the tree with different locks.
+type Key = (tg::directory::Id, tg::lock::Id);
+
+/// The dependency graph of a package.
+#[derive(Default)]
+struct Graph {
+	metadata: BTreeMap<tg::directory::Id, tg::package::Metadata>,
+	dependencies: BTreeMap<Key, Vec<(tg::Dependency, Key)>>,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct Node {
+	/// The dependency that refers to the package.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	dependency: Option<String>,
+
+	name: Option<String>,
+
+	version: Option<String>,
+
+	id: tg::directory::Id,
+
+	/// Whether the dependency is a path dependency.
+	#[serde(skip_serializing_if = "std::ops::Not::not")]
+	path: bool,
+
+	/// Whether the package's dependencies were omitted because they were printed earlier in the tree.
+	#[serde(skip_serializing_if = "std::ops::Not::not")]
+	deduplicated: bool,
+
+	dependencies: Vec<Node>,
 }
 
 impl Cli {
-	#[allow(clippy::unused_async)]
-	pub async fn command_tree(&self, _args: Args) -> Result<()> {
-		return_error!("This command is not yet implemented.");
+	pub async fn command_tree(&self, args: Args) -> Result<()> {
+		let tg = self.handle().await?;
+		let tg = tg.as_ref();
+
+		// Get the package and its lock.
+		let (package, lock) = tg::package::get_with_lock(tg, &args.package).await?;
+		let root = (package.id(tg).await?.clone(), lock.id(tg).await?.clone());
+
+		// Get the dependency graph.
+		let graph = Graph::new(tg, root.clone()).await?;
+
+		// Create the trees.
+		let trees = if let Some(name) = args.invert.as_ref() {
+			// Reverse the graph's edges.
+			let mut dependants: BTreeMap<Key, Vec<(tg::Dependency, Key)>> = BTreeMap::new();
+			for (dependant, dependencies) in &graph.dependencies {
+				for (dependency, key) in dependencies {
+					dependants
+						.entry(key.clone())
+						.or_default()
+						.push((dependency.clone(), dependant.clone()));
+				}
+			}
+
+			// Create a tree for each package with the name.
+			let keys = graph
+				.dependencies
+				.keys()
+				.filter(|(package, _)| {
+					graph
+						.metadata
+						.get(package)
+						.and_then(|metadata| metadata.name.as_ref())
+						== Some(name)
+				})
+				.collect::<Vec<_>>();
+			if keys.is_empty() {
+				return_error!(r#"The dependency tree does not contain "{name}"."#);
+			}
+			let mut visited = BTreeSet::new();
+			keys.into_iter()
+				.map(|key| {
+					let dependency = incoming(key, &dependants);
+					graph.tree(key, dependency, &dependants, true, args.depth, &mut visited)
+				})
+				.collect()
+		} else {
+			let mut visited = BTreeSet::new();
+			vec![graph.tree(
+				&root,
+				None,
+				&graph.dependencies,
+				false,
+				args.depth,
+				&mut visited,
+			)]
+		};
+
+		// Print the trees.
+		match args.format {
+			Format::Text => {
+				let root = args.package.to_string();
+				for tree in &trees {
+					let mut output = tree.label(&root);
+					tree.render("", &mut output);
+					println!("{output}");
+				}
+			},
+			Format::Json => {
+				let output = if args.invert.is_some() {
+					serde_json::to_string_pretty(&trees)
+				} else {
+					serde_json::to_string_pretty(&trees[0])
+				};
+				let output = output.wrap_err("Failed to serialize the tree.")?;
+				println!("{output}");
+			},
+		}
+
+		Ok(())
+	}
+}
+
+impl Graph {
+	async fn new(tg: &dyn tg::Handle, root: Key) -> Result<Self> {
+		let mut graph = Self::default();
+		let mut queue = VecDeque::from([root]);
+		while let Some(key) = queue.pop_front() {
+			if graph.dependencies.contains_key(&key) {
+				continue;
+			}
+
+			// Get the package's metadata. Path dependencies may not have any.
+			if !graph.metadata.contains_key(&key.0) {
+				let dependency = tg::Dependency::with_id(key.0.clone());
+				let metadata = tg
+					.try_get_package_metadata(&dependency)
+					.await
+					.ok()
+					.flatten()
+					.unwrap_or_default();
+				graph.metadata.insert(key.0.clone(), metadata);
+			}
+
+			// Get the package's dependencies from its lock.
+			let lock = tg::Lock::with_id(key.1.clone());
+			let mut dependencies = Vec::new();
+			for (dependency, entry) in lock.dependencies(tg).await? {
+				let package = entry.package.id(tg).await?.clone();
+				let lock = entry.lock.id(tg).await?.clone();
+				queue.push_back((package.clone(), lock.clone()));
+				dependencies.push((dependency.clone(), (package, lock)));
+			}
+			graph.dependencies.insert(key, dependencies);
+		}
+		Ok(graph)
+	}
+
+	/// Create the tree rooted at a package by following `edges`, expanding each package only once and descending at most `depth` levels. If the edges are inverted, then they point from each package to its dependants, and each node's dependency is the one its own dependants refer to it with.
+	fn tree(
+		&self,
+		key: &Key,
+		dependency: Option<&tg::Dependency>,
+		edges: &BTreeMap<Key, Vec<(tg::Dependency, Key)>>,
+		inverted: bool,
+		depth: Option<usize>,
+		visited: &mut BTreeSet<Key>,
+	) -> Node {
+		let metadata = self.metadata.get(&key.0);
+		let mut node = Node {
+			dependency: dependency.map(ToString::to_string),
+			name: metadata.and_then(|metadata| metadata.name.clone()),
+			version: metadata.and_then(|metadata| metadata.version.clone()),
+			id: key.0.clone(),
+			path: dependency.is_some_and(|dependency| dependency.path.is_some()),
+			deduplicated: false,
+			dependencies: Vec::new(),
+		};
+		let children = edges.get(key).map(Vec::as_slice).unwrap_or_default();
+		if children.is_empty() || depth == Some(0) {
+			return node;
+		}
+		if !visited.insert(key.clone()) {
+			node.deduplicated = true;
+			return node;
+		}
+		node.dependencies = children
+			.iter()
+			.map(|(dependency, child)| {
+				let dependency = if inverted {
+					incoming(child, edges)
+				} else {
+					Some(dependency)
+				};
+				self.tree(
+					child,
+					dependency,
+					edges,
+					inverted,
+					depth.map(|depth| depth - 1),
+					visited,
+				)
+			})
+			.collect();
+		node
+	}
+}
+
+/// Get the dependency that refers to a package from the inverted edges, if all of the package's dependants refer to it the same way.
+fn incoming<'a>(
+	key: &Key,
+	dependants: &'a BTreeMap<Key, Vec<(tg::Dependency, Key)>>,
+) -> Option<&'a tg::Dependency> {
+	let mut dependencies = dependants
+		.get(key)?
+		.iter()
+		.map(|(dependency, _)| dependency);
+	let first = dependencies.next()?;
+	dependencies
+		.all(|dependency| dependency == first)
+		.then_some(first)
+}
+
+impl Node {
+	fn label(&self, fallback: &str) -> String {
+		let mut label = match (&self.name, &self.version, &self.dependency) {
+			(Some(name), Some(version), _) => format!("{name}@{version}"),
+			(Some(name), None, _) => name.clone(),
+			(None, _, Some(dependency)) => dependency.clone(),
+			(None, _, None) => fallback.to_owned(),
+		};
+		write!(label, " {}", self.id).unwrap();
+		if self.path {
+			label.push_str(" (path)");
+		}
+		if self.deduplicated {
+			label.push_str(" (*)");
+		}
+		label
+	}
+
+	fn render(&self, prefix: &str, output: &mut String) {
+		for (index, child) in self.dependencies.iter().enumerate() {
+			let last = index == self.dependencies.len() - 1;
+			let branch = if last { "└─ " } else { "├─ " };
+			write!(output, "\n{prefix}{branch}{}", child.label("")).unwrap();
+			let prefix = format!("{prefix}{}", if last { "   " } else { "│  " });
+			child.render(&prefix, output);
+		}
 	}
 }
@@ -1,18 +1,67 @@
 use crate::Cli;
 use std::path::PathBuf;
-use tangram_error::{return_error, Result};
+use tangram_error::Result;
+use tangram_package::{Change, Warning};
 
 /// Update a package's dependencies.
+///
+/// By default, only the named dependencies are updated, and every other dependency keeps its locked version. Pass --all to update every dependency.
 #[derive(Debug, clap::Args)]
 #[command(verbatim_doc_comment)]
 pub struct Args {
+	/// The names of the dependencies to update.
+	pub names: Vec<String>,
+
+	/// Update every dependency.
+	#[arg(long, conflicts_with = "names")]
+	pub all: bool,
+
+	/// Print the changes without writing the lockfile.
+	#[arg(long)]
+	pub dry_run: bool,
+
 	#[arg(short, long, default_value = ".")]
 	pub path: PathBuf,
 }
 
 impl Cli {
-	#[allow(clippy::unused_async)]
-	pub async fn command_update(&self, _args: Args) -> Result<()> {
-		return_error!("This command is not yet implemented.");
+	pub async fn command_update(&self, args: Args) -> Result<()> {
+		let tg = self.handle().await?;
+		let tg = tg.as_ref();
+
+		// Update the lockfile.
+		let path = args.path.try_into()?;
+		let names = (!args.all).then_some(args.names.as_slice());
+		let update = tangram_package::update(tg, &path, names, args.dry_run).await?;
+
+		// Print the changes.
+		if update.changes.is_empty() {
+			eprintln!("The lockfile is up to date.");
+		}
+		for change in update.changes {
+			match change {
+				Change::Added { name, version } => println!("Adding {name}@{version}"),
+				Change::Removed { name, version } => println!("Removing {name}@{version}"),
+				Change::Updated { name, old, new } => println!("Updating {name}@{old} -> {new}"),
+			}
+		}
+
+		// Print the warnings.
+		for warning in update.warnings {
+			match warning {
+				Warning::Deprecated { name, message } => {
+					eprintln!(r#"Warning: "{name}" is deprecated: {message}"#);
+				},
+				Warning::Yanked { name, version } => {
+					eprintln!(r#"Warning: "{name}@{version}" has been yanked."#);
+				},
+			}
+		}
+
+		if args.dry_run {
+			eprintln!("The lockfile was not written because this was a dry run.");
+		}
+
+		Ok(())
 	}
 }
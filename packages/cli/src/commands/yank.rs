@@ -0,0 +1,39 @@
+use crate::Cli;
+use tangram_client as tg;
+use tangram_error::{return_error, Result, WrapErr};
+
+/// Yank a published version of a package.
+///
+/// New resolutions skip a yanked version, but lockfiles that already use it keep working.
+#[derive(Debug, clap::Args)]
+#[command(verbatim_doc_comment)]
+pub struct Args {
+	/// The package and version to yank, such as "name@1.2.3".
+	pub package: tg::Dependency,
+
+	/// Undo a previous yank.
+	#[arg(long)]
+	pub undo: bool,
+}
+
+impl Cli {
+	pub async fn command_yank(&self, args: Args) -> Result<()> {
+		let tg = self.handle().await?;
+		let tg = tg.as_ref();
+
+		// Get the user.
+		let user = self.user().await?;
+
+		// Verify that the dependency has a name and version.
+		if args.package.name.is_none() || args.package.version.is_none() {
+			return_error!("Expected a package name and version, such as \"name@1.2.3\".");
+		}
+
+		// Yank the package.
+		tg.yank_package(user.as_ref(), &args.package, !args.undo)
+			.await
+			.wrap_err("Failed to yank the package.")?;
+
+		Ok(())
+	}
+}
@@ -28,6 +28,12 @@ struct Config {
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	build: Option<BuildConfig>,
 
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	package: Option<PackageConfig>,
+
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	registry: Option<RegistryConfig>,
+
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	remote: Option<RemoteConfig>,
 }
@@ -40,11 +46,31 @@ struct AutoenvConfig {
 
 #[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
 struct BuildConfig {
+	/// Configure JS builds.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	js: Option<JsBuildConfig>,
+
 	/// Configure remote builds.
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	remote: Option<RemoteBuildConfig>,
 }
 
+#[allow(clippy::struct_field_names)]
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+struct JsBuildConfig {
+	/// The maximum size of a JS build's heap in bytes.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	heap_limit: Option<usize>,
+
+	/// The maximum time in seconds that a JS build may spend executing JavaScript.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	execution_time_limit: Option<f64>,
+
+	/// The maximum wall-clock time in seconds of a JS build.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	time_limit: Option<f64>,
+}
+
 #[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
 struct RemoteBuildConfig {
 	/// Enable remote builds.
@@ -56,6 +82,28 @@ struct RemoteBuildConfig {
 	hosts: Option<Vec<tg::System>>,
 }
 
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+struct PackageConfig {
+	/// Allow dependencies from the remote that are not signed by a trusted key.
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	allow_unsigned: bool,
+}
+
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+struct RegistryConfig {
+	/// Enable the registry.
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	enable: bool,
+
+	/// Fall through to the remote for packages that are not in the registry.
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	upstream: bool,
+
+	/// Users that the registry authenticates by their tokens. A user logs in to the registry by saving the same user to their user file.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	users: Vec<tg::User>,
+}
+
 #[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
 struct RemoteConfig {
 	/// The remote URL.
@@ -0,0 +1,914 @@
+use crate::{
+	blob::{ArchiveFormat, ArchiveOptions, CompressionFormat, ExtractOptions},
+	file, return_error, Artifact, Blob, Handle, Result, WrapErr,
+};
+use futures::{stream::FuturesOrdered, TryStreamExt};
+use num::ToPrimitive;
+use std::{
+	collections::HashMap,
+	io::{BufRead, Read, Seek},
+	os::unix::fs::PermissionsExt,
+	path::{Component, Path, PathBuf},
+};
+use tokio_util::io::SyncIoBridge;
+
+/// The name of the PAX extended header record that stores a file's attributes. This matches the record GNU tar uses for xattrs.
+const TAR_ATTRIBUTES_PAX_KEY: &str = "SCHILY.xattr.user.tangram";
+
+/// An entry in an archive.
+pub(crate) enum Entry {
+	Directory,
+	File {
+		contents: Blob,
+		size: u64,
+		executable: bool,
+		attributes: Option<Vec<u8>>,
+	},
+	Symlink {
+		target: String,
+	},
+}
+
+/// Collect the entries of an archive in sorted order.
+pub(crate) async fn entries(
+	tg: &dyn Handle,
+	artifact: &Artifact,
+	options: ArchiveOptions,
+) -> Result<Vec<(String, Entry)>> {
+	let mut entries = Vec::new();
+	let mut stack = match artifact {
+		Artifact::Directory(directory) => directory
+			.entries(tg)
+			.await?
+			.iter()
+			.rev()
+			.map(|(name, artifact)| (name.clone(), artifact.clone()))
+			.collect(),
+		_ => vec![(artifact.id(tg).await?.to_string(), artifact.clone())],
+	};
+	while let Some((path, artifact)) = stack.pop() {
+		let entry = match &artifact {
+			Artifact::Directory(directory) => {
+				stack.extend(
+					directory
+						.entries(tg)
+						.await?
+						.iter()
+						.rev()
+						.map(|(name, artifact)| (format!("{path}/{name}"), artifact.clone())),
+				);
+				Entry::Directory
+			},
+			Artifact::File(file) => {
+				let contents = file.contents(tg).await?.clone();
+				let size = contents.size(tg).await?;
+				let executable = file.executable(tg).await?;
+				let references = file.references(tg).await?;
+				let attributes = if options.references && !references.is_empty() {
+					let references = references
+						.iter()
+						.map(|artifact| artifact.id(tg))
+						.collect::<FuturesOrdered<_>>()
+						.try_collect()
+						.await?;
+					let attributes = file::Attributes { references };
+					let attributes = serde_json::to_vec(&attributes)
+						.wrap_err("Failed to serialize the attributes.")?;
+					Some(attributes)
+				} else {
+					None
+				};
+				Entry::File {
+					contents,
+					size,
+					executable,
+					attributes,
+				}
+			},
+			Artifact::Symlink(symlink) => {
+				let target = symlink.path(tg).await?.clone();
+				let target = if let Some(artifact) = symlink.artifact(tg).await? {
+					if !options.references {
+						return_error!(
+							r#"Cannot archive the symlink at "{path}" because it contains an artifact."#
+						);
+					}
+					let id = artifact.id(tg).await?;
+					if let Some(target) = target {
+						format!("/.tangram/artifacts/{id}/{target}")
+					} else {
+						format!("/.tangram/artifacts/{id}")
+					}
+				} else {
+					target.unwrap_or_default()
+				};
+				Entry::Symlink { target }
+			},
+		};
+		entries.push((path, entry));
+	}
+	Ok(entries)
+}
+
+/// Write entries to a tar archive.
+pub(crate) fn write_tar(
+	tg: &dyn Handle,
+	file: &mut std::fs::File,
+	entries: Vec<(String, Entry)>,
+) -> Result<()> {
+	let mut builder = tar::Builder::new(file);
+	for (path, entry) in entries {
+		let mut header = tar::Header::new_gnu();
+		header.set_mtime(0);
+		header.set_uid(0);
+		header.set_gid(0);
+		match entry {
+			Entry::Directory => {
+				header.set_entry_type(tar::EntryType::Directory);
+				header.set_mode(0o755);
+				header.set_size(0);
+				builder
+					.append_data(&mut header, format!("{path}/"), std::io::empty())
+					.wrap_err("Failed to write the directory.")?;
+			},
+			Entry::File {
+				contents,
+				size,
+				executable,
+				attributes,
+			} => {
+				// Write the attributes to a PAX extended header.
+				if let Some(attributes) = attributes {
+					let record = pax_record(TAR_ATTRIBUTES_PAX_KEY, &attributes);
+					let mut header = tar::Header::new_ustar();
+					header.set_entry_type(tar::EntryType::XHeader);
+					header.set_mtime(0);
+					header.set_mode(0o644);
+					header.set_size(record.len().to_u64().unwrap());
+					builder
+						.append_data(&mut header, "././@PaxHeader", record.as_slice())
+						.wrap_err("Failed to write the attributes.")?;
+				}
+				header.set_entry_type(tar::EntryType::Regular);
+				header.set_mode(if executable { 0o755 } else { 0o644 });
+				header.set_size(size);
+				let reader = tokio::runtime::Handle::current().block_on(contents.reader(tg))?;
+				let reader = SyncIoBridge::new(reader);
+				builder
+					.append_data(&mut header, &path, reader)
+					.wrap_err("Failed to write the file.")?;
+			},
+			Entry::Symlink { target } => {
+				header.set_entry_type(tar::EntryType::Symlink);
+				header.set_mode(0o777);
+				header.set_size(0);
+				builder
+					.append_link(&mut header, &path, &target)
+					.wrap_err("Failed to write the symlink.")?;
+			},
+		}
+	}
+	builder.finish().wrap_err("Failed to finish the archive.")?;
+	Ok(())
+}
+
+/// Write entries to a zip archive.
+pub(crate) fn write_zip(
+	tg: &dyn Handle,
+	file: &mut std::fs::File,
+	entries: Vec<(String, Entry)>,
+) -> Result<()> {
+	let mut writer = zip::ZipWriter::new(file);
+	let options = zip::write::FileOptions::default()
+		.compression_method(zip::CompressionMethod::Deflated)
+		.last_modified_time(zip::DateTime::default());
+	for (path, entry) in entries {
+		match entry {
+			Entry::Directory => {
+				writer
+					.add_directory(path, options.unix_permissions(0o755))
+					.wrap_err("Failed to write the directory.")?;
+			},
+			Entry::File {
+				contents,
+				size,
+				executable,
+				..
+			} => {
+				let options = options
+					.unix_permissions(if executable { 0o755 } else { 0o644 })
+					.large_file(size >= u64::from(u32::MAX));
+				writer
+					.start_file(path, options)
+					.wrap_err("Failed to write the file.")?;
+				let reader = tokio::runtime::Handle::current().block_on(contents.reader(tg))?;
+				let mut reader = SyncIoBridge::new(reader);
+				std::io::copy(&mut reader, &mut writer).wrap_err("Failed to write the file.")?;
+			},
+			Entry::Symlink { target } => {
+				writer
+					.add_symlink(path, target, options.unix_permissions(0o777))
+					.wrap_err("Failed to write the symlink.")?;
+			},
+		}
+	}
+	writer.finish().wrap_err("Failed to finish the archive.")?;
+	Ok(())
+}
+
+/// Format a PAX extended header record. The record's length prefix includes its own digits.
+fn pax_record(key: &str, value: &[u8]) -> Vec<u8> {
+	let rest = key.len() + value.len() + 3;
+	let mut length = rest + 1;
+	while rest + length.to_string().len() != length {
+		length = rest + length.to_string().len();
+	}
+	let mut record = format!("{length} {key}=").into_bytes();
+	record.extend_from_slice(value);
+	record.push(b'\n');
+	record
+}
+
+/// Detect the compression format of a blob from its first bytes.
+pub(crate) fn detect_compression_format(bytes: &[u8]) -> Option<CompressionFormat> {
+	if bytes.starts_with(b"BZh") {
+		Some(CompressionFormat::Bz2)
+	} else if bytes.starts_with(&[0x1f, 0x8b]) {
+		Some(CompressionFormat::Gz)
+	} else if bytes.starts_with(b"LZIP") {
+		Some(CompressionFormat::Lz)
+	} else if bytes.starts_with(&[0xfd, b'7', b'z', b'X', b'Z', 0x00]) {
+		Some(CompressionFormat::Xz)
+	} else if bytes.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+		Some(CompressionFormat::Zstd)
+	} else {
+		None
+	}
+}
+
+/// Detect the archive format of a decompressed blob from its first bytes.
+pub(crate) fn detect_archive_format(bytes: &[u8]) -> Option<ArchiveFormat> {
+	if bytes.starts_with(b"!<arch>\n") {
+		Some(ArchiveFormat::Ar)
+	} else if bytes.starts_with(b"070701")
+		|| bytes.starts_with(b"070702")
+		|| bytes.starts_with(b"070707")
+	{
+		Some(ArchiveFormat::Cpio)
+	} else if bytes.starts_with(b"PK\x03\x04") || bytes.starts_with(b"PK\x05\x06") {
+		Some(ArchiveFormat::Zip)
+	} else if bytes.get(257..262) == Some(b"ustar") || is_tar_header(bytes) {
+		Some(ArchiveFormat::Tar)
+	} else {
+		None
+	}
+}
+
+/// Determine if the bytes begin with a tar header with a valid checksum, which identifies tar archives that predate the ustar magic.
+fn is_tar_header(bytes: &[u8]) -> bool {
+	let Some(header) = bytes.get(..512) else {
+		return false;
+	};
+	let Some(expected) = std::str::from_utf8(&header[148..156])
+		.ok()
+		.map(|field| field.trim_matches(|c: char| c == ' ' || c == '\0'))
+		.and_then(|field| u64::from_str_radix(field, 8).ok())
+	else {
+		return false;
+	};
+	let actual = header
+		.iter()
+		.enumerate()
+		.map(|(i, byte)| {
+			if (148..156).contains(&i) {
+				u64::from(b' ')
+			} else {
+				u64::from(*byte)
+			}
+		})
+		.sum::<u64>();
+	actual == expected
+}
+
+/// The lzip header's size.
+pub(crate) const LZIP_HEADER_SIZE: usize = 6;
+
+/// Convert an lzip member header to the header of an LZMA stream with an end marker. Lzip always uses the properties `lc=3`, `lp=0`, and `pb=2`.
+pub(crate) fn lzma_header_for_lzip_header(header: [u8; LZIP_HEADER_SIZE]) -> Result<[u8; 13]> {
+	if &header[..4] != b"LZIP" {
+		return_error!("Invalid lzip header.");
+	}
+	if header[4] != 1 {
+		return_error!("Unsupported lzip version {}.", header[4]);
+	}
+	let exponent = u32::from(header[5] & 0x1f);
+	if !(12..=29).contains(&exponent) {
+		return_error!("Invalid lzip dictionary size.");
+	}
+	let dictionary_size = (1u32 << exponent) - (1u32 << exponent) / 16 * u32::from(header[5] >> 5);
+	let mut lzma_header = [0u8; 13];
+	lzma_header[0] = 0x5d;
+	lzma_header[1..5].copy_from_slice(&dictionary_size.to_le_bytes());
+	lzma_header[5..13].copy_from_slice(&u64::MAX.to_le_bytes());
+	Ok(lzma_header)
+}
+
+/// Extract an archive that does not need to be seekable.
+pub(crate) fn extract(
+	format: ArchiveFormat,
+	reader: impl Read,
+	path: &Path,
+	options: ExtractOptions,
+) -> Result<()> {
+	let destination = Destination::new(path, options)?;
+	match format {
+		ArchiveFormat::Ar => extract_ar(reader, &destination),
+		ArchiveFormat::Cpio => extract_cpio(reader, &destination),
+		ArchiveFormat::Tar => extract_tar(reader, &destination),
+		ArchiveFormat::Zip => return_error!("Zip archives must be seekable."),
+	}
+}
+
+/// Extract a tar archive, restoring files' attributes from PAX extended headers.
+fn extract_tar(reader: impl Read, destination: &Destination) -> Result<()> {
+	let mut archive = tar::Archive::new(reader);
+	let entries = archive
+		.entries()
+		.wrap_err("Failed to read the archive entries.")?;
+	for entry in entries {
+		let mut entry = entry.wrap_err("Failed to read the archive entry.")?;
+
+		// Get the attributes.
+		let mut attributes = None;
+		if let Some(extensions) = entry
+			.pax_extensions()
+			.wrap_err("Failed to read the PAX extensions.")?
+		{
+			for extension in extensions {
+				let extension = extension.wrap_err("Failed to read the PAX extension.")?;
+				if extension.key() == Ok(TAR_ATTRIBUTES_PAX_KEY) {
+					attributes = Some(extension.value_bytes().to_owned());
+				}
+			}
+		}
+
+		// Get the path.
+		let entry_path = entry
+			.path()
+			.wrap_err("Failed to read the entry's path.")?
+			.into_owned();
+		let Some(path) = destination.path(&entry_path)? else {
+			continue;
+		};
+
+		// Extract the entry.
+		let entry_type = entry.header().entry_type();
+		match entry_type {
+			tar::EntryType::Directory => {
+				Destination::create_directory(&path)?;
+			},
+			tar::EntryType::Regular | tar::EntryType::Continuous => {
+				let mode = entry
+					.header()
+					.mode()
+					.wrap_err("Failed to read the entry's mode.")?;
+				Destination::create_file(&path, &mut entry, mode & 0o111 != 0)?;
+				if let Some(attributes) = attributes {
+					xattr::set(&path, file::TANGRAM_FILE_XATTR_NAME, &attributes)
+						.wrap_err("Failed to set the attributes.")?;
+				}
+			},
+			tar::EntryType::Symlink | tar::EntryType::Link => {
+				let target = entry
+					.link_name()
+					.wrap_err("Failed to read the entry's link name.")?
+					.wrap_err("Expected the entry to have a link name.")?
+					.into_owned();
+				if entry_type == tar::EntryType::Symlink {
+					Destination::create_symlink(&path, &target)?;
+				} else {
+					let target = destination
+						.path(&target)?
+						.wrap_err("Invalid hard link target.")?;
+					Destination::create_hard_link(&path, &target)?;
+				}
+			},
+			// Skip devices, fifos, and other special files.
+			_ => (),
+		}
+	}
+	Ok(())
+}
+
+/// Extract a zip archive.
+pub(crate) fn extract_zip(
+	reader: impl Read + Seek,
+	path: &Path,
+	options: ExtractOptions,
+) -> Result<()> {
+	let destination = Destination::new(path, options)?;
+	let mut archive = zip::ZipArchive::new(reader).wrap_err("Failed to read the archive.")?;
+	for i in 0..archive.len() {
+		let mut file = archive
+			.by_index(i)
+			.wrap_err("Failed to read the archive entry.")?;
+		let entry_path = PathBuf::from(file.name());
+		let Some(path) = destination.path(&entry_path)? else {
+			continue;
+		};
+		let mode = file.unix_mode().unwrap_or(0o644);
+		if file.is_dir() {
+			Destination::create_directory(&path)?;
+		} else if mode & 0o170_000 == 0o120_000 {
+			let mut target = String::new();
+			file.read_to_string(&mut target)
+				.wrap_err("Failed to read the symlink target.")?;
+			Destination::create_symlink(&path, Path::new(&target))?;
+		} else {
+			Destination::create_file(&path, &mut file, mode & 0o111 != 0)?;
+		}
+	}
+	Ok(())
+}
+
+/// Extract an ar archive, including Debian packages. Both the GNU and BSD variants of long file names are supported.
+fn extract_ar(reader: impl Read, destination: &Destination) -> Result<()> {
+	let mut reader = std::io::BufReader::new(reader);
+
+	// Read the magic.
+	let mut magic = [0u8; 8];
+	reader
+		.read_exact(&mut magic)
+		.wrap_err("Failed to read the archive's magic.")?;
+	if &magic != b"!<arch>\n" {
+		return_error!("Invalid ar archive.");
+	}
+
+	let mut names = Vec::new();
+	loop {
+		// Read the header.
+		if reader
+			.fill_buf()
+			.wrap_err("Failed to read the archive.")?
+			.is_empty()
+		{
+			break;
+		}
+		let mut header = [0u8; 60];
+		reader
+			.read_exact(&mut header)
+			.wrap_err("Failed to read the entry's header.")?;
+		if &header[58..60] != b"`\n" {
+			return_error!("Invalid ar entry header.");
+		}
+		let name = std::str::from_utf8(&header[0..16])
+			.wrap_err("Invalid ar entry name.")?
+			.trim_end();
+		let mode = parse_number(&header[40..48], 8).wrap_err("Invalid ar entry mode.")?;
+		let size = parse_number(&header[48..58], 10).wrap_err("Invalid ar entry size.")?;
+		let mut data = (&mut reader).take(size);
+
+		// Get the name.
+		let name = if name == "/" || name == "/SYM64/" || name.starts_with("__.SYMDEF") {
+			// Skip the symbol table.
+			None
+		} else if name == "//" {
+			// Read the GNU long names table.
+			names.clear();
+			data.read_to_end(&mut names)
+				.wrap_err("Failed to read the long names table.")?;
+			None
+		} else if let Some(length) = name.strip_prefix("#1/") {
+			// Read a BSD long name, which precedes the data.
+			let length = length.parse::<u64>().wrap_err("Invalid ar entry name.")?;
+			let mut name = Vec::new();
+			(&mut data)
+				.take(length)
+				.read_to_end(&mut name)
+				.wrap_err("Failed to read the entry's name.")?;
+			let name = String::from_utf8(name).wrap_err("Invalid ar entry name.")?;
+			Some(name.trim_end_matches('\0').to_owned())
+		} else if let Some(offset) = name.strip_prefix('/') {
+			// Look up a GNU long name.
+			let offset = offset.parse::<usize>().wrap_err("Invalid ar entry name.")?;
+			let name = names.get(offset..).wrap_err("Invalid ar entry name.")?;
+			let end = name
+				.windows(2)
+				.position(|window| window == b"/\n")
+				.wrap_err("Invalid ar entry name.")?;
+			let name = std::str::from_utf8(&name[..end]).wrap_err("Invalid ar entry name.")?;
+			Some(name.to_owned())
+		} else {
+			Some(name.strip_suffix('/').unwrap_or(name).to_owned())
+		};
+
+		// Extract the entry.
+		if let Some(name) = name {
+			if let Some(path) = destination.path(Path::new(&name))? {
+				Destination::create_file(&path, &mut data, mode & 0o111 != 0)?;
+			}
+		}
+
+		// Skip the rest of the data and the padding.
+		std::io::copy(&mut data, &mut std::io::sink()).wrap_err("Failed to read the archive.")?;
+		if size % 2 == 1 {
+			reader
+				.read(&mut [0u8; 1])
+				.wrap_err("Failed to read the archive.")?;
+		}
+	}
+
+	Ok(())
+}
+
+/// Extract a cpio archive in the "newc" format. Concatenated archives, as in initramfs images, are extracted in order.
+fn extract_cpio(reader: impl Read, destination: &Destination) -> Result<()> {
+	let mut reader = std::io::BufReader::new(reader);
+	let mut links: HashMap<(u64, u64, u64), Vec<PathBuf>> = HashMap::new();
+	loop {
+		// Skip the zero padding between concatenated archives.
+		let buffer = reader.fill_buf().wrap_err("Failed to read the archive.")?;
+		if buffer.is_empty() {
+			break;
+		}
+		let zeros = buffer.iter().take_while(|byte| **byte == 0).count();
+		if zeros > 0 {
+			reader.consume(zeros);
+			continue;
+		}
+
+		// Read the header.
+		let mut header = [0u8; 110];
+		reader
+			.read_exact(&mut header)
+			.wrap_err("Failed to read the entry's header.")?;
+		match &header[0..6] {
+			b"070701" | b"070702" => (),
+			b"070707" => return_error!("The portable ASCII cpio format is not supported."),
+			_ => return_error!("Invalid cpio entry header."),
+		}
+		let field = |index: usize| {
+			let start = 6 + index * 8;
+			parse_number(&header[start..start + 8], 16).wrap_err("Invalid cpio entry header.")
+		};
+		let inode = field(0)?;
+		let mode = field(1)?;
+		let nlink = field(4)?;
+		let size = field(6)?;
+		let device = (field(7)?, field(8)?);
+		let name_size = field(11)?;
+
+		// Read the name.
+		let mut name = Vec::new();
+		(&mut reader)
+			.take(name_size)
+			.read_to_end(&mut name)
+			.wrap_err("Failed to read the entry's name.")?;
+		skip(&mut reader, padding(110 + name_size))?;
+		let name = std::str::from_utf8(&name)
+			.wrap_err("Invalid cpio entry name.")?
+			.trim_end_matches('\0')
+			.to_owned();
+
+		// Handle the end of the archive.
+		if name == "TRAILER!!!" {
+			for (_, paths) in links.drain() {
+				let mut paths = paths.into_iter();
+				if let Some(first) = paths.next() {
+					Destination::create_file(&first, &mut std::io::empty(), false)?;
+					for path in paths {
+						Destination::create_hard_link(&path, &first)?;
+					}
+				}
+			}
+			continue;
+		}
+
+		// Extract the entry.
+		let mut data = (&mut reader).take(size);
+		if let Some(path) = destination.path(Path::new(&name))? {
+			match mode & 0o170_000 {
+				0o040_000 => {
+					Destination::create_directory(&path)?;
+				},
+				0o100_000 => {
+					let key = (inode, device.0, device.1);
+					if nlink > 1 && size == 0 {
+						// The data for hard linked files is stored with the last link.
+						links.entry(key).or_default().push(path);
+					} else {
+						Destination::create_file(&path, &mut data, mode & 0o111 != 0)?;
+						for link in links.remove(&key).unwrap_or_default() {
+							Destination::create_hard_link(&link, &path)?;
+						}
+					}
+				},
+				0o120_000 => {
+					let mut target = String::new();
+					data.read_to_string(&mut target)
+						.wrap_err("Failed to read the symlink target.")?;
+					Destination::create_symlink(&path, Path::new(&target))?;
+				},
+				// Skip devices, fifos, and other special files.
+				_ => (),
+			}
+		}
+
+		// Skip the rest of the data and the padding.
+		std::io::copy(&mut data, &mut std::io::sink()).wrap_err("Failed to read the archive.")?;
+		skip(&mut reader, padding(size))?;
+	}
+	Ok(())
+}
+
+/// Parse an ASCII number in an archive header.
+fn parse_number(bytes: &[u8], radix: u32) -> Option<u64> {
+	let string = std::str::from_utf8(bytes).ok()?;
+	let string = string.trim_matches(|c: char| c == ' ' || c == '\0');
+	if string.is_empty() {
+		return Some(0);
+	}
+	u64::from_str_radix(string, radix).ok()
+}
+
+/// Get the number of bytes required to pad a size to a multiple of four.
+fn padding(size: u64) -> u64 {
+	(4 - size % 4) % 4
+}
+
+/// Skip bytes in a reader.
+fn skip(reader: &mut impl Read, count: u64) -> Result<()> {
+	std::io::copy(&mut reader.take(count), &mut std::io::sink())
+		.wrap_err("Failed to read the archive.")?;
+	Ok(())
+}
+
+/// The directory that an archive is extracted to. Entries with paths that would be written outside of the directory, either with `..` components or through symlinks, are rejected.
+struct Destination {
+	root: PathBuf,
+	strip_components: usize,
+}
+
+impl Destination {
+	fn new(root: &Path, options: ExtractOptions) -> Result<Self> {
+		std::fs::create_dir_all(root).wrap_err("Failed to create the directory.")?;
+		Ok(Self {
+			root: root.to_owned(),
+			strip_components: options.strip_components,
+		})
+	}
+
+	/// Get the path to extract an entry to, creating its parent directories. Returns `None` if the entry is removed by stripping components.
+	fn path(&self, entry_path: &Path) -> Result<Option<PathBuf>> {
+		// Normalize the path.
+		let mut components = Vec::new();
+		for component in entry_path.components() {
+			match component {
+				Component::Prefix(_) | Component::RootDir | Component::CurDir => (),
+				Component::ParentDir => {
+					let entry_path = entry_path.display();
+					return_error!(
+						r#"The archive entry "{entry_path}" would be extracted outside of the destination."#
+					);
+				},
+				Component::Normal(component) => components.push(component),
+			}
+		}
+
+		// Strip the leading components.
+		if components.len() <= self.strip_components {
+			return Ok(None);
+		}
+		let components = &components[self.strip_components..];
+
+		// Create the parent directories, making sure none of them are symlinks.
+		let mut path = self.root.clone();
+		for component in &components[..components.len() - 1] {
+			path.push(component);
+			match std::fs::symlink_metadata(&path) {
+				Ok(metadata) if metadata.is_dir() => (),
+				Ok(_) => {
+					let entry_path = entry_path.display();
+					return_error!(
+						r#"The archive entry "{entry_path}" would be extracted through a symlink or file."#
+					);
+				},
+				Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+					std::fs::create_dir(&path).wrap_err("Failed to create the directory.")?;
+				},
+				Err(error) => {
+					return Err(error).wrap_err("Failed to get the metadata.");
+				},
+			}
+		}
+		path.push(components[components.len() - 1]);
+
+		Ok(Some(path))
+	}
+
+	/// Remove an existing file or symlink at a path, so that later entries replace earlier ones and are never written through a symlink.
+	fn remove(path: &Path) -> Result<()> {
+		match std::fs::symlink_metadata(path) {
+			Ok(metadata) if !metadata.is_dir() => {
+				std::fs::remove_file(path).wrap_err("Failed to remove the existing file.")?;
+			},
+			Ok(_) => (),
+			Err(error) if error.kind() == std::io::ErrorKind::NotFound => (),
+			Err(error) => {
+				return Err(error).wrap_err("Failed to get the metadata.");
+			},
+		}
+		Ok(())
+	}
+
+	fn create_directory(path: &Path) -> Result<()> {
+		Self::remove(path)?;
+		if !path.is_dir() {
+			std::fs::create_dir(path).wrap_err("Failed to create the directory.")?;
+		}
+		Ok(())
+	}
+
+	fn create_file(path: &Path, reader: &mut impl Read, executable: bool) -> Result<()> {
+		Self::remove(path)?;
+		let mut file = std::fs::File::create(path).wrap_err("Failed to create the file.")?;
+		std::io::copy(reader, &mut file).wrap_err("Failed to write the file.")?;
+		let mode = if executable { 0o755 } else { 0o644 };
+		std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))
+			.wrap_err("Failed to set the permissions.")?;
+		Ok(())
+	}
+
+	fn create_symlink(path: &Path, target: &Path) -> Result<()> {
+		Self::remove(path)?;
+		std::os::unix::fs::symlink(target, path).wrap_err("Failed to create the symlink.")?;
+		Ok(())
+	}
+
+	fn create_hard_link(path: &Path, target: &Path) -> Result<()> {
+		Self::remove(path)?;
+		let metadata = std::fs::symlink_metadata(target).wrap_err("Failed to get the metadata.")?;
+		if !metadata.is_file() {
+			return_error!("Hard links must point to files.");
+		}
+		std::fs::hard_link(target, path).wrap_err("Failed to create the hard link.")?;
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::test::{client, directory, file, Store};
+
+	#[test]
+	fn pax_record_length() {
+		assert_eq!(pax_record("path", b"foo"), b"12 path=foo\n");
+
+		// The length prefix gains a digit when the record reaches 100 bytes.
+		let record = pax_record("k", &[b'a'; 94]);
+		assert_eq!(record.len(), 101);
+		assert!(record.starts_with(b"101 k="));
+	}
+
+	#[test]
+	fn destination_path() {
+		let tempdir = tempfile::TempDir::new().unwrap();
+		let root = tempdir.path().join("archive");
+		let options = ExtractOptions {
+			strip_components: 1,
+		};
+		let destination = Destination::new(&root, options).unwrap();
+		assert_eq!(
+			destination.path(Path::new("./package/bin/tool")).unwrap(),
+			Some(root.join("bin/tool"))
+		);
+		assert!(root.join("bin").is_dir());
+		assert_eq!(destination.path(Path::new("package/")).unwrap(), None);
+		assert!(destination.path(Path::new("package/../../etc")).is_err());
+
+		// Entries must not be written through symlinks.
+		std::os::unix::fs::symlink("/etc", root.join("link")).unwrap();
+		assert!(destination.path(Path::new("package/link/passwd")).is_err());
+	}
+
+	async fn tar(client: &crate::Client, artifact: &Artifact, options: ArchiveOptions) -> Vec<u8> {
+		let entries = entries(client, artifact, options).await.unwrap();
+		let client = client.clone();
+		tokio::task::spawn_blocking(move || {
+			let mut file = tempfile::tempfile().unwrap();
+			write_tar(&client, &mut file, entries).unwrap();
+			file.rewind().unwrap();
+			let mut bytes = Vec::new();
+			file.read_to_end(&mut bytes).unwrap();
+			bytes
+		})
+		.await
+		.unwrap()
+	}
+
+	#[tokio::test]
+	async fn tar_round_trip() {
+		let client = client();
+		let artifact = directory(vec![
+			("README", file("hello", false)),
+			("bin", directory(vec![("tool", file("#!/bin/sh", true))])),
+			(
+				"link",
+				crate::Symlink::new(None, Some("bin/tool".to_owned())).into(),
+			),
+		]);
+		let bytes = tar(&client, &artifact, ArchiveOptions::default()).await;
+		assert_eq!(detect_archive_format(&bytes), Some(ArchiveFormat::Tar));
+
+		let tempdir = tempfile::TempDir::new().unwrap();
+		let root = tempdir.path().join("archive");
+		extract(
+			ArchiveFormat::Tar,
+			bytes.as_slice(),
+			&root,
+			ExtractOptions::default(),
+		)
+		.unwrap();
+		assert_eq!(
+			std::fs::read_to_string(root.join("README")).unwrap(),
+			"hello"
+		);
+		let tool = root.join("bin/tool");
+		assert_eq!(std::fs::read_to_string(&tool).unwrap(), "#!/bin/sh");
+		let mode = std::fs::metadata(&tool).unwrap().permissions().mode();
+		assert_eq!(mode & 0o777, 0o755);
+		assert_eq!(
+			std::fs::read_link(root.join("link")).unwrap(),
+			Path::new("bin/tool")
+		);
+	}
+
+	#[tokio::test]
+	async fn tar_is_deterministic() {
+		let client = client();
+		let id = crate::file::Id::new(&"target".into());
+		let target = crate::File::with_id(id.clone()).into();
+		let artifact = directory(vec![
+			("file", file("contents", false)),
+			(
+				"link",
+				crate::Symlink::new(Some(target), Some("bin/tool".to_owned())).into(),
+			),
+		]);
+		let options = ArchiveOptions { references: true };
+
+		// A symlink with an artifact is rendered under the artifacts directory.
+		let entries = entries(&client, &artifact, options).await.unwrap();
+		let (_, Entry::Symlink { target }) = &entries[1] else {
+			panic!("Expected a symlink.");
+		};
+		assert_eq!(target, &format!("/.tangram/artifacts/{id}/bin/tool"));
+
+		// Archiving the same artifact twice produces the same bytes.
+		let first = tar(&client, &artifact, options).await;
+		let second = tar(&client, &artifact, options).await;
+		assert_eq!(first, second);
+	}
+
+	#[tokio::test]
+	async fn tar_round_trip_with_references() {
+		let store = Store::default();
+		let tool = file("#!/bin/sh", true);
+		let library = directory(vec![("lib.so", file("library", false))]);
+		let contents = crate::Leaf::with_object(crate::leaf::Object {
+			bytes: "binary".to_owned().into(),
+		});
+		let binary = crate::File::new(contents.into(), true, vec![library.clone()]).into();
+		let artifact = directory(vec![
+			("README", file("hello", false)),
+			(
+				"bin",
+				directory(vec![("tool", tool.clone()), ("binary", binary)]),
+			),
+			(
+				"link",
+				crate::Symlink::new(None, Some("bin/tool".to_owned())).into(),
+			),
+			("tool", crate::Symlink::new(Some(tool.clone()), None).into()),
+			(
+				"lib",
+				crate::Symlink::new(Some(library), Some("lib.so".to_owned())).into(),
+			),
+		]);
+		let options = ArchiveOptions { references: true };
+
+		// Extracting the archive produces the same artifact, including its references.
+		let blob = Blob::archive(&store, &artifact, ArchiveFormat::Tar, options)
+			.await
+			.unwrap();
+		let extracted = blob
+			.extract(&store, None, ExtractOptions::default())
+			.await
+			.unwrap();
+		assert_eq!(
+			extracted.id(&store).await.unwrap(),
+			artifact.id(&store).await.unwrap()
+		);
+	}
+}
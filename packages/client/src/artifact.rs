@@ -1,15 +1,17 @@
 use crate::{
-	checksum, directory, file, id, object, symlink, util, Blob, Checksum, Directory, File, Handle,
-	Symlink, Template, Value,
+	checksum, directory, file, id, object, symlink, template, util, Blob, Checksum, Directory,
+	File, Handle, Symlink, Template, Value,
 };
 use async_recursion::async_recursion;
 use derive_more::{From, TryInto, TryUnwrap};
 use futures::stream::{FuturesOrdered, FuturesUnordered, TryStreamExt};
+use num::ToPrimitive;
 use std::{
 	collections::{HashSet, VecDeque},
 	os::unix::fs::PermissionsExt,
 };
 use tangram_error::{return_error, Error, Result, WrapErr};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
 
 /// An artifact kind.
 #[derive(Clone, Copy, Debug)]
@@ -112,17 +114,99 @@ impl Artifact {
 		Ok(())
 	}
 
-	/// Compute an artifact's checksum.
-	#[allow(clippy::unused_async)]
+	/// Compute an artifact's checksum. The checksum of a file is the checksum of its contents. The checksum of a directory or symlink is the checksum of its serialization, which is described in [`Artifact::serialize_for_checksum`].
 	pub async fn checksum(
 		&self,
-		_tg: &dyn Handle,
+		tg: &dyn Handle,
 		algorithm: checksum::Algorithm,
 	) -> Result<Checksum> {
-		match algorithm {
-			checksum::Algorithm::Unsafe => Ok(Checksum::Unsafe),
-			_ => unimplemented!(),
+		let mut writer = checksum::Writer::new(algorithm);
+		match (algorithm, self) {
+			(checksum::Algorithm::Unsafe, _) => (),
+			(_, Self::File(file)) => {
+				let mut reader = file.reader(tg).await?;
+				tokio::io::copy(&mut reader, &mut writer)
+					.await
+					.wrap_err("Failed to read the file.")?;
+			},
+			(_, Self::Directory(_) | Self::Symlink(_)) => {
+				self.serialize_for_checksum(tg, &mut writer).await?;
+			},
+		}
+		Ok(writer.finalize())
+	}
+
+	/// Write the canonical serialization of an artifact that is used to compute checksums. The serialization is similar to the Nix archive format. It consists of the string `tangram-artifact-1` followed by the artifact's node, where:
+	///
+	/// - A string is its length as a little-endian `u64`, followed by its bytes, followed by zero bytes to pad it to a multiple of eight bytes.
+	/// - A directory node is the string `directory`, followed by the number of entries as a little-endian `u64`, followed by the name and node of each entry in order of their names' bytes.
+	/// - A file node is the string `file`, followed by `1` if the file is executable or `0` otherwise as a little-endian `u64`, followed by the contents as a string.
+	/// - A symlink node is the string `symlink`, followed by the target as a string. If the symlink has an artifact, then the target is `/.tangram/artifacts/<id>`, followed by `/` and its path if it has one.
+	///
+	/// File references are not part of the serialization.
+	pub async fn serialize_for_checksum(
+		&self,
+		tg: &dyn Handle,
+		writer: &mut (dyn AsyncWrite + Send + Unpin),
+	) -> Result<()> {
+		write_checksum_string(writer, b"tangram-artifact-1").await?;
+		self.serialize_node_for_checksum(tg, writer).await?;
+		writer
+			.flush()
+			.await
+			.wrap_err("Failed to flush the writer.")?;
+		Ok(())
+	}
+
+	#[async_recursion]
+	async fn serialize_node_for_checksum(
+		&self,
+		tg: &dyn Handle,
+		writer: &mut (dyn AsyncWrite + Send + Unpin),
+	) -> Result<()> {
+		match self {
+			Self::Directory(directory) => {
+				write_checksum_string(writer, b"directory").await?;
+				let entries = directory.entries(tg).await?;
+				write_checksum_u64(writer, entries.len().to_u64().unwrap()).await?;
+				for (name, artifact) in entries {
+					write_checksum_string(writer, name.as_bytes()).await?;
+					artifact.serialize_node_for_checksum(tg, writer).await?;
+				}
+			},
+			Self::File(file) => {
+				write_checksum_string(writer, b"file").await?;
+				let executable = file.executable(tg).await?;
+				write_checksum_u64(writer, u64::from(executable)).await?;
+				let contents = file.contents(tg).await?;
+				let size = contents.size(tg).await?;
+				write_checksum_u64(writer, size).await?;
+				let mut reader = contents.reader(tg).await?;
+				let copied = tokio::io::copy(&mut reader, writer)
+					.await
+					.wrap_err("Failed to read the file.")?;
+				if copied != size {
+					return_error!("The file's contents did not have the expected size.");
+				}
+				write_checksum_padding(writer, size).await?;
+			},
+			Self::Symlink(symlink) => {
+				write_checksum_string(writer, b"symlink").await?;
+				let path = symlink.path(tg).await?.clone();
+				let target = if let Some(artifact) = symlink.artifact(tg).await? {
+					let id = artifact.id(tg).await?;
+					if let Some(path) = path {
+						format!("/.tangram/artifacts/{id}/{path}")
+					} else {
+						format!("/.tangram/artifacts/{id}")
+					}
+				} else {
+					path.unwrap_or_default()
+				};
+				write_checksum_string(writer, target.as_bytes()).await?;
+			},
 		}
+		Ok(())
 	}
 
 	/// Collect an artifact's references.
@@ -311,28 +395,18 @@ impl Artifact {
 			.wrap_err("The symlink target must be valid UTF-8.")?;
 		let target = Template::unrender(target)?;
 
-		// Get the artifact and path.
-		let (artifact, path) = if target.components.len() == 1 {
-			let path = target.components[0]
-				.try_unwrap_string_ref()
-				.ok()
-				.wrap_err("Invalid sylink.")?
-				.clone();
-			(None, Some(path))
-		} else if target.components.len() == 2 {
-			let artifact = target.components[0]
-				.try_unwrap_artifact_ref()
-				.ok()
-				.wrap_err("Invalid sylink.")?
-				.clone();
-			let path = target.components[1]
-				.try_unwrap_string_ref()
-				.ok()
-				.wrap_err("Invalid sylink.")?
-				.clone();
-			(Some(artifact), Some(path))
-		} else {
-			return_error!("Invalid symlink.");
+		// Get the artifact and path. A target with an artifact is rendered as `/.tangram/artifacts/<id>`, followed by `/` and the path if there is one.
+		let (artifact, path) = match target.components.as_slice() {
+			[template::Component::String(path)] => (None, Some(path.clone())),
+			[template::Component::Artifact(artifact)] => (Some(artifact.clone()), None),
+			[template::Component::Artifact(artifact), template::Component::String(path)] => {
+				let path = path
+					.strip_prefix('/')
+					.filter(|path| !path.is_empty())
+					.wrap_err("Invalid symlink.")?;
+				(Some(artifact.clone()), Some(path.to_owned()))
+			},
+			_ => return_error!("Invalid symlink."),
 		};
 
 		// Create the symlink.
@@ -669,3 +743,138 @@ impl TryFrom<Value> for Artifact {
 		}
 	}
 }
+
+async fn write_checksum_u64(
+	writer: &mut (dyn AsyncWrite + Send + Unpin),
+	value: u64,
+) -> Result<()> {
+	writer
+		.write_all(&value.to_le_bytes())
+		.await
+		.wrap_err("Failed to write.")?;
+	Ok(())
+}
+
+async fn write_checksum_string(
+	writer: &mut (dyn AsyncWrite + Send + Unpin),
+	bytes: &[u8],
+) -> Result<()> {
+	let size = bytes.len().to_u64().unwrap();
+	write_checksum_u64(writer, size).await?;
+	writer.write_all(bytes).await.wrap_err("Failed to write.")?;
+	write_checksum_padding(writer, size).await?;
+	Ok(())
+}
+
+async fn write_checksum_padding(
+	writer: &mut (dyn AsyncWrite + Send + Unpin),
+	size: u64,
+) -> Result<()> {
+	let padding = ((8 - size % 8) % 8).to_usize().unwrap();
+	writer
+		.write_all(&[0u8; 8][..padding])
+		.await
+		.wrap_err("Failed to write.")?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::test::{client, file};
+
+	async fn serialize(artifact: &Artifact) -> Vec<u8> {
+		let mut bytes = Vec::new();
+		artifact
+			.serialize_for_checksum(&client(), &mut bytes)
+			.await
+			.unwrap();
+		bytes
+	}
+
+	#[tokio::test]
+	async fn serialize_file_for_checksum() {
+		let artifact = file("hello", true);
+		let expected: &[&[u8]] = &[
+			&18u64.to_le_bytes(),
+			b"tangram-artifact-1",
+			&[0; 6],
+			&4u64.to_le_bytes(),
+			b"file",
+			&[0; 4],
+			&1u64.to_le_bytes(),
+			&5u64.to_le_bytes(),
+			b"hello",
+			&[0; 3],
+		];
+		assert_eq!(serialize(&artifact).await, expected.concat());
+	}
+
+	#[tokio::test]
+	async fn serialize_directory_for_checksum() {
+		let entries = [
+			("b".to_owned(), file("", false)),
+			("a".to_owned(), file("12345678", false)),
+		];
+		let artifact: Artifact = Directory::new(entries.into_iter().collect()).into();
+		let expected: &[&[u8]] = &[
+			&18u64.to_le_bytes(),
+			b"tangram-artifact-1",
+			&[0; 6],
+			&9u64.to_le_bytes(),
+			b"directory",
+			&[0; 7],
+			&2u64.to_le_bytes(),
+			&1u64.to_le_bytes(),
+			b"a",
+			&[0; 7],
+			&4u64.to_le_bytes(),
+			b"file",
+			&[0; 4],
+			&0u64.to_le_bytes(),
+			&8u64.to_le_bytes(),
+			b"12345678",
+			&1u64.to_le_bytes(),
+			b"b",
+			&[0; 7],
+			&4u64.to_le_bytes(),
+			b"file",
+			&[0; 4],
+			&0u64.to_le_bytes(),
+			&0u64.to_le_bytes(),
+		];
+		assert_eq!(serialize(&artifact).await, expected.concat());
+	}
+
+	#[tokio::test]
+	async fn serialize_symlink_for_checksum() {
+		let id = file::Id::new(&"target".into());
+		let target = File::with_id(id.clone()).into();
+		let artifact = Symlink::new(Some(target), Some("bin/tool".to_owned())).into();
+		let target = format!("/.tangram/artifacts/{id}/bin/tool");
+		let padding = vec![0u8; (8 - target.len() % 8) % 8];
+		let expected: &[&[u8]] = &[
+			&18u64.to_le_bytes(),
+			b"tangram-artifact-1",
+			&[0; 6],
+			&7u64.to_le_bytes(),
+			b"symlink",
+			&[0; 1],
+			&target.len().to_u64().unwrap().to_le_bytes(),
+			target.as_bytes(),
+			&padding,
+		];
+		assert_eq!(serialize(&artifact).await, expected.concat());
+
+		// A symlink without a path targets the artifact itself.
+		let target = File::with_id(id.clone()).into();
+		let artifact = Symlink::new(Some(target), None).into();
+		let bytes = serialize(&artifact).await;
+		let target = format!("/.tangram/artifacts/{id}");
+		assert_eq!(
+			&bytes[48..56],
+			&target.len().to_u64().unwrap().to_le_bytes()
+		);
+		assert_eq!(&bytes[56..56 + target.len()], target.as_bytes());
+	}
+}
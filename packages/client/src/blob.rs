@@ -1,6 +1,6 @@
 use crate::{
-	branch, id, leaf, object, return_error, Artifact, Branch, Error, Handle, Leaf, Result, Value,
-	WrapErr,
+	archive, branch, id, leaf, object, return_error, Artifact, Branch, Error, Handle, Leaf, Result,
+	Value, WrapErr,
 };
 use bytes::Bytes;
 use derive_more::From;
@@ -11,7 +11,11 @@ use futures::{
 };
 use num::ToPrimitive;
 use pin_project::pin_project;
-use std::{io::Cursor, pin::Pin, task::Poll};
+use std::{
+	io::{Cursor, Seek},
+	pin::Pin,
+	task::Poll,
+};
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek};
 use tokio_util::io::SyncIoBridge;
 
@@ -41,16 +45,34 @@ pub enum Blob {
 	Branch(Branch),
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum ArchiveFormat {
+	Ar,
+	Cpio,
 	Tar,
 	Zip,
 }
 
+#[derive(Clone, Copy, Debug, Default, serde::Deserialize, serde::Serialize)]
+pub struct ArchiveOptions {
+	/// Whether to embed the references of files and the artifacts of symlinks, so that the archive extracts to the same artifact.
+	#[serde(default)]
+	pub references: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractOptions {
+	/// The number of leading path components to remove from each entry. Entries with no remaining components are skipped.
+	#[serde(default)]
+	pub strip_components: usize,
+}
+
 #[derive(Clone, Copy, Debug)]
 pub enum CompressionFormat {
 	Bz2,
 	Gz,
+	Lz,
 	Xz,
 	Zstd,
 }
@@ -178,6 +200,9 @@ impl Blob {
 			CompressionFormat::Gz => {
 				Box::new(async_compression::tokio::bufread::GzipEncoder::new(reader))
 			},
+			CompressionFormat::Lz => {
+				return_error!("Compressing with lzip is not supported.");
+			},
 			CompressionFormat::Xz => {
 				Box::new(async_compression::tokio::bufread::XzEncoder::new(reader))
 			},
@@ -191,70 +216,114 @@ impl Blob {
 
 	pub async fn decompress(&self, tg: &dyn Handle, format: CompressionFormat) -> Result<Blob> {
 		let reader = self.reader(tg).await?;
-		let reader = tokio::io::BufReader::new(reader);
-		let reader: Box<dyn AsyncRead + Unpin> = match format {
-			CompressionFormat::Bz2 => {
-				Box::new(async_compression::tokio::bufread::BzDecoder::new(reader))
-			},
-			CompressionFormat::Gz => {
-				Box::new(async_compression::tokio::bufread::GzipDecoder::new(reader))
-			},
-			CompressionFormat::Xz => {
-				Box::new(async_compression::tokio::bufread::XzDecoder::new(reader))
-			},
-			CompressionFormat::Zstd => {
-				Box::new(async_compression::tokio::bufread::ZstdDecoder::new(reader))
-			},
-		};
+		let reader = decoder(reader, format).await?;
 		let blob = Blob::with_reader(tg, reader).await?;
 		Ok(blob)
 	}
 
-	#[allow(clippy::unused_async)]
+	/// Archive an artifact. If the artifact is a directory, then its entries are placed at the root of the archive. Otherwise, the archive contains a single entry named with the artifact's ID. The output is deterministic: entries are sorted, timestamps and owners are zeroed, and modes are normalized.
 	pub async fn archive(
-		_tg: &dyn Handle,
-		_artifact: &Artifact,
-		_format: ArchiveFormat,
+		tg: &dyn Handle,
+		artifact: &Artifact,
+		format: ArchiveFormat,
+		options: ArchiveOptions,
 	) -> Result<Self> {
-		unimplemented!()
-	}
-
-	pub async fn extract(&self, tg: &dyn Handle, format: ArchiveFormat) -> Result<Artifact> {
-		// Create the reader.
-		let reader = self.reader(tg).await?;
+		match format {
+			ArchiveFormat::Ar | ArchiveFormat::Cpio => {
+				return_error!(r#"Creating "{format}" archives is not supported."#);
+			},
+			ArchiveFormat::Zip if options.references => {
+				return_error!("Embedding references is only supported for tar archives.");
+			},
+			_ => (),
+		}
 
-		// Create a temp.
-		let tempdir = tempfile::TempDir::new().wrap_err("Failed to create the temporary leaf.")?;
-		let path = tempdir.path().join("archive");
+		// Collect the entries.
+		let entries = archive::entries(tg, artifact, options).await?;
 
-		// Extract in a blocking task.
-		tokio::task::spawn_blocking({
-			let reader = SyncIoBridge::new(reader);
-			let path = path.clone();
+		// Write the archive to a temporary file in a blocking task.
+		let file = tokio::task::spawn_blocking({
+			let tg = tg.clone_box();
 			move || -> Result<_> {
+				let mut file =
+					tempfile::tempfile().wrap_err("Failed to create the temporary file.")?;
 				match format {
-					ArchiveFormat::Tar => {
-						let mut archive = tar::Archive::new(reader);
-						archive.set_preserve_permissions(false);
-						archive.set_unpack_xattrs(false);
-						archive
-							.unpack(path)
-							.wrap_err("Failed to extract the archive.")?;
-					},
-					ArchiveFormat::Zip => {
-						let mut archive = zip::ZipArchive::new(reader)
-							.wrap_err("Failed to extract the archive.")?;
-						archive
-							.extract(&path)
-							.wrap_err("Failed to extract the archive.")?;
-					},
+					ArchiveFormat::Tar => archive::write_tar(tg.as_ref(), &mut file, entries)?,
+					ArchiveFormat::Zip => archive::write_zip(tg.as_ref(), &mut file, entries)?,
+					ArchiveFormat::Ar | ArchiveFormat::Cpio => unreachable!(),
 				}
-				Ok(())
+				file.rewind()
+					.wrap_err("Failed to seek the temporary file.")?;
+				Ok(file)
 			}
 		})
 		.await
 		.unwrap()?;
 
+		// Create the blob.
+		let blob = Blob::with_reader(tg, tokio::fs::File::from_std(file)).await?;
+
+		Ok(blob)
+	}
+
+	/// Extract an archive. The compression format is always detected. If the archive format is not provided, then it is detected as well.
+	pub async fn extract(
+		&self,
+		tg: &dyn Handle,
+		format: Option<ArchiveFormat>,
+		options: ExtractOptions,
+	) -> Result<Artifact> {
+		// Detect the compression format.
+		let header = read_header(self.reader(tg).await?).await?;
+		let compression = archive::detect_compression_format(&header);
+
+		// Detect the archive format.
+		let format = if let Some(format) = format {
+			format
+		} else {
+			let header = if let Some(compression) = compression {
+				read_header(decoder(self.reader(tg).await?, compression).await?).await?
+			} else {
+				header
+			};
+			archive::detect_archive_format(&header)
+				.wrap_err("Failed to detect the archive format.")?
+		};
+
+		// Create a temp.
+		let tempdir = tempfile::TempDir::new().wrap_err("Failed to create the temporary leaf.")?;
+		let path = tempdir.path().join("archive");
+
+		// Extract in a blocking task.
+		let reader = self.reader(tg).await?;
+		match (format, compression) {
+			(ArchiveFormat::Zip, None) => {
+				tokio::task::spawn_blocking({
+					let reader = SyncIoBridge::new(reader);
+					let path = path.clone();
+					move || archive::extract_zip(reader, &path, options)
+				})
+				.await
+				.unwrap()?;
+			},
+			(ArchiveFormat::Zip, Some(_)) => {
+				return_error!("Compressed zip archives are not supported.");
+			},
+			(format, compression) => {
+				let reader: Box<dyn AsyncRead + Send + Unpin> = match compression {
+					Some(compression) => decoder(reader, compression).await?,
+					None => Box::new(reader),
+				};
+				tokio::task::spawn_blocking({
+					let reader = SyncIoBridge::new(reader);
+					let path = path.clone();
+					move || archive::extract(format, reader, &path, options)
+				})
+				.await
+				.unwrap()?;
+			},
+		}
+
 		// Check in the extracted artifact.
 		let path = path.try_into()?;
 		let artifact = Artifact::check_in(tg, &path)
@@ -265,6 +334,53 @@ impl Blob {
 	}
 }
 
+/// Create a reader that decompresses a reader.
+async fn decoder(
+	reader: impl AsyncRead + Send + Unpin + 'static,
+	format: CompressionFormat,
+) -> Result<Box<dyn AsyncRead + Send + Unpin>> {
+	let reader = tokio::io::BufReader::new(reader);
+	let reader: Box<dyn AsyncRead + Send + Unpin> = match format {
+		CompressionFormat::Bz2 => {
+			Box::new(async_compression::tokio::bufread::BzDecoder::new(reader))
+		},
+		CompressionFormat::Gz => {
+			Box::new(async_compression::tokio::bufread::GzipDecoder::new(reader))
+		},
+		CompressionFormat::Lz => {
+			// Decode the lzip member's LZMA stream. The member's trailer is not verified.
+			let mut reader = reader;
+			let mut header = [0u8; archive::LZIP_HEADER_SIZE];
+			reader
+				.read_exact(&mut header)
+				.await
+				.wrap_err("Failed to read the lzip header.")?;
+			let header = archive::lzma_header_for_lzip_header(header)?;
+			let reader = Cursor::new(header).chain(reader);
+			let reader = tokio::io::BufReader::new(reader);
+			Box::new(async_compression::tokio::bufread::LzmaDecoder::new(reader))
+		},
+		CompressionFormat::Xz => {
+			Box::new(async_compression::tokio::bufread::XzDecoder::new(reader))
+		},
+		CompressionFormat::Zstd => {
+			Box::new(async_compression::tokio::bufread::ZstdDecoder::new(reader))
+		},
+	};
+	Ok(reader)
+}
+
+/// Read the first bytes of a reader to detect its format.
+async fn read_header(reader: impl AsyncRead + Unpin) -> Result<Vec<u8>> {
+	let mut header = Vec::new();
+	reader
+		.take(512)
+		.read_to_end(&mut header)
+		.await
+		.wrap_err("Failed to read the header.")?;
+	Ok(header)
+}
+
 impl std::fmt::Display for Id {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		match self {
@@ -516,6 +632,12 @@ impl AsyncSeek for Reader {
 impl std::fmt::Display for ArchiveFormat {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		match self {
+			Self::Ar => {
+				write!(f, ".ar")?;
+			},
+			Self::Cpio => {
+				write!(f, ".cpio")?;
+			},
 			Self::Tar => {
 				write!(f, ".tar")?;
 			},
@@ -532,6 +654,8 @@ impl std::str::FromStr for ArchiveFormat {
 
 	fn from_str(s: &str) -> Result<Self, Self::Err> {
 		match s {
+			".ar" | ".deb" => Ok(Self::Ar),
+			".cpio" => Ok(Self::Cpio),
 			".tar" => Ok(Self::Tar),
 			".zip" => Ok(Self::Zip),
 			_ => return_error!("Invalid format."),
@@ -558,6 +682,7 @@ impl std::fmt::Display for CompressionFormat {
 		let string = match self {
 			Self::Bz2 => ".bz2",
 			Self::Gz => ".gz",
+			Self::Lz => ".lz",
 			Self::Xz => ".xz",
 			Self::Zstd => ".zst",
 		};
@@ -573,6 +698,7 @@ impl std::str::FromStr for CompressionFormat {
 		match s {
 			".bz2" => Ok(Self::Bz2),
 			".gz" => Ok(Self::Gz),
+			".lz" => Ok(Self::Lz),
 			".xz" => Ok(Self::Xz),
 			".zst" | ".zstd" => Ok(Self::Zstd),
 			_ => return_error!("Invalid compression format."),
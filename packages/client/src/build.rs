@@ -38,6 +38,19 @@ pub struct Object {
 	pub children: Vec<Build>,
 	pub log: Blob,
 	pub outcome: Outcome,
+	pub profile: Option<Profile>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Profile {
+	pub cpu: Blob,
+	pub heap_snapshot: Option<Blob>,
+}
+
+#[derive(Clone, Copy, Debug, Default, serde::Deserialize, serde::Serialize)]
+pub struct ProfileOptions {
+	#[serde(default)]
+	pub heap_snapshot: bool,
 }
 
 #[derive(Clone, Debug, serde::Deserialize, TryUnwrap)]
@@ -83,6 +96,15 @@ pub mod data {
 		pub children: Vec<Id>,
 		pub log: blob::Id,
 		pub outcome: Outcome,
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		pub profile: Option<Profile>,
+	}
+
+	#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+	pub struct Profile {
+		pub cpu: blob::Id,
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		pub heap_snapshot: Option<blob::Id>,
 	}
 
 	#[derive(Clone, Debug, serde::Deserialize, serde::Serialize, TryUnwrap)]
@@ -201,11 +223,23 @@ impl Build {
 			Outcome::Failed(error) => data::Outcome::Failed(error.clone()),
 			Outcome::Succeeded(value) => data::Outcome::Succeeded(value.data(tg).await?),
 		};
+		let profile = if let Some(profile) = &object.profile {
+			let cpu = profile.cpu.id(tg).await?;
+			let heap_snapshot = if let Some(heap_snapshot) = &profile.heap_snapshot {
+				Some(heap_snapshot.id(tg).await?)
+			} else {
+				None
+			};
+			Some(data::Profile { cpu, heap_snapshot })
+		} else {
+			None
+		};
 		Ok(Data {
 			target,
 			children,
 			log,
 			outcome,
+			profile,
 		})
 	}
 }
@@ -218,12 +252,14 @@ impl Build {
 		children: Vec<Build>,
 		log: Blob,
 		outcome: Outcome,
+		profile: Option<Profile>,
 	) -> Result<Self> {
 		let object = Object {
 			target,
 			children,
 			log,
 			outcome,
+			profile,
 		};
 		let build = Self::with_state(State {
 			id: Some(id.clone()),
@@ -389,11 +425,19 @@ impl Data {
 			.map(value::Data::children)
 			.into_iter()
 			.flatten();
+		let profile = self
+			.profile
+			.iter()
+			.flat_map(|profile| {
+				std::iter::once(profile.cpu.clone()).chain(profile.heap_snapshot.clone())
+			})
+			.map(Into::into);
 		std::iter::empty()
 			.chain(target)
 			.chain(children)
 			.chain(log)
 			.chain(outcome)
+			.chain(profile)
 			.collect()
 	}
 }
@@ -406,11 +450,16 @@ impl TryFrom<Data> for Object {
 		let children = data.children.into_iter().map(Build::with_id).collect();
 		let log = Blob::with_id(data.log);
 		let outcome = data.outcome.try_into()?;
+		let profile = data.profile.map(|profile| Profile {
+			cpu: Blob::with_id(profile.cpu),
+			heap_snapshot: profile.heap_snapshot.map(Blob::with_id),
+		});
 		Ok(Self {
 			target,
 			children,
 			log,
 			outcome,
+			profile,
 		})
 	}
 }
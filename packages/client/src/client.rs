@@ -1,6 +1,6 @@
 use crate::{
-	artifact, build, directory, lock, object, package, target, user, Dependency, Handle, Id,
-	Runtime, Status, System, User,
+	artifact, blob, build, directory, lock, object, package, target, user, Checksum, Dependency,
+	Handle, Id, Runtime, Status, System, User,
 };
 use async_trait::async_trait;
 use bytes::Bytes;
@@ -98,6 +98,12 @@ pub struct GetOrCreateBuildForTargetSearchParams {
 	pub depth: u64,
 	#[serde(default)]
 	pub retry: build::Retry,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub inspect: Option<std::net::SocketAddr>,
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub profile: bool,
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub heap_snapshot: bool,
 }
 
 type Incoming = hyper::body::Incoming;
@@ -477,6 +483,42 @@ impl Handle for Client {
 		Ok(())
 	}
 
+	async fn try_get_blob_for_checksum(&self, checksum: &Checksum) -> Result<Option<blob::Id>> {
+		let request = http::request::Builder::default()
+			.method(http::Method::GET)
+			.uri(format!("/v1/checksums/{checksum}/blob"))
+			.body(empty())
+			.wrap_err("Failed to create the request.")?;
+		let response = self.send(request).await?;
+		if response.status() == http::StatusCode::NOT_FOUND {
+			return Ok(None);
+		}
+		if !response.status().is_success() {
+			return_error!("Expected the response's status to be success.");
+		}
+		let bytes = response
+			.collect()
+			.await
+			.wrap_err("Failed to collect the response body.")?
+			.to_bytes();
+		let id = serde_json::from_slice(&bytes).wrap_err("Failed to deserialize the body.")?;
+		Ok(Some(id))
+	}
+
+	async fn set_blob_for_checksum(&self, checksum: &Checksum, id: &blob::Id) -> Result<()> {
+		let body = serde_json::to_string(id).wrap_err("Failed to serialize the body.")?;
+		let request = http::request::Builder::default()
+			.method(http::Method::PUT)
+			.uri(format!("/v1/checksums/{checksum}/blob"))
+			.body(full(body))
+			.wrap_err("Failed to create the request.")?;
+		let response = self.send(request).await?;
+		if !response.status().is_success() {
+			return_error!("Expected the response's status to be success.");
+		}
+		Ok(())
+	}
+
 	async fn try_get_build_for_target(&self, id: &target::Id) -> Result<Option<build::Id>> {
 		let request = http::request::Builder::default()
 			.method(http::Method::GET)
@@ -505,8 +547,16 @@ impl Handle for Client {
 		id: &target::Id,
 		depth: u64,
 		retry: build::Retry,
+		inspect: Option<std::net::SocketAddr>,
+		profile: Option<build::ProfileOptions>,
 	) -> Result<build::Id> {
-		let search_params = GetOrCreateBuildForTargetSearchParams { depth, retry };
+		let search_params = GetOrCreateBuildForTargetSearchParams {
+			depth,
+			retry,
+			inspect,
+			profile: profile.is_some(),
+			heap_snapshot: profile.is_some_and(|profile| profile.heap_snapshot),
+		};
 		let search_params = serde_urlencoded::to_string(search_params)
 			.wrap_err("Failed to serialize the search params.")?;
 		let uri = format!("/v1/targets/{id}/build?{search_params}");
@@ -777,7 +827,7 @@ impl Handle for Client {
 		Ok(())
 	}
 
-	async fn search_packages(&self, query: &str) -> Result<Vec<String>> {
+	async fn search_packages(&self, query: &str) -> Result<Vec<package::SearchResult>> {
 		let mut uri = "/v1/packages/search".to_owned();
 		let search_params = SearchPackagesSearchParams {
 			query: query.to_owned(),
@@ -965,6 +1015,140 @@ impl Handle for Client {
 		Ok(())
 	}
 
+	async fn try_get_package_status(
+		&self,
+		dependency: &Dependency,
+	) -> Result<Option<package::Status>> {
+		let dependency = dependency.to_string();
+		let dependency = urlencoding::encode(&dependency);
+		let request = http::request::Builder::default()
+			.method(http::Method::GET)
+			.uri(format!("/v1/packages/{dependency}/status"))
+			.body(empty())
+			.wrap_err("Failed to create the request.")?;
+		let response = self.send(request).await?;
+		if response.status() == http::StatusCode::NOT_FOUND {
+			return Ok(None);
+		}
+		if !response.status().is_success() {
+			return_error!("Expected the response's status to be success.");
+		}
+		let bytes = response
+			.collect()
+			.await
+			.wrap_err("Failed to collect the response body.")?
+			.to_bytes();
+		let status =
+			serde_json::from_slice(&bytes).wrap_err("Failed to deserialize the response body.")?;
+		Ok(Some(status))
+	}
+
+	async fn yank_package(
+		&self,
+		user: Option<&User>,
+		dependency: &Dependency,
+		yanked: bool,
+	) -> Result<()> {
+		let dependency = dependency.to_string();
+		let dependency = urlencoding::encode(&dependency);
+		let mut request = http::request::Builder::default()
+			.method(http::Method::POST)
+			.uri(format!("/v1/packages/{dependency}/yank"));
+		let user = user.or(self.inner.user.as_ref());
+		if let Some(token) = user.and_then(|user| user.token.as_ref()) {
+			request = request.header(http::header::AUTHORIZATION, format!("Bearer {token}"));
+		}
+		let body = serde_json::to_vec(&yanked).wrap_err("Failed to serialize the body.")?;
+		let request = request
+			.body(full(body))
+			.wrap_err("Failed to create the request.")?;
+		let response = self.send(request).await?;
+		if !response.status().is_success() {
+			return_error!("Expected the response's status to be success.");
+		}
+		Ok(())
+	}
+
+	async fn deprecate_package(
+		&self,
+		user: Option<&User>,
+		dependency: &Dependency,
+		message: Option<&str>,
+	) -> Result<()> {
+		let dependency = dependency.to_string();
+		let dependency = urlencoding::encode(&dependency);
+		let mut request = http::request::Builder::default()
+			.method(http::Method::POST)
+			.uri(format!("/v1/packages/{dependency}/deprecate"));
+		let user = user.or(self.inner.user.as_ref());
+		if let Some(token) = user.and_then(|user| user.token.as_ref()) {
+			request = request.header(http::header::AUTHORIZATION, format!("Bearer {token}"));
+		}
+		let body = serde_json::to_vec(&message).wrap_err("Failed to serialize the body.")?;
+		let request = request
+			.body(full(body))
+			.wrap_err("Failed to create the request.")?;
+		let response = self.send(request).await?;
+		if !response.status().is_success() {
+			return_error!("Expected the response's status to be success.");
+		}
+		Ok(())
+	}
+
+	async fn try_get_package_signatures(
+		&self,
+		dependency: &Dependency,
+	) -> Result<Option<Vec<package::Signature>>> {
+		let dependency = dependency.to_string();
+		let dependency = urlencoding::encode(&dependency);
+		let request = http::request::Builder::default()
+			.method(http::Method::GET)
+			.uri(format!("/v1/packages/{dependency}/signatures"))
+			.body(empty())
+			.wrap_err("Failed to create the request.")?;
+		let response = self.send(request).await?;
+		if response.status() == http::StatusCode::NOT_FOUND {
+			return Ok(None);
+		}
+		if !response.status().is_success() {
+			return_error!("Expected the response's status to be success.");
+		}
+		let bytes = response
+			.collect()
+			.await
+			.wrap_err("Failed to collect the response body.")?
+			.to_bytes();
+		let signatures =
+			serde_json::from_slice(&bytes).wrap_err("Failed to deserialize the response body.")?;
+		Ok(Some(signatures))
+	}
+
+	async fn add_package_signature(
+		&self,
+		user: Option<&User>,
+		dependency: &Dependency,
+		signature: &package::Signature,
+	) -> Result<()> {
+		let dependency = dependency.to_string();
+		let dependency = urlencoding::encode(&dependency);
+		let mut request = http::request::Builder::default()
+			.method(http::Method::POST)
+			.uri(format!("/v1/packages/{dependency}/signatures"));
+		let user = user.or(self.inner.user.as_ref());
+		if let Some(token) = user.and_then(|user| user.token.as_ref()) {
+			request = request.header(http::header::AUTHORIZATION, format!("Bearer {token}"));
+		}
+		let body = serde_json::to_vec(signature).wrap_err("Failed to serialize the body.")?;
+		let request = request
+			.body(full(body))
+			.wrap_err("Failed to create the request.")?;
+		let response = self.send(request).await?;
+		if !response.status().is_success() {
+			return_error!("Expected the response's status to be success.");
+		}
+		Ok(())
+	}
+
 	async fn create_login(&self) -> Result<user::Login> {
 		let request = http::request::Builder::default()
 			.method(http::Method::POST)
@@ -1028,6 +1212,58 @@ impl Handle for Client {
 			serde_json::from_slice(&bytes).wrap_err("Failed to deserialize the response body.")?;
 		Ok(response)
 	}
+
+	async fn list_secrets(&self) -> Result<Vec<String>> {
+		let request = http::request::Builder::default()
+			.method(http::Method::GET)
+			.uri("/v1/secrets")
+			.body(empty())
+			.wrap_err("Failed to create the request.")?;
+		let response = self.send(request).await?;
+		if !response.status().is_success() {
+			return_error!("Expected the response's status to be success.");
+		}
+		let bytes = response
+			.collect()
+			.await
+			.wrap_err("Failed to collect the response body.")?
+			.to_bytes();
+		let response =
+			serde_json::from_slice(&bytes).wrap_err("Failed to deserialize the response body.")?;
+		Ok(response)
+	}
+
+	async fn set_secret(&self, name: &str, value: &str) -> Result<()> {
+		let name = urlencoding::encode(name);
+		let body = serde_json::to_vec(value).wrap_err("Failed to serialize the body.")?;
+		let request = http::request::Builder::default()
+			.method(http::Method::PUT)
+			.uri(format!("/v1/secrets/{name}"))
+			.body(full(body))
+			.wrap_err("Failed to create the request.")?;
+		let response = self.send(request).await?;
+		if !response.status().is_success() {
+			return_error!("Expected the response's status to be success.");
+		}
+		Ok(())
+	}
+
+	async fn remove_secret(&self, name: &str) -> Result<bool> {
+		let name = urlencoding::encode(name);
+		let request = http::request::Builder::default()
+			.method(http::Method::DELETE)
+			.uri(format!("/v1/secrets/{name}"))
+			.body(empty())
+			.wrap_err("Failed to create the request.")?;
+		let response = self.send(request).await?;
+		if response.status() == http::StatusCode::NOT_FOUND {
+			return Ok(false);
+		}
+		if !response.status().is_success() {
+			return_error!("Expected the response's status to be success.");
+		}
+		Ok(true)
+	}
 }
 
 impl Addr {
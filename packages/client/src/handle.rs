@@ -1,6 +1,6 @@
 use crate::{
-	artifact, build, directory, lock, object, package, status, system, target, user, Dependency,
-	Id, User,
+	artifact, blob, build, directory, lock, object, package, status, system, target, user,
+	Checksum, Dependency, Id, User,
 };
 use async_trait::async_trait;
 use bytes::Bytes;
@@ -44,6 +44,10 @@ pub trait Handle: Send + Sync + 'static {
 
 	async fn check_out_artifact(&self, id: &artifact::Id, path: &crate::Path) -> Result<()>;
 
+	async fn try_get_blob_for_checksum(&self, checksum: &Checksum) -> Result<Option<blob::Id>>;
+
+	async fn set_blob_for_checksum(&self, checksum: &Checksum, id: &blob::Id) -> Result<()>;
+
 	async fn try_get_build_for_target(&self, id: &target::Id) -> Result<Option<build::Id>>;
 
 	async fn get_or_create_build_for_target(
@@ -52,6 +56,8 @@ pub trait Handle: Send + Sync + 'static {
 		id: &target::Id,
 		depth: u64,
 		retry: build::Retry,
+		inspect: Option<std::net::SocketAddr>,
+		profile: Option<build::ProfileOptions>,
 	) -> Result<build::Id>;
 
 	async fn get_build_from_queue(
@@ -123,7 +129,7 @@ pub trait Handle: Send + Sync + 'static {
 		outcome: build::Outcome,
 	) -> Result<()>;
 
-	async fn search_packages(&self, query: &str) -> Result<Vec<String>>;
+	async fn search_packages(&self, query: &str) -> Result<Vec<package::SearchResult>>;
 
 	async fn get_package(&self, dependency: &Dependency) -> Result<directory::Id> {
 		Ok(self
@@ -187,9 +193,53 @@ pub trait Handle: Send + Sync + 'static {
 
 	async fn publish_package(&self, user: Option<&User>, id: &directory::Id) -> Result<()>;
 
+	async fn get_package_status(&self, dependency: &Dependency) -> Result<package::Status> {
+		Ok(self
+			.try_get_package_status(dependency)
+			.await?
+			.wrap_err("Failed to get the package status.")?)
+	}
+
+	async fn try_get_package_status(
+		&self,
+		dependency: &Dependency,
+	) -> Result<Option<package::Status>>;
+
+	async fn yank_package(
+		&self,
+		user: Option<&User>,
+		dependency: &Dependency,
+		yanked: bool,
+	) -> Result<()>;
+
+	async fn deprecate_package(
+		&self,
+		user: Option<&User>,
+		dependency: &Dependency,
+		message: Option<&str>,
+	) -> Result<()>;
+
+	async fn try_get_package_signatures(
+		&self,
+		dependency: &Dependency,
+	) -> Result<Option<Vec<package::Signature>>>;
+
+	async fn add_package_signature(
+		&self,
+		user: Option<&User>,
+		dependency: &Dependency,
+		signature: &package::Signature,
+	) -> Result<()>;
+
 	async fn create_login(&self) -> Result<user::Login>;
 
 	async fn get_login(&self, id: &Id) -> Result<Option<user::Login>>;
 
 	async fn get_user_for_token(&self, token: &str) -> Result<Option<User>>;
+
+	async fn list_secrets(&self) -> Result<Vec<String>>;
+
+	async fn set_secret(&self, name: &str, value: &str) -> Result<()>;
+
+	async fn remove_secret(&self, name: &str) -> Result<bool>;
 }
@@ -7,6 +7,7 @@ pub use self::{
 };
 use tangram_error::{return_error, Error, Result, Wrap, WrapErr};
 
+mod archive;
 pub mod artifact;
 pub mod blob;
 pub mod branch;
@@ -32,6 +33,8 @@ pub mod symlink;
 pub mod system;
 pub mod target;
 pub mod template;
+#[cfg(any(test, feature = "test"))]
+pub mod test;
 pub mod user;
 pub mod util;
 pub mod value;
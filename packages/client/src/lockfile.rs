@@ -5,6 +5,10 @@ use std::collections::BTreeMap;
 pub struct Lockfile {
 	pub root: usize,
 	pub locks: Vec<Lock>,
+
+	/// The overrides declared by the root package when the lockfile was created.
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	pub overrides: BTreeMap<Dependency, Override>,
 }
 
 #[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd, serde::Deserialize, serde::Serialize)]
@@ -19,3 +23,13 @@ pub struct Entry {
 	pub package: Option<directory::Id>,
 	pub lock: usize,
 }
+
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd, serde::Deserialize, serde::Serialize)]
+pub struct Override {
+	/// The dependency that replaces the overridden dependency.
+	pub replacement: Dependency,
+
+	/// If the replacement is a path, the index of its lock.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub lock: Option<usize>,
+}
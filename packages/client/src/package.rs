@@ -1,6 +1,7 @@
-use crate::{Dependency, Directory, Handle, Lock};
+use crate::{directory, Dependency, Directory, Handle, Lock};
 use async_trait::async_trait;
-use tangram_error::Result;
+use std::collections::BTreeMap;
+use tangram_error::{return_error, Error, Result, WrapErr};
 
 /// The file name of the root module in a package.
 pub const ROOT_MODULE_FILE_NAME: &str = "tangram.tg";
@@ -13,8 +14,58 @@ pub struct Metadata {
 	pub name: Option<String>,
 	pub version: Option<String>,
 	pub description: Option<String>,
+	pub license: Option<String>,
+	pub homepage: Option<String>,
+	pub repository: Option<String>,
+	pub authors: Option<Vec<String>>,
+	pub keywords: Option<Vec<String>>,
+
+	/// Replacements for dependencies anywhere in the package's dependency graph, keyed by `name` or `name@req`. Each replacement is a path or a `name@version`. Overrides are only honored for the root package.
+	pub overrides: Option<BTreeMap<Dependency, Dependency>>,
+}
+
+/// A package that matched a search.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct SearchResult {
+	pub name: String,
+
+	/// The latest published version that is not yanked.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub version: Option<String>,
+
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub description: Option<String>,
+
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub keywords: Vec<String>,
+}
+
+/// The status of a published package.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct Status {
+	/// If the package is deprecated, the message that explains why.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub deprecated: Option<String>,
+
+	/// The yanked versions. New resolutions skip them, but lockfiles that already use them keep working.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub yanked: Vec<String>,
 }
 
+/// A signature of a published package's name, version, and directory ID.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct Signature {
+	/// The signer's hex encoded ed25519 public key.
+	pub public_key: String,
+
+	/// The hex encoded ed25519 signature.
+	pub signature: String,
+}
+
+/// An ed25519 key for signing packages.
+#[derive(Clone)]
+pub struct SigningKey(ed25519_dalek::SigningKey);
+
 #[async_trait]
 pub trait Ext {
 	async fn metadata(&self, tg: &dyn Handle) -> Result<Metadata>;
@@ -34,6 +85,75 @@ pub async fn get_with_lock(tg: &dyn Handle, dependency: &Dependency) -> Result<(
 	Ok((package, lock))
 }
 
+impl Signature {
+	/// Verify that this signature was created by its public key for the package.
+	pub fn verify(&self, name: &str, version: &str, id: &directory::Id) -> Result<()> {
+		let public_key: [u8; 32] = hex::decode(&self.public_key)
+			.ok()
+			.and_then(|bytes| bytes.try_into().ok())
+			.wrap_err("Invalid public key.")?;
+		let public_key =
+			ed25519_dalek::VerifyingKey::from_bytes(&public_key).wrap_err("Invalid public key.")?;
+		let signature: [u8; 64] = hex::decode(&self.signature)
+			.ok()
+			.and_then(|bytes| bytes.try_into().ok())
+			.wrap_err("Invalid signature.")?;
+		let signature = ed25519_dalek::Signature::from_bytes(&signature);
+		public_key
+			.verify_strict(&signature_message(name, version, id), &signature)
+			.wrap_err("The signature is not valid.")?;
+		Ok(())
+	}
+}
+
+impl SigningKey {
+	#[must_use]
+	pub fn generate() -> Self {
+		Self(ed25519_dalek::SigningKey::from_bytes(&rand::random()))
+	}
+
+	/// Get the hex encoded public key.
+	#[must_use]
+	pub fn public_key(&self) -> String {
+		hex::encode(self.0.verifying_key().as_bytes())
+	}
+
+	#[must_use]
+	pub fn sign(&self, name: &str, version: &str, id: &directory::Id) -> Signature {
+		use ed25519_dalek::Signer;
+		let signature = self.0.sign(&signature_message(name, version, id));
+		Signature {
+			public_key: self.public_key(),
+			signature: hex::encode(signature.to_bytes()),
+		}
+	}
+}
+
+impl std::fmt::Display for SigningKey {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", hex::encode(self.0.to_bytes()))
+	}
+}
+
+impl std::str::FromStr for SigningKey {
+	type Err = Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let Some(bytes) = hex::decode(s.trim())
+			.ok()
+			.and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+		else {
+			return_error!("Invalid signing key.");
+		};
+		Ok(Self(ed25519_dalek::SigningKey::from_bytes(&bytes)))
+	}
+}
+
+/// Create the message that is signed for a package. Names and versions cannot contain newlines, so the message is unambiguous.
+fn signature_message(name: &str, version: &str, id: &directory::Id) -> Vec<u8> {
+	format!("tangram package\n{name}\n{version}\n{id}\n").into_bytes()
+}
+
 #[async_trait]
 impl Ext for Directory {
 	async fn metadata(&self, tg: &dyn Handle) -> Result<Metadata> {
@@ -50,3 +170,24 @@ impl Ext for Directory {
 		Ok(dependencies)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn sign_and_verify() {
+		let id: directory::Id = crate::Id::new_hashed(crate::id::Kind::Directory, b"package")
+			.try_into()
+			.unwrap();
+		let key = SigningKey::generate();
+		let signature = key.sign("foo", "1.0.0", &id);
+		assert!(signature.verify("foo", "1.0.0", &id).is_ok());
+		assert!(signature.verify("foo", "1.0.1", &id).is_err());
+		assert!(signature.verify("bar", "1.0.0", &id).is_err());
+
+		// The key survives a round trip through its string representation.
+		let key: SigningKey = key.to_string().parse().unwrap();
+		assert_eq!(key.sign("foo", "1.0.0", &id), signature);
+	}
+}
@@ -57,6 +57,25 @@ pub struct Object {
 
 	/// If a checksum of the target's output is provided, then the target will have access to the network.
 	pub checksum: Option<Checksum>,
+
+	/// If provided, the target's output will be checked for references to host paths.
+	pub purity: Option<Purity>,
+
+	/// Artifacts to mount at paths in the target's sandbox.
+	pub mounts: Vec<Mount>,
+
+	/// The names of secrets to provide to the target as environment variables. Only the names are part of the target. The values are resolved by the server that builds the target.
+	pub secrets: Vec<String>,
+}
+
+/// A mount of an artifact at a path in a target's sandbox. Mounts are read-only.
+#[derive(Clone, Debug)]
+pub struct Mount {
+	/// The artifact to mount.
+	pub artifact: Artifact,
+
+	/// The absolute path to mount the artifact at.
+	pub path: crate::Path,
 }
 
 /// Target data.
@@ -74,6 +93,46 @@ pub struct Data {
 	pub args: Vec<value::Data>,
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub checksum: Option<Checksum>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub purity: Option<Purity>,
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub mounts: Vec<data::Mount>,
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub secrets: Vec<String>,
+}
+
+pub mod data {
+	use crate::artifact;
+
+	#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+	pub struct Mount {
+		pub artifact: artifact::Id,
+		pub path: crate::Path,
+	}
+}
+
+/// The configuration of the check of a target's output for references to host paths.
+#[derive(Clone, Debug, Default, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Purity {
+	/// What to do when the output contains a forbidden path.
+	#[serde(default)]
+	pub mode: PurityMode,
+
+	/// Additional prefixes that must not appear in the output.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub prefixes: Vec<String>,
+}
+
+/// What to do when a target's output contains a forbidden path.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PurityMode {
+	/// Add a warning to the build's log.
+	#[default]
+	Warn,
+
+	/// Fail the build.
+	Fail,
 }
 
 impl Id {
@@ -196,6 +255,15 @@ impl Target {
 			.try_collect()
 			.await?;
 		let checksum = object.checksum.clone();
+		let purity = object.purity.clone();
+		let mounts = object
+			.mounts
+			.iter()
+			.map(|mount| mount.data(tg))
+			.collect::<FuturesOrdered<_>>()
+			.try_collect()
+			.await?;
+		let secrets = object.secrets.clone();
 		Ok(Data {
 			host,
 			executable,
@@ -204,6 +272,9 @@ impl Target {
 			env,
 			args,
 			checksum,
+			purity,
+			mounts,
+			secrets,
 		})
 	}
 }
@@ -237,6 +308,18 @@ impl Target {
 		Ok(&self.object(tg).await?.checksum)
 	}
 
+	pub async fn purity(&self, tg: &dyn Handle) -> Result<&Option<Purity>> {
+		Ok(&self.object(tg).await?.purity)
+	}
+
+	pub async fn mounts(&self, tg: &dyn Handle) -> Result<&Vec<Mount>> {
+		Ok(&self.object(tg).await?.mounts)
+	}
+
+	pub async fn secrets(&self, tg: &dyn Handle) -> Result<&Vec<String>> {
+		Ok(&self.object(tg).await?.secrets)
+	}
+
 	pub async fn package(&self, tg: &dyn Handle) -> Result<Option<&Directory>> {
 		let object = &self.object(tg).await?;
 		let Artifact::Symlink(symlink) = &object.executable else {
@@ -260,13 +343,22 @@ impl Target {
 	) -> Result<Build> {
 		let target_id = self.id(tg).await?;
 		let build_id = tg
-			.get_or_create_build_for_target(user, target_id, depth, retry)
+			.get_or_create_build_for_target(user, target_id, depth, retry, None, None)
 			.await?;
 		let build = Build::with_id(build_id);
 		Ok(build)
 	}
 }
 
+impl Mount {
+	pub async fn data(&self, tg: &dyn Handle) -> Result<data::Mount> {
+		Ok(data::Mount {
+			artifact: self.artifact.id(tg).await?,
+			path: self.path.clone(),
+		})
+	}
+}
+
 impl Data {
 	pub fn serialize(&self) -> Result<Bytes> {
 		serde_json::to_vec(self)
@@ -285,6 +377,11 @@ impl Data {
 			.chain(self.lock.clone().map(Into::into))
 			.chain(self.env.values().flat_map(value::Data::children))
 			.chain(self.args.iter().flat_map(value::Data::children))
+			.chain(
+				self.mounts
+					.iter()
+					.map(|mount| mount.artifact.clone().into()),
+			)
 			.collect()
 	}
 }
@@ -305,10 +402,22 @@ impl TryFrom<Data> for Object {
 				.try_collect()?,
 			args: data.args.into_iter().map(TryInto::try_into).try_collect()?,
 			checksum: data.checksum,
+			purity: data.purity,
+			mounts: data.mounts.into_iter().map(Into::into).collect(),
+			secrets: data.secrets,
 		})
 	}
 }
 
+impl From<data::Mount> for Mount {
+	fn from(value: data::Mount) -> Self {
+		Self {
+			artifact: Artifact::with_id(value.artifact),
+			path: value.path,
+		}
+	}
+}
+
 impl std::fmt::Display for Target {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		write!(f, "{}", self.state.read().unwrap().id().as_ref().unwrap())?;
@@ -350,6 +459,9 @@ pub struct Builder {
 	env: BTreeMap<String, Value>,
 	args: Vec<Value>,
 	checksum: Option<Checksum>,
+	purity: Option<Purity>,
+	mounts: Vec<Mount>,
+	secrets: Vec<String>,
 }
 
 impl Builder {
@@ -363,6 +475,9 @@ impl Builder {
 			env: BTreeMap::new(),
 			args: Vec::new(),
 			checksum: None,
+			purity: None,
+			mounts: Vec::new(),
+			secrets: Vec::new(),
 		}
 	}
 
@@ -408,6 +523,24 @@ impl Builder {
 		self
 	}
 
+	#[must_use]
+	pub fn purity(mut self, purity: Option<Purity>) -> Self {
+		self.purity = purity;
+		self
+	}
+
+	#[must_use]
+	pub fn mounts(mut self, mounts: Vec<Mount>) -> Self {
+		self.mounts = mounts;
+		self
+	}
+
+	#[must_use]
+	pub fn secrets(mut self, secrets: Vec<String>) -> Self {
+		self.secrets = secrets;
+		self
+	}
+
 	#[must_use]
 	pub fn build(self) -> Target {
 		Target::with_object(Object {
@@ -418,6 +551,9 @@ impl Builder {
 			env: self.env,
 			args: self.args,
 			checksum: self.checksum,
+			purity: self.purity,
+			mounts: self.mounts,
+			secrets: self.secrets,
 		})
 	}
 }
@@ -0,0 +1,313 @@
+use crate::{
+	artifact, blob, build, directory, lock, object, package, status, system, target, user,
+	Artifact, Checksum, Client, Dependency, Directory, File, Handle, Id, Leaf, User,
+};
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use std::{collections::HashMap, sync::Arc};
+use tangram_error::Result;
+
+/// Create a client that never connects, which is enough for artifacts whose objects are in memory.
+#[must_use]
+pub fn client() -> Client {
+	crate::client::Builder::new(crate::client::Addr::Unix("/dev/null".into())).build()
+}
+
+#[must_use]
+pub fn file(contents: &str, executable: bool) -> Artifact {
+	let contents = Leaf::with_object(crate::leaf::Object {
+		bytes: contents.to_owned().into(),
+	});
+	File::new(contents.into(), executable, vec![]).into()
+}
+
+#[must_use]
+pub fn directory(entries: Vec<(&str, Artifact)>) -> Artifact {
+	let entries = entries
+		.into_iter()
+		.map(|(name, artifact)| (name.to_owned(), artifact))
+		.collect();
+	Directory::new(entries).into()
+}
+
+/// A handle that stores objects in memory and checks in artifacts locally, which is enough to archive and extract artifacts without a server.
+#[derive(Clone, Default)]
+pub struct Store {
+	inner: Arc<StoreInner>,
+}
+
+struct StoreInner {
+	objects: std::sync::Mutex<HashMap<Id, Bytes>>,
+	file_descriptor_semaphore: tokio::sync::Semaphore,
+}
+
+impl Default for StoreInner {
+	fn default() -> Self {
+		Self {
+			objects: std::sync::Mutex::default(),
+			file_descriptor_semaphore: tokio::sync::Semaphore::new(16),
+		}
+	}
+}
+
+#[async_trait]
+impl Handle for Store {
+	fn clone_box(&self) -> Box<dyn Handle> {
+		Box::new(self.clone())
+	}
+
+	fn file_descriptor_semaphore(&self) -> &tokio::sync::Semaphore {
+		&self.inner.file_descriptor_semaphore
+	}
+
+	async fn stop(&self) -> Result<()> {
+		unimplemented!()
+	}
+
+	async fn status(&self) -> Result<status::Status> {
+		unimplemented!()
+	}
+
+	async fn clean(&self) -> Result<()> {
+		unimplemented!()
+	}
+
+	async fn get_object_exists(&self, id: &object::Id) -> Result<bool> {
+		Ok(self
+			.inner
+			.objects
+			.lock()
+			.unwrap()
+			.contains_key(&id.clone().into()))
+	}
+
+	async fn try_get_object(&self, id: &object::Id) -> Result<Option<Bytes>> {
+		Ok(self
+			.inner
+			.objects
+			.lock()
+			.unwrap()
+			.get(&id.clone().into())
+			.cloned())
+	}
+
+	async fn try_put_object(
+		&self,
+		id: &object::Id,
+		bytes: &Bytes,
+	) -> Result<Result<(), Vec<object::Id>>> {
+		self.inner
+			.objects
+			.lock()
+			.unwrap()
+			.insert(id.clone().into(), bytes.clone());
+		Ok(Ok(()))
+	}
+
+	async fn push_object(&self, _id: &object::Id) -> Result<()> {
+		unimplemented!()
+	}
+
+	async fn pull_object(&self, _id: &object::Id) -> Result<()> {
+		unimplemented!()
+	}
+
+	async fn check_in_artifact(&self, path: &crate::Path) -> Result<artifact::Id> {
+		Artifact::check_in_local(self, path).await
+	}
+
+	async fn check_out_artifact(&self, _id: &artifact::Id, _path: &crate::Path) -> Result<()> {
+		unimplemented!()
+	}
+
+	async fn try_get_blob_for_checksum(&self, _checksum: &Checksum) -> Result<Option<blob::Id>> {
+		unimplemented!()
+	}
+
+	async fn set_blob_for_checksum(&self, _checksum: &Checksum, _id: &blob::Id) -> Result<()> {
+		unimplemented!()
+	}
+
+	async fn try_get_build_for_target(&self, _id: &target::Id) -> Result<Option<build::Id>> {
+		unimplemented!()
+	}
+
+	async fn get_or_create_build_for_target(
+		&self,
+		_user: Option<&User>,
+		_id: &target::Id,
+		_depth: u64,
+		_retry: build::Retry,
+		_inspect: Option<std::net::SocketAddr>,
+		_profile: Option<build::ProfileOptions>,
+	) -> Result<build::Id> {
+		unimplemented!()
+	}
+
+	async fn get_build_from_queue(
+		&self,
+		_user: Option<&User>,
+		_hosts: Option<Vec<system::System>>,
+	) -> Result<Option<build::queue::Item>> {
+		unimplemented!()
+	}
+
+	async fn try_get_build_target(&self, _id: &build::Id) -> Result<Option<target::Id>> {
+		unimplemented!()
+	}
+
+	async fn try_get_build_children(
+		&self,
+		_id: &build::Id,
+	) -> Result<Option<BoxStream<'static, Result<build::Id>>>> {
+		unimplemented!()
+	}
+
+	async fn add_build_child(
+		&self,
+		_user: Option<&User>,
+		_id: &build::Id,
+		_child_id: &build::Id,
+	) -> Result<()> {
+		unimplemented!()
+	}
+
+	async fn try_get_build_log(
+		&self,
+		_id: &build::Id,
+	) -> Result<Option<BoxStream<'static, Result<Bytes>>>> {
+		unimplemented!()
+	}
+
+	async fn add_build_log(
+		&self,
+		_user: Option<&User>,
+		_id: &build::Id,
+		_bytes: Bytes,
+	) -> Result<()> {
+		unimplemented!()
+	}
+
+	async fn try_get_build_outcome(&self, _id: &build::Id) -> Result<Option<build::Outcome>> {
+		unimplemented!()
+	}
+
+	async fn cancel_build(&self, _user: Option<&User>, _id: &build::Id) -> Result<()> {
+		unimplemented!()
+	}
+
+	async fn finish_build(
+		&self,
+		_user: Option<&User>,
+		_id: &build::Id,
+		_outcome: build::Outcome,
+	) -> Result<()> {
+		unimplemented!()
+	}
+
+	async fn search_packages(&self, _query: &str) -> Result<Vec<package::SearchResult>> {
+		unimplemented!()
+	}
+
+	async fn try_get_package(&self, _dependency: &Dependency) -> Result<Option<directory::Id>> {
+		unimplemented!()
+	}
+
+	async fn try_get_package_and_lock(
+		&self,
+		_dependency: &Dependency,
+	) -> Result<Option<(directory::Id, lock::Id)>> {
+		unimplemented!()
+	}
+
+	async fn try_get_package_versions(
+		&self,
+		_dependency: &Dependency,
+	) -> Result<Option<Vec<String>>> {
+		unimplemented!()
+	}
+
+	async fn try_get_package_metadata(
+		&self,
+		_dependency: &Dependency,
+	) -> Result<Option<package::Metadata>> {
+		unimplemented!()
+	}
+
+	async fn try_get_package_dependencies(
+		&self,
+		_dependency: &Dependency,
+	) -> Result<Option<Vec<Dependency>>> {
+		unimplemented!()
+	}
+
+	async fn publish_package(&self, _user: Option<&User>, _id: &directory::Id) -> Result<()> {
+		unimplemented!()
+	}
+
+	async fn try_get_package_status(
+		&self,
+		_dependency: &Dependency,
+	) -> Result<Option<package::Status>> {
+		unimplemented!()
+	}
+
+	async fn yank_package(
+		&self,
+		_user: Option<&User>,
+		_dependency: &Dependency,
+		_yanked: bool,
+	) -> Result<()> {
+		unimplemented!()
+	}
+
+	async fn deprecate_package(
+		&self,
+		_user: Option<&User>,
+		_dependency: &Dependency,
+		_message: Option<&str>,
+	) -> Result<()> {
+		unimplemented!()
+	}
+
+	async fn try_get_package_signatures(
+		&self,
+		_dependency: &Dependency,
+	) -> Result<Option<Vec<package::Signature>>> {
+		unimplemented!()
+	}
+
+	async fn add_package_signature(
+		&self,
+		_user: Option<&User>,
+		_dependency: &Dependency,
+		_signature: &package::Signature,
+	) -> Result<()> {
+		unimplemented!()
+	}
+
+	async fn create_login(&self) -> Result<user::Login> {
+		unimplemented!()
+	}
+
+	async fn get_login(&self, _id: &Id) -> Result<Option<user::Login>> {
+		unimplemented!()
+	}
+
+	async fn get_user_for_token(&self, _token: &str) -> Result<Option<User>> {
+		unimplemented!()
+	}
+
+	async fn list_secrets(&self) -> Result<Vec<String>> {
+		unimplemented!()
+	}
+
+	async fn set_secret(&self, _name: &str, _value: &str) -> Result<()> {
+		unimplemented!()
+	}
+
+	async fn remove_secret(&self, _name: &str) -> Result<bool> {
+		unimplemented!()
+	}
+}
@@ -14,6 +14,9 @@ pub struct Analysis {
 	pub metadata: Option<tg::package::Metadata>,
 	pub imports: HashSet<Import, fnv::FnvBuildHasher>,
 	pub includes: HashSet<tg::Path, fnv::FnvBuildHasher>,
+
+	/// The byte range of the specifier of each import, in the order they appear.
+	pub import_ranges: Vec<(Import, std::ops::Range<usize>)>,
 }
 
 pub struct Error {
@@ -51,6 +54,7 @@ impl Module {
 			metadata: visitor.metadata,
 			imports: visitor.imports,
 			includes: visitor.includes,
+			import_ranges: visitor.import_ranges,
 		};
 
 		Ok(output)
@@ -86,6 +90,7 @@ struct Visitor {
 	metadata: Option<tg::package::Metadata>,
 	imports: HashSet<Import, fnv::FnvBuildHasher>,
 	includes: HashSet<tg::Path, fnv::FnvBuildHasher>,
+	import_ranges: Vec<(Import, std::ops::Range<usize>)>,
 }
 
 impl Visitor {
@@ -122,8 +127,14 @@ impl swc::ecma::visit::Visit for Visitor {
 				continue;
 			};
 			let metadata = self.object_to_json(object);
-			let Ok(metadata) = serde_json::from_value(metadata) else {
-				continue;
+			let metadata = match serde_json::from_value(metadata) {
+				Ok(metadata) => metadata,
+				Err(error) => {
+					let loc = self.source_map.lookup_char_pos(object.span.lo);
+					self.errors
+						.push(Error::new(format!("Invalid metadata: {error}"), &loc));
+					continue;
+				},
 			};
 			self.metadata = Some(metadata);
 		}
@@ -132,17 +143,17 @@ impl swc::ecma::visit::Visit for Visitor {
 	}
 
 	fn visit_import_decl(&mut self, n: &ast::ImportDecl) {
-		self.add_import(&n.src.value, n.with.as_deref(), n.span);
+		self.add_import(&n.src.value, n.with.as_deref(), n.src.span);
 	}
 
 	fn visit_named_export(&mut self, n: &ast::NamedExport) {
 		if let Some(src) = n.src.as_deref() {
-			self.add_import(&src.value, n.with.as_deref(), n.span);
+			self.add_import(&src.value, n.with.as_deref(), src.span);
 		}
 	}
 
 	fn visit_export_all(&mut self, n: &ast::ExportAll) {
-		self.add_import(&n.src.value, n.with.as_deref(), n.span);
+		self.add_import(&n.src.value, n.with.as_deref(), n.src.span);
 	}
 
 	fn visit_call_expr(&mut self, n: &ast::CallExpr) {
@@ -208,8 +219,12 @@ impl swc::ecma::visit::Visit for Visitor {
 					));
 					return;
 				};
-				let with = n.args.get(1).and_then(|arg| arg.expr.as_object());
-				self.add_import(&arg.value, with, n.span);
+				let with = n
+					.args
+					.get(1)
+					.and_then(|arg| arg.expr.as_object())
+					.and_then(import_options_attributes);
+				self.add_import(&arg.value, with, arg.span);
 			},
 
 			// Ignore a call to super.
@@ -274,7 +289,15 @@ impl Visitor {
 			return;
 		};
 
-		// Add the import.
+		// If the import is of a file, then add it to the includes so that it is checked in with the package.
+		if let Import::File { path, .. } = &import {
+			self.includes.insert(path.clone());
+		}
+
+		// Add the import and the byte range of its specifier.
+		let start = self.source_map.lookup_byte_offset(span.lo()).pos.0 as usize;
+		let end = self.source_map.lookup_byte_offset(span.hi()).pos.0 as usize;
+		self.import_ranges.push((import.clone(), start..end));
 		self.imports.insert(import);
 	}
 
@@ -300,34 +323,82 @@ impl Visitor {
 					continue;
 				},
 			};
-			let value = match key_value.value.as_ref() {
-				ast::Expr::Lit(ast::Lit::Null(_)) => serde_json::Value::Null,
-				ast::Expr::Lit(ast::Lit::Bool(value)) => serde_json::Value::Bool(value.value),
-				ast::Expr::Lit(ast::Lit::Num(value)) => {
-					let Some(value) = serde_json::Number::from_f64(value.value) else {
-						self.errors.push(Error::new("Invalid number.", &loc));
-						continue;
-					};
-					serde_json::Value::Number(value)
-				},
-				ast::Expr::Lit(ast::Lit::Str(value)) => {
-					serde_json::Value::String(value.value.to_string())
-				},
-				_ => {
-					self.errors
-						.push(Error::new("Values must be valid JSON.", &loc));
-					continue;
-				},
+			let Some(value) = self.expr_to_json(&key_value.value, &loc) else {
+				continue;
 			};
 			output.insert(key, value);
 		}
 		serde_json::Value::Object(output)
 	}
+
+	fn expr_to_json(
+		&mut self,
+		expr: &ast::Expr,
+		loc: &swc::common::Loc,
+	) -> Option<serde_json::Value> {
+		let value = match expr {
+			ast::Expr::Lit(ast::Lit::Null(_)) => serde_json::Value::Null,
+			ast::Expr::Lit(ast::Lit::Bool(value)) => serde_json::Value::Bool(value.value),
+			ast::Expr::Lit(ast::Lit::Num(value)) => {
+				let Some(value) = serde_json::Number::from_f64(value.value) else {
+					self.errors.push(Error::new("Invalid number.", loc));
+					return None;
+				};
+				serde_json::Value::Number(value)
+			},
+			ast::Expr::Lit(ast::Lit::Str(value)) => {
+				serde_json::Value::String(value.value.to_string())
+			},
+			ast::Expr::Array(array) => {
+				let mut output = Vec::new();
+				for element in &array.elems {
+					let Some(element) = element else {
+						self.errors
+							.push(Error::new("Array holes are not allowed.", loc));
+						continue;
+					};
+					if element.spread.is_some() {
+						self.errors
+							.push(Error::new("Spread elements are not allowed.", loc));
+						continue;
+					}
+					if let Some(value) = self.expr_to_json(&element.expr, loc) {
+						output.push(value);
+					}
+				}
+				serde_json::Value::Array(output)
+			},
+			ast::Expr::Object(object) => self.object_to_json(object),
+			_ => {
+				self.errors
+					.push(Error::new("Values must be valid JSON.", loc));
+				return None;
+			},
+		};
+		Some(value)
+	}
+}
+
+/// Get the attributes from the options of a dynamic import, such as `{ with: { type: "json" } }`.
+fn import_options_attributes(options: &ast::ObjectLit) -> Option<&ast::ObjectLit> {
+	options.props.iter().find_map(|prop| {
+		let key_value = prop.as_prop()?.as_key_value()?;
+		let key = match &key_value.key {
+			ast::PropName::Ident(ident) => ident.sym.as_ref(),
+			ast::PropName::Str(value) => value.value.as_ref(),
+			_ => return None,
+		};
+		if key != "with" {
+			return None;
+		}
+		key_value.value.as_object()
+	})
 }
 
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::import;
 
 	#[test]
 	fn test_analyze() {
@@ -371,6 +442,83 @@ mod tests {
 			metadata: Some(metadata),
 			imports,
 			includes,
+			import_ranges: left.import_ranges.clone(),
+		};
+		assert_eq!(left, right);
+
+		// Verify that the import ranges cover the specifiers.
+		let (import, range) = &left.import_ranges[0];
+		assert_eq!(import, &"tg:default_import".parse().unwrap());
+		assert_eq!(&text[range.clone()], r#""tg:default_import""#);
+	}
+
+	#[test]
+	fn test_analyze_metadata() {
+		let text = r#"
+			export let metadata = {
+				name: "name",
+				version: "1.0.0",
+				description: "description",
+				license: "MIT",
+				homepage: "https://example.com",
+				repository: "https://example.com/repository",
+				authors: ["Author <author@example.com>"],
+				keywords: ["first", "second"],
+			};
+		"#;
+		let left = Module::analyze(text.to_owned()).unwrap().metadata;
+		let right = Some(tg::package::Metadata {
+			name: Some("name".to_owned()),
+			version: Some("1.0.0".to_owned()),
+			description: Some("description".to_owned()),
+			license: Some("MIT".to_owned()),
+			homepage: Some("https://example.com".to_owned()),
+			repository: Some("https://example.com/repository".to_owned()),
+			authors: Some(vec!["Author <author@example.com>".to_owned()]),
+			keywords: Some(vec!["first".to_owned(), "second".to_owned()]),
+			overrides: None,
+		});
+		assert_eq!(left, right);
+	}
+
+	#[test]
+	fn test_analyze_invalid_metadata() {
+		let text = r#"
+			export let metadata = {
+				name: ["name"],
+			};
+		"#;
+		assert!(Module::analyze(text.to_owned()).is_err());
+	}
+
+	#[test]
+	fn test_analyze_file_imports() {
+		let text = r#"
+			import data from "./data.json" with { type: "json" };
+			import script from "./script.sh" with { type: "text" };
+			let image = import("./image.png", { with: { type: "bytes" } });
+		"#;
+		let left = Module::analyze(text.to_owned()).unwrap();
+		let imports = [
+			("./data.json", import::Kind::Json),
+			("./script.sh", import::Kind::Text),
+			("./image.png", import::Kind::Bytes),
+		]
+		.into_iter()
+		.map(|(path, kind)| Import::File {
+			path: path.parse().unwrap(),
+			kind,
+		})
+		.collect();
+		let includes = ["./data.json", "./script.sh", "./image.png"]
+			.into_iter()
+			.map(|include| include.parse().unwrap())
+			.collect();
+		let right = Analysis {
+			metadata: None,
+			imports,
+			includes,
+			import_ranges: left.import_ranges.clone(),
 		};
 		assert_eq!(left, right);
 	}
@@ -1,8 +1,16 @@
-use crate::{return_error, send_notification, Diagnostic, Module, Sender, Server};
+use crate::{
+	diagnostic::Severity, return_error, send_notification, Diagnostic, Import, Location, Module,
+	Range, Sender, Server,
+};
+use futures::future;
 use lsp_types as lsp;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
+use tangram_client as tg;
 use tangram_error::Result;
 
+/// How long the status of a package is cached before it is retrieved again.
+const PACKAGE_STATUS_TTL: std::time::Duration = std::time::Duration::from_secs(300);
+
 #[derive(Debug, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Request {}
@@ -69,8 +77,160 @@ impl Server {
 		};
 
 		// Get the result the response.
-		let Response { diagnostics } = response;
+		let Response { mut diagnostics } = response;
+
+		// Add the diagnostics for deprecated packages and yanked versions.
+		diagnostics.extend(self.package_diagnostics());
 
 		Ok(diagnostics)
 	}
+
+	/// Get the most recent warnings for the dependencies of the documents that are deprecated or whose locked versions have been yanked, and start refreshing them in the background. Looking up the packages requires network requests, so the refreshed warnings are published with the next diagnostics.
+	fn package_diagnostics(&self) -> Vec<Diagnostic> {
+		let mut state = self.inner.package_diagnostics.lock().unwrap();
+		if !state.refreshing {
+			state.refreshing = true;
+			let server = self.clone();
+			self.inner.main_runtime_handle.spawn(async move {
+				let diagnostics = server.refresh_package_diagnostics().await;
+				let mut state = server.inner.package_diagnostics.lock().unwrap();
+				state.diagnostics = diagnostics;
+				state.refreshing = false;
+			});
+		}
+		state.diagnostics.clone()
+	}
+
+	async fn refresh_package_diagnostics(&self) -> Vec<Diagnostic> {
+		let store = &self.inner.document_store;
+		let mut diagnostics = Vec::new();
+		for document in store.documents().await {
+			// Analyze the document.
+			let Ok(text) = document.text(store).await else {
+				continue;
+			};
+			let Ok(analysis) = Module::analyze(text.clone()) else {
+				continue;
+			};
+			let module = Module::Document(document);
+
+			// Get the messages for each registry dependency concurrently.
+			let messages =
+				future::join_all(analysis.import_ranges.iter().map(|(import, range)| {
+					let module = &module;
+					async move {
+						let messages = self.package_messages(module, import).await;
+						(range.clone(), messages)
+					}
+				}))
+				.await;
+
+			// Create the diagnostics at the imports' specifiers.
+			for (range, messages) in messages {
+				let range = Range::from_byte_range_in_string(&text, range);
+				for message in messages {
+					diagnostics.push(Diagnostic {
+						location: Some(Location {
+							module: module.clone(),
+							range,
+						}),
+						severity: Severity::Warning,
+						message,
+					});
+				}
+			}
+		}
+		diagnostics
+	}
+
+	/// Get the warnings for an import of a registry dependency.
+	async fn package_messages(&self, module: &Module, import: &Import) -> Vec<String> {
+		// Get the registry dependency.
+		let Import::Dependency(dependency) = import else {
+			return Vec::new();
+		};
+		let (None, Some(name)) = (&dependency.path, &dependency.name) else {
+			return Vec::new();
+		};
+
+		// Get the locked version and the package's status.
+		let version = self.locked_version(module, import).await;
+		let Some(status) = self.package_status(name, version.as_deref()).await else {
+			return Vec::new();
+		};
+
+		// Create the messages.
+		let mut messages = Vec::new();
+		if let Some(message) = status.deprecated {
+			messages.push(format!(r#"The package "{name}" is deprecated: {message}"#));
+		}
+		if let Some(version) = version.filter(|version| status.yanked.contains(version)) {
+			messages.push(format!(
+				r#"The version "{version}" of "{name}" has been yanked."#
+			));
+		}
+		messages
+	}
+
+	/// Get the status of a package, using the cached status for `name@version` if it is recent. Packages whose status cannot be retrieved are skipped.
+	async fn package_status(
+		&self,
+		name: &str,
+		version: Option<&str>,
+	) -> Option<tg::package::Status> {
+		let key = match version {
+			Some(version) => format!("{name}@{version}"),
+			None => name.to_owned(),
+		};
+		{
+			let state = self.inner.package_diagnostics.lock().unwrap();
+			if let Some((time, status)) = state.statuses.get(&key) {
+				if time.elapsed() < PACKAGE_STATUS_TTL {
+					return status.clone();
+				}
+			}
+		}
+		let status = self
+			.inner
+			.tg
+			.try_get_package_status(&tg::Dependency::with_name(name.to_owned()))
+			.await
+			.ok()
+			.flatten();
+		self.inner
+			.package_diagnostics
+			.lock()
+			.unwrap()
+			.statuses
+			.insert(key, (std::time::Instant::now(), status.clone()));
+		status
+	}
+
+	/// Get the version that an import of a dependency is locked to.
+	async fn locked_version(&self, module: &Module, import: &Import) -> Option<String> {
+		let tg = self.inner.tg.as_ref();
+		let resolved = module
+			.resolve(tg, Some(&self.inner.document_store), import)
+			.await
+			.ok()?;
+		let Module::Normal(normal) = resolved else {
+			return None;
+		};
+		let dependency = tg::Dependency::with_id(normal.package);
+		let metadata = tg.try_get_package_metadata(&dependency).await.ok()??;
+		metadata.version
+	}
+}
+
+/// The cached warnings for the documents' dependencies.
+#[derive(Debug, Default)]
+pub(crate) struct PackageDiagnostics {
+	/// The warnings from the most recent refresh.
+	diagnostics: Vec<Diagnostic>,
+
+	/// Whether a refresh is in progress.
+	refreshing: bool,
+
+	/// The statuses of packages keyed by `name@version`, with the time each was retrieved.
+	statuses: HashMap<String, (std::time::Instant, Option<tg::package::Status>)>,
 }
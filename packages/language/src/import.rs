@@ -11,6 +11,25 @@ pub enum Import {
 
 	/// An import of a dependency, such as `import "tg:std"`.
 	Dependency(tg::Dependency),
+
+	/// An import of a file with a type attribute, such as `import data from "./data.json" with { type: "json" }`.
+	File { path: tg::Path, kind: Kind },
+}
+
+/// The kind of an imported file, which is specified by the import's type attribute.
+#[derive(
+	Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, serde::Deserialize, serde::Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Kind {
+	/// A JSON file, whose default export is the parsed value.
+	Json,
+
+	/// A UTF-8 text file, whose default export is a string.
+	Text,
+
+	/// A file whose default export is a `Uint8Array` of its bytes.
+	Bytes,
 }
 
 impl Import {
@@ -18,6 +37,18 @@ impl Import {
 		specifier: &str,
 		attributes: Option<&BTreeMap<String, String>>,
 	) -> Result<Self> {
+		// If the import has a type attribute, then it is an import of a file.
+		if let Some(kind) = attributes.and_then(|attributes| attributes.get("type")) {
+			let kind = kind.parse()?;
+			if !specifier.starts_with('.') {
+				return_error!(
+					r#"The import "{specifier}" has a type attribute, but is not a relative path."#
+				);
+			}
+			let path = specifier.parse()?;
+			return Ok(Self::File { path, kind });
+		}
+
 		// Parse the specifier.
 		let import = specifier.parse()?;
 
@@ -25,6 +56,7 @@ impl Import {
 		let import = if let Some(attributes) = attributes {
 			match import {
 				Self::Module(module) => Self::Module(module),
+				Self::File { path, kind } => Self::File { path, kind },
 				Self::Dependency(mut dependency) => {
 					let attributes = attributes
 						.iter()
@@ -47,7 +79,7 @@ impl Import {
 impl std::fmt::Display for Import {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		match self {
-			Import::Module(path) => {
+			Import::Module(path) | Import::File { path, .. } => {
 				write!(f, "{path}")?;
 			},
 
@@ -59,6 +91,29 @@ impl std::fmt::Display for Import {
 	}
 }
 
+impl std::fmt::Display for Kind {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Kind::Json => write!(f, "json"),
+			Kind::Text => write!(f, "text"),
+			Kind::Bytes => write!(f, "bytes"),
+		}
+	}
+}
+
+impl std::str::FromStr for Kind {
+	type Err = Error;
+
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		match value {
+			"json" => Ok(Kind::Json),
+			"text" => Ok(Kind::Text),
+			"bytes" => Ok(Kind::Bytes),
+			_ => return_error!(r#"Invalid import type "{value}"."#),
+		}
+	}
+}
+
 impl std::str::FromStr for Import {
 	type Err = Error;
 
@@ -78,6 +78,9 @@ struct Inner {
 
 	/// The workspaces.
 	workspaces: Arc<tokio::sync::RwLock<BTreeSet<PathBuf>>>,
+
+	/// The cached warnings for the documents' dependencies.
+	package_diagnostics: std::sync::Mutex<diagnostics::PackageDiagnostics>,
 }
 
 #[derive(Debug, serde::Serialize)]
@@ -139,6 +142,7 @@ impl Server {
 			request_sender,
 			main_runtime_handle,
 			workspaces,
+			package_diagnostics: std::sync::Mutex::default(),
 		});
 
 		// Spawn a thread to handle requests.
@@ -246,6 +250,7 @@ impl Server {
 				let path = path.display();
 				format!("file://{path}").parse().unwrap()
 			},
+			Module::File(file) => self.url_for_module(&file.module),
 			_ => module.clone().into(),
 		}
 	}
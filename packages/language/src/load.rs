@@ -1,7 +1,8 @@
-use super::{document, Module};
+use super::{document, import, Module};
+use base64::Engine as _;
 use include_dir::include_dir;
 use tangram_client as tg;
-use tangram_error::{Result, WrapErr};
+use tangram_error::{return_error, Result, WrapErr};
 
 const TANGRAM_D_TS: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/tangram.d.ts"));
 const LIB: include_dir::Dir = include_dir!("$CARGO_MANIFEST_DIR/src/lib");
@@ -48,6 +49,76 @@ impl Module {
 
 				Ok(text)
 			},
+
+			// Load a file as a module whose default export is the file's contents.
+			Self::File(file) => {
+				let bytes = file.module.load_bytes(tg).await?;
+				module_text_for_file(file.kind, bytes)
+			},
+		}
+	}
+
+	/// Load the bytes of a document or normal module's file.
+	async fn load_bytes(&self, tg: &dyn tg::Handle) -> Result<Vec<u8>> {
+		match self {
+			Self::Document(document) => {
+				let path = document.path();
+				tokio::fs::read(&path).await.wrap_err_with(|| {
+					let path = path.display();
+					format!(r#"Failed to read the file at path "{path}"."#)
+				})
+			},
+			Self::Normal(module) => {
+				let package = tg::Directory::with_id(module.package.clone());
+				let entry = package.get(tg, &module.path).await?;
+				let file = entry
+					.try_unwrap_file_ref()
+					.ok()
+					.wrap_err("Expected a file.")?;
+				file.bytes(tg).await
+			},
+			_ => return_error!("Expected a document or normal module."),
 		}
 	}
 }
+
+/// Create the text of a module whose default export is a file's contents.
+fn module_text_for_file(kind: import::Kind, bytes: Vec<u8>) -> Result<String> {
+	let text = match kind {
+		import::Kind::Json => {
+			let text = String::from_utf8(bytes).wrap_err("The file is not valid UTF-8.")?;
+			serde_json::from_str::<serde_json::Value>(&text)
+				.wrap_err("Failed to parse the file as JSON.")?;
+			let text = serde_json::to_string(&text).unwrap();
+			format!("export default JSON.parse({text});\n")
+		},
+		import::Kind::Text => {
+			let text = String::from_utf8(bytes).wrap_err("The file is not valid UTF-8.")?;
+			let text = serde_json::to_string(&text).unwrap();
+			format!("export default {text} as string;\n")
+		},
+		import::Kind::Bytes => {
+			let data = base64::engine::general_purpose::STANDARD.encode(bytes);
+			format!("export default tg.encoding.base64.decode(\"{data}\");\n")
+		},
+	};
+	Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn json_module_text() {
+		// A file that is not only JSON is rejected.
+		let json = "{}; globalThis.x = 1";
+		assert!(module_text_for_file(import::Kind::Json, json.into()).is_err());
+
+		// The JSON is embedded as a string and parsed at runtime.
+		let json = "{ \"a\": \"b\" }\n";
+		let text = module_text_for_file(import::Kind::Json, json.into()).unwrap();
+		let expected = r#"export default JSON.parse("{ \"a\": \"b\" }\n");"#;
+		assert_eq!(text, format!("{expected}\n"));
+	}
+}
@@ -1,4 +1,4 @@
-use super::document::Document;
+use super::{document::Document, import};
 use derive_more::{TryUnwrap, Unwrap};
 use tangram_client as tg;
 use tangram_error::{return_error, Error, Result, WrapErr};
@@ -30,6 +30,9 @@ pub enum Module {
 
 	/// A normal module.
 	Normal(Normal),
+
+	/// A file imported with a type attribute.
+	File(File),
 }
 
 #[derive(
@@ -56,6 +59,31 @@ pub struct Normal {
 	pub path: tg::Path,
 }
 
+#[derive(
+	Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, serde::Deserialize, serde::Serialize,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct File {
+	/// The file's kind.
+	pub kind: import::Kind,
+
+	/// The file's location, which is a document or normal module with the file's path.
+	pub module: Box<Module>,
+}
+
+impl Module {
+	/// Get the module's path.
+	#[must_use]
+	pub fn path(&self) -> &tg::Path {
+		match self {
+			Module::Library(library) => &library.path,
+			Module::Document(document) => &document.path,
+			Module::Normal(normal) => &normal.path,
+			Module::File(file) => file.module.path(),
+		}
+	}
+}
+
 impl From<Module> for Url {
 	fn from(value: Module) -> Self {
 		// Serialize and encode the module.
@@ -67,6 +95,7 @@ impl From<Module> for Url {
 				format!("{}/{}", document.package_path.display(), document.path)
 			},
 			Module::Normal(normal) => normal.path.to_string(),
+			Module::File(file) => file.module.path().to_string(),
 		};
 
 		// Create the URL.
@@ -1,7 +1,7 @@
 use super::ROOT_MODULE_FILE_NAME;
 use crate::{
 	document,
-	module::{Library, Normal},
+	module::{File, Library, Normal},
 	Document, Import, Module,
 };
 use tangram_client as tg;
@@ -17,45 +17,14 @@ impl Module {
 		import: &Import,
 	) -> Result<Self> {
 		match (self, import) {
-			(Self::Library(module), Import::Module(path)) => {
-				let path = module.path.clone().parent().join(path.clone()).normalize();
-				Ok(Self::Library(Library { path }))
+			(Self::Library(_) | Self::Document(_) | Self::Normal(_), Import::Module(path)) => {
+				self.resolve_path(document_store, path).await
 			},
 
 			(Self::Library(_), Import::Dependency(_)) => Err(error!(
 				r#"Cannot resolve a dependency import from a library module."#
 			)),
 
-			(Self::Document(document), Import::Module(path)) => {
-				// Resolve the module path.
-				let package_path = document.package_path.clone();
-				let module_path = document
-					.path
-					.clone()
-					.parent()
-					.join(path.clone())
-					.normalize();
-
-				// Ensure that the module exists.
-				let module_absolute_path = package_path.join(module_path.to_string());
-				let exists = tokio::fs::try_exists(&module_absolute_path)
-					.await
-					.wrap_err("Failed to determine if the path exists.")?;
-				if !exists {
-					let path = module_absolute_path.display();
-					return_error!(r#"Could not find a module at path "{path}"."#);
-				}
-
-				// Create the document.
-				let document =
-					Document::new(document_store.unwrap(), package_path, module_path).await?;
-
-				// Create the module.
-				let module = Self::Document(document);
-
-				Ok(module)
-			},
-
 			(Self::Document(document), Import::Dependency(dependency))
 				if dependency.path.is_some() =>
 			{
@@ -119,15 +88,6 @@ impl Module {
 				Ok(module)
 			},
 
-			(Self::Normal(module), Import::Module(path)) => {
-				let path = module.path.clone().parent().join(path.clone()).normalize();
-				Ok(Self::Normal(Normal {
-					package: module.package.clone(),
-					path,
-					lock: module.lock.clone(),
-				}))
-			},
-
 			(Self::Normal(module), Import::Dependency(dependency)) => {
 				// Make the dependency path relative to the package.
 				let mut dependency = dependency.clone();
@@ -154,6 +114,80 @@ impl Module {
 
 				Ok(module)
 			},
+
+			(Self::Library(_), Import::File { .. }) => Err(error!(
+				r#"Cannot resolve a file import from a library module."#
+			)),
+
+			(Self::Document(_) | Self::Normal(_), Import::File { path, kind }) => {
+				// Resolve the file's location the same way as a module's.
+				let module = self.resolve_path(document_store, path).await?;
+
+				// Create the module.
+				let module = Self::File(File {
+					kind: *kind,
+					module: Box::new(module),
+				});
+
+				Ok(module)
+			},
+
+			(Self::File(_), _) => Err(error!(r#"Cannot resolve an import from a file."#)),
+		}
+	}
+
+	/// Resolve a path relative to a module.
+	async fn resolve_path(
+		&self,
+		document_store: Option<&document::Store>,
+		path: &tg::Path,
+	) -> Result<Self> {
+		match self {
+			Self::Library(module) => {
+				let path = module.path.clone().parent().join(path.clone()).normalize();
+				Ok(Self::Library(Library { path }))
+			},
+
+			Self::Document(document) => {
+				// Resolve the module path.
+				let package_path = document.package_path.clone();
+				let module_path = document
+					.path
+					.clone()
+					.parent()
+					.join(path.clone())
+					.normalize();
+
+				// Ensure that the module exists.
+				let module_absolute_path = package_path.join(module_path.to_string());
+				let exists = tokio::fs::try_exists(&module_absolute_path)
+					.await
+					.wrap_err("Failed to determine if the path exists.")?;
+				if !exists {
+					let path = module_absolute_path.display();
+					return_error!(r#"Could not find a module at path "{path}"."#);
+				}
+
+				// Create the document.
+				let document =
+					Document::new(document_store.unwrap(), package_path, module_path).await?;
+
+				// Create the module.
+				let module = Self::Document(document);
+
+				Ok(module)
+			},
+
+			Self::Normal(module) => {
+				let path = module.path.clone().parent().join(path.clone()).normalize();
+				Ok(Self::Normal(Normal {
+					package: module.package.clone(),
+					path,
+					lock: module.lock.clone(),
+				}))
+			},
+
+			Self::File(_) => Err(error!(r#"Cannot resolve a path from a file."#)),
 		}
 	}
 }
@@ -63,6 +63,7 @@ fn syscall_documents(
 			.documents()
 			.await
 			.into_iter()
+			.filter(|document| document.path.extension() == Some("tg"))
 			.map(Module::Document)
 			.collect())
 	})
@@ -60,8 +60,11 @@ declare namespace tg {
 	/** Create a blob. */
 	export let blob: (...args: Args<Blob.Arg>) => Promise<Blob>;
 
-	/** Download the contents of a URL. */
-	export let download: (url: string, checksum: Checksum) => Promise<Blob>;
+	/** Download the contents of a URL. If multiple URLs are provided, they are tried in order until one succeeds. If a blob with the same checksum was downloaded before, it is returned without downloading it again. `file://` URLs are also supported for vendored files in the server's `vendor` directory, and they require a checksum. */
+	export let download: (
+		url: string | Array<string>,
+		checksum: Checksum,
+	) => Promise<Blob>;
 
 	/** A blob. */
 	export type Blob = Leaf | Branch;
@@ -71,14 +74,26 @@ declare namespace tg {
 
 		export type Id = string;
 
-		export type ArchiveFormat = ".tar" | ".zip";
+		/** Archive formats. Debian packages are `.ar` archives. */
+		export type ArchiveFormat = ".ar" | ".cpio" | ".tar" | ".zip";
+
+		export type ArchiveOptions = {
+			/** Embed the references of files and the artifacts of symlinks so that the archive extracts to the same artifact. This is only supported for tar archives. */
+			references?: boolean;
+		};
+
+		export type ExtractOptions = {
+			/** Remove this many leading path components from each entry. Entries with no remaining components are skipped. */
+			stripComponents?: number;
+		};
 
 		export type CompressionFormat = ".bz2" | ".gz" | ".lz" | ".xz" | ".zst";
 
-		/** Archive an artifact. */
+		/** Archive an artifact. The output is deterministic: entries are sorted, timestamps and owners are zeroed, and modes are normalized. */
 		export let archive: (
 			artifact: Artifact,
 			format: Blob.ArchiveFormat,
+			options?: Blob.ArchiveOptions,
 		) => Promise<Blob>;
 	}
 
@@ -137,8 +152,16 @@ declare namespace tg {
 		/** Bundle this directory. */
 		bundle: () => Promise<Directory>;
 
-		/** Get an async iterator of this directory's recursive entries. */
-		walk(): AsyncIterableIterator<[string, Artifact]>;
+		/** Get an async iterator of this directory's recursive entries and their paths, in depth-first order with each directory's entries sorted by name. */
+		walk(
+			options?: Directory.WalkOptions,
+		): AsyncIterableIterator<[string, Artifact]>;
+
+		/** Get an async iterator of this directory's recursive entries whose paths match any of the glob patterns. In a pattern, `*` and `?` do not match `/`, `**` matches any number of path components, and `{a,b}` and `[ab]` match alternatives. */
+		glob(
+			pattern: string | Array<string>,
+			options?: Directory.WalkOptions,
+		): AsyncIterableIterator<[string, Artifact]>;
 
 		/** Get an async iterator of this directory's entries. */
 		[Symbol.asyncIterator](): AsyncIterator<[string, Artifact]>;
@@ -158,6 +181,14 @@ declare namespace tg {
 		};
 
 		export type Id = string;
+
+		export type WalkOptions = {
+			/** Whether to resolve symlinks and walk the directories they point to. A symlink to one of its own ancestors is not walked. The default is `false`. */
+			followSymlinks?: boolean;
+
+			/** The maximum depth of the entries, where the directory's own entries have depth 1. */
+			maxDepth?: number;
+		};
 	}
 
 	export namespace encoding {
@@ -296,8 +327,11 @@ declare namespace tg {
 		/** Decompress this branch. */
 		decompress(format: Blob.CompressionFormat): Promise<Blob>;
 
-		/** Extract an artifact from this branch. */
-		extract(format: Blob.ArchiveFormat): Promise<Artifact>;
+		/** Extract an artifact from this branch. The compression format is detected automatically, as is the archive format if it is not provided. Entries that would be extracted outside of the artifact are rejected. */
+		extract(
+			format?: Blob.ArchiveFormat,
+			options?: Blob.ExtractOptions,
+		): Promise<Artifact>;
 	}
 
 	export namespace Branch {
@@ -349,8 +383,11 @@ declare namespace tg {
 		/** Decompress this leaf. */
 		decompress(format: Blob.CompressionFormat): Promise<Blob>;
 
-		/** Extract an artifact from this leaf. */
-		extract(format: Blob.ArchiveFormat): Promise<Artifact>;
+		/** Extract an artifact from this leaf. The compression format is detected automatically, as is the archive format if it is not provided. Entries that would be extracted outside of the artifact are rejected. */
+		extract(
+			format?: Blob.ArchiveFormat,
+			options?: Blob.ExtractOptions,
+		): Promise<Artifact>;
 	}
 
 	export namespace Leaf {
@@ -373,9 +410,14 @@ declare namespace tg {
 	export let log: (...args: Array<unknown>) => void;
 
 	export type Metadata = {
+		authors?: Array<string>;
+		description?: string;
 		homepage?: string;
+		keywords?: Array<string>;
 		license?: string;
 		name?: string;
+		/** Replacements for dependencies anywhere in the package's dependency graph, keyed by `name` or `name@req`. Each replacement is a path or a `name@version`. Overrides are only honored for the root package. */
+		overrides?: { [dependency: string]: string };
 		repository?: string;
 		version?: string;
 	};
@@ -576,6 +618,21 @@ declare namespace tg {
 					? Resolved<U>
 					: never;
 
+	/**
+	 * Apply unified or git-style diffs to a directory. Diffs may create, delete, rename, and change the mode of files, and an error is thrown if a hunk does not apply, or if a created or renamed file already exists. Pass a `patch.Options` object among the diffs to configure how they are applied.
+	 */
+	export let patch: (
+		directory: Directory,
+		...args: Array<string | File | Blob | patch.Options>
+	) => Promise<Directory>;
+
+	export namespace patch {
+		export type Options = {
+			/** The number of leading components to remove from each path in a diff, like `patch -p`. The default is 1, which removes the `a/` and `b/` prefixes of git diffs. */
+			strip?: number;
+		};
+	}
+
 	/** Sleep for the specified duration in seconds. */
 	export let sleep: (duration: number) => Promise<void>;
 
@@ -739,6 +796,15 @@ declare namespace tg {
 		/** Get this target's checksum. */
 		checksum(): Promise<Checksum | undefined>;
 
+		/** Get this target's purity configuration. */
+		purity(): Promise<Target.Purity | undefined>;
+
+		/** Get this target's mounts. */
+		mounts(): Promise<Array<Target.Mount>>;
+
+		/** Get the names of this target's secrets. */
+		secrets(): Promise<Array<string>>;
+
 		/** Build this target. */
 		build(...args: { [K in keyof A]: Unresolved<A[K]> }): Promise<R>;
 	}
@@ -774,9 +840,34 @@ declare namespace tg {
 
 			/** If a checksum of the target's output is provided, then the target will have access to the network. */
 			checksum?: Checksum | undefined;
+
+			/** If provided, the target's output will be checked for references to host paths, such as the build's temporary directories, the working directory, or the host's home directory. */
+			purity?: Purity | undefined;
+
+			/** Artifacts to mount at absolute paths in the target's sandbox. Mounts are read-only. */
+			mounts?: Array<Mount>;
+
+			/** The names of secrets to provide to the target as environment variables. The secrets are resolved by the server that builds the target and are not part of the target's ID. Only targets with a checksum may use secrets. */
+			secrets?: Array<string>;
 		};
 
 		export type Id = string;
+
+		export type Purity = {
+			/** Whether a reference to a host path produces a warning in the build's log or fails the build. The default is `"warn"`. */
+			mode?: "warn" | "fail";
+
+			/** Additional prefixes that must not appear in the output. */
+			prefixes?: Array<string>;
+		};
+
+		export type Mount = {
+			/** The artifact to mount. */
+			artifact: Artifact;
+
+			/** The absolute path to mount the artifact at, such as `/usr` or `/opt/toolchain`. */
+			path: string;
+		};
 	}
 
 	/** The currently building target. */
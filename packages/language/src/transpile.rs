@@ -3,6 +3,9 @@ use std::rc::Rc;
 use swc::ecma::{ast, visit::VisitMutWith};
 use swc_core as swc;
 
+/// The version of the transpiler's output. Increment this when the output changes so that cached output is invalidated.
+pub const VERSION: u32 = 1;
+
 #[derive(Debug)]
 pub struct Output {
 	pub transpiled_text: String,
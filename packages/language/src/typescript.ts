@@ -80,19 +80,7 @@ export let host: ts.LanguageServiceHost & ts.CompilerHost = {
 	resolveModuleNameLiterals: (imports, module) => {
 		return imports.map((import_) => {
 			let specifier = import_.text;
-			let declaration = import_.parent;
-			assert(
-				ts.isImportDeclaration(declaration) ||
-					ts.isExportDeclaration(declaration),
-			);
-			let attributes = Object.fromEntries(
-				(declaration.attributes?.elements ?? []).map((attribute) => {
-					let key = attribute.name.text;
-					assert(ts.isStringLiteral(attribute.value));
-					let value = attribute.value.text;
-					return [key, value];
-				}),
-			);
+			let attributes = getImportAttributes(import_.parent);
 			let resolvedFileName;
 			try {
 				resolvedFileName = fileNameFromModule(
@@ -126,6 +114,53 @@ export let documentRegistry = ts.createDocumentRegistry();
 // Create the TypeScript language service.
 export let languageService = ts.createLanguageService(host, documentRegistry);
 
+/** Get the attributes of an import declaration, export declaration, or dynamic import. */
+let getImportAttributes = (node: ts.Node): { [key: string]: string } => {
+	let elements: ReadonlyArray<ts.ImportAttribute> = [];
+	if (ts.isImportDeclaration(node) || ts.isExportDeclaration(node)) {
+		elements = node.attributes?.elements ?? [];
+	} else {
+		assert(ts.isCallExpression(node));
+		let options = node.arguments[1];
+		if (options !== undefined && ts.isObjectLiteralExpression(options)) {
+			let with_ = options.properties.find(
+				(property) =>
+					ts.isPropertyAssignment(property) &&
+					ts.isIdentifier(property.name) &&
+					property.name.text === "with",
+			);
+			if (
+				with_ !== undefined &&
+				ts.isPropertyAssignment(with_) &&
+				ts.isObjectLiteralExpression(with_.initializer)
+			) {
+				return Object.fromEntries(
+					with_.initializer.properties.flatMap((property) => {
+						if (
+							!ts.isPropertyAssignment(property) ||
+							!ts.isStringLiteral(property.initializer)
+						) {
+							return [];
+						}
+						let key = ts.isStringLiteral(property.name)
+							? property.name.text
+							: property.name.getText();
+						return [[key, property.initializer.text]];
+					}),
+				);
+			}
+		}
+	}
+	return Object.fromEntries(
+		elements.map((attribute) => {
+			let key = attribute.name.text;
+			assert(ts.isStringLiteral(attribute.value));
+			let value = attribute.value.text;
+			return [key, value];
+		}),
+	);
+};
+
 /** Convert a module to a TypeScript file name. */
 export let fileNameFromModule = (module_: Module): string => {
 	if (module_.kind === "library") {
@@ -6,6 +6,10 @@ impl Module {
 		match self {
 			Module::Library(_) | Module::Normal { .. } => Ok(0),
 			Module::Document(document) => document.version(document_store.unwrap()).await,
+			Module::File(file) => match file.module.as_ref() {
+				Module::Document(document) => document.version(document_store.unwrap()).await,
+				_ => Ok(0),
+			},
 		}
 	}
 }
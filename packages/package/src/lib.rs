@@ -1,4 +1,5 @@
 use async_recursion::async_recursion;
+use async_trait::async_trait;
 use futures::stream::{FuturesUnordered, TryStreamExt};
 use itertools::Itertools;
 use std::{
@@ -6,8 +7,9 @@ use std::{
 	path::{Path, PathBuf},
 };
 use tangram_client as tg;
-use tangram_error::{error, return_error, Result, WrapErr};
-use tg::Handle;
+use tangram_error::{return_error, Result, WrapErr};
+
+mod solve;
 
 #[derive(Clone, Debug)]
 pub struct PackageWithPathDependencies {
@@ -15,106 +17,83 @@ pub struct PackageWithPathDependencies {
 	pub path_dependencies: BTreeMap<tg::Dependency, PackageWithPathDependencies>,
 }
 
-// Mutable state used during the version solving algorithm to cache package metadata and published packages.
-struct Context {
-	// A cache of package analysis (metadata, direct dependencies).
-	analysis: BTreeMap<tg::directory::Id, Analysis>,
-
-	// A cache of published packages that we know about.
-	published_packages: im::HashMap<tg::package::Metadata, tg::directory::Id>,
-
-	// A table of resolved path dependencies. A flattened representation of PackageWithPathDependencies.
-	path_dependencies: BTreeMap<tg::directory::Id, BTreeMap<tg::Path, tg::directory::Id>>,
+/// A change to the version of a published package in a lockfile.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Change {
+	Added {
+		name: String,
+		version: String,
+	},
+	Removed {
+		name: String,
+		version: String,
+	},
+	Updated {
+		name: String,
+		old: String,
+		new: String,
+	},
 }
 
-/// An error type that can be pretty printed to describe why version solving failed.
-struct Report {
-	// List of errors and the corresponding Dependant structures they correspond to.
-	errors: Vec<(Dependant, Error)>,
-
-	// The context, used for formatting.
-	context: Context,
-
-	// The solution structure.
-	solution: Solution,
+/// A warning about a published package in a lockfile.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Warning {
+	Deprecated { name: String, message: String },
+	Yanked { name: String, version: String },
 }
 
-// A stack frame, used for backtracking. Implicitly, a single stack frame corresponds to a single dependant (the top of the working set).
-#[derive(Clone, Debug)]
-struct Frame {
-	// The current solution space.
-	solution: Solution,
-
-	// The list of remaining dependants to solve.
-	working_set: im::Vector<Dependant>,
-
-	// The list of remaining versions for a given dependant.
-	remaining_versions: Option<im::Vector<String>>,
-
-	// The last error seen, used for error reporting.
-	last_error: Option<Error>,
-}
-
-// The solution space of the algorithm.
+/// The result of updating a lockfile.
 #[derive(Clone, Debug, Default)]
-struct Solution {
-	// A table of package names to resolved package ids.
-	permanent: im::HashMap<String, Result<tg::directory::Id, Error>>,
-
-	// The partial solution for each individual dependant.
-	partial: im::HashMap<Dependant, Mark>,
+pub struct Update {
+	pub changes: Vec<Change>,
+	pub warnings: Vec<Warning>,
 }
 
-// A dependant represents an edge in the dependency graph.
-#[derive(Clone, Debug, Hash, PartialEq, Eq)]
-struct Dependant {
-	package: tg::directory::Id,
+/// A dependency override declared by the root package.
+#[derive(Clone, Debug)]
+struct Override {
+	/// The overridden dependency, which has a name and an optional version requirement.
 	dependency: tg::Dependency,
-}
 
-// A Mark is used to detect cyclic dependencies.
-#[derive(Clone, Debug)]
-enum Mark {
-	// A preliminary guess at what package may resolve a dependant.
-	Temporary(tg::directory::Id),
+	/// The overridden dependency's version requirement.
+	version: Option<semver::VersionReq>,
 
-	// A permanent solution for a dependant, either success or an error.
-	Permanent(Result<tg::directory::Id, Error>),
-}
+	/// The dependency that replaces it, which has either a path or a name.
+	replacement: tg::Dependency,
 
-// A cached representation of a package's metadata and dependencies.
-#[derive(Debug, Clone)]
-struct Analysis {
-	metadata: tg::package::Metadata,
-	dependencies: Vec<tg::Dependency>,
+	/// If the replacement is a path, the package at the path.
+	package: Option<PackageWithPathDependencies>,
 }
 
-/// Errors that may arise during version solving.
-#[derive(Debug, Clone)]
-enum Error {
-	/// No version could be found that satisfies all constraints.
-	PackageVersionConflict,
+/// A source of published packages, which caches their versions, packages, and dependencies. Yanked versions are skipped unless they are locked.
+struct Registry<'a> {
+	tg: &'a dyn tg::Handle,
+	locked: &'a BTreeMap<String, semver::Version>,
+	versions: std::sync::Mutex<BTreeMap<String, Vec<String>>>,
+	packages: std::sync::Mutex<BTreeMap<(String, String), tg::directory::Id>>,
+	dependencies: std::sync::Mutex<BTreeMap<tg::directory::Id, Vec<tg::Dependency>>>,
+}
 
-	/// A package cycle exists.
-	PackageCycleExists {
-		/// Represents the terminal edge of cycle in the dependency graph.
-		dependant: Dependant,
-	},
+/// A source of the packages needed to create a lockfile.
+#[async_trait]
+trait Packages: solve::Source {
+	/// Get the published version of a package.
+	async fn package(&self, name: &str, version: &str) -> Result<tg::directory::Id>;
 
-	/// A nested error that arises during backtracking.
-	Backtrack {
-		/// The package that we backtracked from.
-		package: tg::directory::Id,
+	/// Get the ID of a package.
+	async fn id(&self, package: &tg::Directory) -> Result<tg::directory::Id>;
 
-		/// The version that was tried previously and failed.
-		previous_version: String,
+	/// Get the name of a package from its metadata, if it has one.
+	async fn name(&self, package: &tg::Directory) -> Option<String>;
 
-		/// A list of dependencies of `previous_version` that caused an error.
-		erroneous_dependencies: Vec<(tg::Dependency, Error)>,
-	},
+	/// Get the dependencies imported by the modules of a package, in order.
+	async fn package_dependencies(&self, package: &tg::Directory) -> Result<Vec<tg::Dependency>>;
+}
 
-	/// A tangram error.
-	Other(tangram_error::Error),
+/// A source of published packages whose dependencies have the overrides applied.
+struct Overridden<'a> {
+	packages: &'a dyn Packages,
+	overrides: &'a [Override],
 }
 
 pub async fn try_get_package(
@@ -133,6 +112,9 @@ pub async fn try_get_package_and_lock(
 	// Get the package with its path dependencies.
 	let package_with_path_dependencies = get_package_with_path_dependencies(tg, dependency).await?;
 
+	// Get the overrides.
+	let overrides = get_overrides(tg, dependency, &package_with_path_dependencies).await?;
+
 	// If this is a path dependency, then attempt to read the lockfile from the path.
 	let lockfile = if let Some(path) = dependency.path.as_ref() {
 		try_read_lockfile_from_path(path).await?
@@ -140,16 +122,24 @@ pub async fn try_get_package_and_lock(
 		None
 	};
 
-	// Verify that the lockfile's dependencies match the package with path dependencies.
-	let lockfile = if let Some(lockfile) = lockfile {
-		let matches = lockfile_matches(tg, &package_with_path_dependencies, &lockfile).await?;
+	// Verify that the lockfile's dependencies match the package with path dependencies. If they do not, then keep the lockfile to prefer the versions it selected.
+	let (lockfile, existing) = if let Some(lockfile) = lockfile {
+		let locked = BTreeMap::new();
+		let registry = Registry::new(tg, &locked);
+		let matches = lockfile_matches(
+			&registry,
+			&package_with_path_dependencies,
+			&overrides,
+			&lockfile,
+		)
+		.await?;
 		if matches {
-			Some(lockfile)
+			(Some(lockfile), None)
 		} else {
-			None
+			(None, Some(lockfile))
 		}
 	} else {
-		None
+		(None, None)
 	};
 
 	// Otherwise, create the lockfile.
@@ -157,7 +147,20 @@ pub async fn try_get_package_and_lock(
 	let lockfile = if let Some(lockfile) = lockfile {
 		lockfile
 	} else {
-		create_lockfile(tg, &package_with_path_dependencies).await?
+		let locked = if let Some(lockfile) = existing.as_ref() {
+			locked_versions(tg, lockfile).await
+		} else {
+			BTreeMap::new()
+		};
+		let registry = Registry::new(tg, &locked);
+		let (lockfile, _) = create_lockfile(
+			&registry,
+			&package_with_path_dependencies,
+			&overrides,
+			&locked,
+		)
+		.await?;
+		lockfile
 	};
 
 	// If this is a path dependency and the lockfile was created, then write the lockfile.
@@ -171,12 +174,128 @@ pub async fn try_get_package_and_lock(
 	let package = package_with_path_dependencies.package.clone();
 
 	// Create the lock.
-	let lock = create_lock(&package_with_path_dependencies, &lockfile)?;
+	let lock = create_lock(&package_with_path_dependencies, &overrides, &lockfile)?;
 
 	// Return.
 	Ok(Some((package, lock)))
 }
 
+/// Update the lockfile of the package at a path. If `names` is `None`, then every dependency may change version. Otherwise, only the named dependencies may change version, and the others keep their locked versions where possible. The lockfile is not written if `dry_run` is true. Returns the changes to the versions in the lockfile and warnings about deprecated packages and yanked versions in it.
+pub async fn update(
+	tg: &dyn tg::Handle,
+	path: &tg::Path,
+	names: Option<&[String]>,
+	dry_run: bool,
+) -> Result<Update> {
+	// Get the package with its path dependencies.
+	let dependency = tg::Dependency::with_path(path.clone());
+	let package_with_path_dependencies =
+		get_package_with_path_dependencies(tg, &dependency).await?;
+
+	// Get the overrides.
+	let overrides = get_overrides(tg, &dependency, &package_with_path_dependencies).await?;
+
+	// Get the versions in the existing lockfile.
+	let old = if let Some(lockfile) = try_read_lockfile_from_path(path).await? {
+		locked_versions(tg, &lockfile).await
+	} else {
+		BTreeMap::new()
+	};
+
+	// Keep the locked versions of the dependencies that are not being updated.
+	let locked = if let Some(names) = names {
+		for name in names {
+			if !old.contains_key(name) {
+				return_error!(r#"The lockfile does not contain "{name}"."#);
+			}
+		}
+		old.iter()
+			.filter(|(name, _)| !names.contains(name))
+			.map(|(name, version)| (name.clone(), version.clone()))
+			.collect()
+	} else {
+		BTreeMap::new()
+	};
+
+	// Create the lockfile.
+	let registry = Registry::new(tg, &locked);
+	let (lockfile, new) = create_lockfile(
+		&registry,
+		&package_with_path_dependencies,
+		&overrides,
+		&locked,
+	)
+	.await?;
+
+	// Write the lockfile.
+	if !dry_run {
+		write_lockfile(path, &lockfile).await?;
+	}
+
+	// Compute the changes.
+	let names: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+	let changes = names
+		.into_iter()
+		.filter_map(|name| {
+			let name = name.clone();
+			match (old.get(&name), new.get(&name)) {
+				(None, Some(version)) => Some(Change::Added {
+					name,
+					version: version.to_string(),
+				}),
+				(Some(version), None) => Some(Change::Removed {
+					name,
+					version: version.to_string(),
+				}),
+				(Some(old), Some(new)) if old != new => Some(Change::Updated {
+					name,
+					old: old.to_string(),
+					new: new.to_string(),
+				}),
+				_ => None,
+			}
+		})
+		.collect();
+
+	// Get the warnings.
+	let warnings = warnings(tg, &new).await;
+
+	Ok(Update { changes, warnings })
+}
+
+/// Get warnings about the deprecated packages and yanked versions in a set of versions. Packages whose status cannot be retrieved are skipped.
+async fn warnings(
+	tg: &dyn tg::Handle,
+	versions: &BTreeMap<String, semver::Version>,
+) -> Vec<Warning> {
+	let mut warnings = Vec::new();
+	for (name, version) in versions {
+		let dependency = tg::Dependency::with_name(name.clone());
+		let status = match tg.try_get_package_status(&dependency).await {
+			Ok(Some(status)) => status,
+			Ok(None) => continue,
+			Err(error) => {
+				tracing::warn!(?name, ?error, "Failed to get the status of a package.");
+				continue;
+			},
+		};
+		if let Some(message) = status.deprecated {
+			warnings.push(Warning::Deprecated {
+				name: name.clone(),
+				message,
+			});
+		}
+		let version = version.to_string();
+		if status.yanked.contains(&version) {
+			warnings.push(Warning::Yanked {
+				name: name.clone(),
+				version,
+			});
+		}
+	}
+	warnings
+}
+
 async fn get_package_with_path_dependencies(
 	tg: &dyn tg::Handle,
 	dependency: &tg::Dependency,
@@ -360,6 +479,65 @@ async fn get_package_with_path_dependencies_for_path_inner(
 	Ok(package_with_path_dependencies)
 }
 
+/// Get the overrides declared in the metadata of the package for a dependency. Overrides are only honored for a root package at a path, so a package that is not at a path has none.
+async fn get_overrides(
+	tg: &dyn tg::Handle,
+	dependency: &tg::Dependency,
+	package_with_path_dependencies: &PackageWithPathDependencies,
+) -> Result<Vec<Override>> {
+	let Some(path) = dependency.path.as_ref() else {
+		return Ok(Vec::new());
+	};
+	let Some(metadata) = try_get_metadata(tg, &package_with_path_dependencies.package).await?
+	else {
+		return Ok(Vec::new());
+	};
+	let path = tokio::fs::canonicalize(PathBuf::from(path.clone()))
+		.await
+		.wrap_err("Failed to canonicalize the path.")?;
+	let mut overrides = Vec::new();
+	for (dependency, replacement) in metadata.overrides.unwrap_or_default() {
+		// Validate the overridden dependency.
+		if dependency.name.is_none() || dependency.id.is_some() || dependency.path.is_some() {
+			return_error!(
+				r#"The override "{dependency}" must be a name with an optional version."#
+			);
+		}
+		let version: Option<semver::VersionReq> = dependency
+			.version
+			.as_ref()
+			.map(|version| version.parse())
+			.transpose()
+			.wrap_err_with(|| {
+				format!(r#"Failed to parse the version of the override "{dependency}"."#)
+			})?;
+
+		// Get the package at the replacement's path.
+		let package = if let Some(replacement_path) = replacement.path.as_ref() {
+			let replacement_path = tokio::fs::canonicalize(path.join(replacement_path.to_string()))
+				.await
+				.wrap_err_with(|| {
+					format!(r#"Failed to canonicalize the path of the override "{dependency}"."#)
+				})?;
+			Some(get_package_with_path_dependencies_for_path(tg, &replacement_path).await?)
+		} else if replacement.name.is_some() && replacement.id.is_none() {
+			None
+		} else {
+			return_error!(
+				r#"The replacement for the override "{dependency}" must be a path or a name with an optional version."#
+			);
+		};
+
+		overrides.push(Override {
+			dependency,
+			version,
+			replacement,
+			package,
+		});
+	}
+	Ok(overrides)
+}
+
 async fn try_read_lockfile_from_path(path: &tg::Path) -> Result<Option<tg::Lockfile>> {
 	// Canonicalize the path.
 	let path = tokio::fs::canonicalize(PathBuf::from(path.clone()))
@@ -397,22 +575,62 @@ async fn write_lockfile(path: &tg::Path, lockfile: &tg::Lockfile) -> Result<()>
 }
 
 async fn lockfile_matches(
-	tg: &dyn Handle,
+	packages: &dyn Packages,
 	package_with_path_dependencies: &PackageWithPathDependencies,
+	overrides: &[Override],
 	lockfile: &tg::Lockfile,
 ) -> Result<bool> {
-	lockfile_matches_inner(tg, package_with_path_dependencies, lockfile, 0).await
+	// Verify that the overrides match.
+	let matches = itertools::equal(
+		lockfile
+			.overrides
+			.iter()
+			.map(|(dependency, override_)| (dependency, &override_.replacement)),
+		overrides
+			.iter()
+			.map(|override_| (&override_.dependency, &override_.replacement)),
+	);
+	if !matches {
+		return Ok(false);
+	}
+
+	// Verify that the packages at the overrides' paths match.
+	for override_ in overrides {
+		let Some(package) = override_.package.as_ref() else {
+			continue;
+		};
+		let Some(lock) = lockfile
+			.overrides
+			.get(&override_.dependency)
+			.and_then(|override_| override_.lock)
+		else {
+			continue;
+		};
+		if !lockfile_matches_inner(packages, package, lockfile, lock).await? {
+			return Ok(false);
+		}
+	}
+
+	lockfile_matches_inner(
+		packages,
+		package_with_path_dependencies,
+		lockfile,
+		lockfile.root,
+	)
+	.await
 }
 
 #[async_recursion]
 async fn lockfile_matches_inner(
-	tg: &dyn Handle,
+	packages: &dyn Packages,
 	package_with_path_dependencies: &PackageWithPathDependencies,
 	lockfile: &tg::Lockfile,
 	index: usize,
 ) -> Result<bool> {
 	// Get the package's dependencies.
-	let dependencies = dependencies(tg, &package_with_path_dependencies.package).await?;
+	let dependencies = packages
+		.package_dependencies(&package_with_path_dependencies.package)
+		.await?;
 
 	// Get the package's lock from the lockfile.
 	let lock = lockfile.locks.get(index).wrap_err("Invalid lockfile.")?;
@@ -425,216 +643,274 @@ async fn lockfile_matches_inner(
 	// Recurse into the path dependencies.
 	package_with_path_dependencies
 		.path_dependencies
-		.keys()
-		.map(|dependency| {
-			// let dependencies = &dependencies;
-			async move {
-				let index = lock.dependencies.get(dependency).unwrap().lock;
-				lockfile_matches_inner(tg, package_with_path_dependencies, lockfile, index).await
-			}
+		.iter()
+		.map(|(dependency, package_with_path_dependencies)| async move {
+			let index = lock.dependencies.get(dependency).unwrap().lock;
+			lockfile_matches_inner(packages, package_with_path_dependencies, lockfile, index).await
 		})
 		.collect::<FuturesUnordered<_>>()
 		.try_all(|matches| async move { matches })
-		.await?;
-
-	Ok(true)
+		.await
 }
 
+/// Create a lockfile with the overrides applied, preferring the versions in `locked`. Returns the lockfile and the version selected for each published package.
 async fn create_lockfile(
-	tg: &dyn tg::Handle,
+	packages: &dyn Packages,
 	package_with_path_dependencies: &PackageWithPathDependencies,
-) -> Result<tg::Lockfile> {
-	// Construct the version solving context and working set.
-	let mut analysis = BTreeMap::new();
-	let mut path_dependencies = BTreeMap::new();
-	let mut working_set = im::Vector::new();
-
+	overrides: &[Override],
+	locked: &BTreeMap<String, semver::Version>,
+) -> Result<(tg::Lockfile, BTreeMap<String, semver::Version>)> {
+	// Get the registry dependencies of the package and its path dependencies.
+	let root = packages
+		.name(&package_with_path_dependencies.package)
+		.await
+		.unwrap_or_else(|| "the package".to_owned());
+	let mut visited = BTreeSet::new();
+	let mut dependencies = Vec::new();
 	scan_package_with_path_dependencies(
-		tg,
+		packages,
 		package_with_path_dependencies,
-		&mut analysis,
-		&mut path_dependencies,
-		&mut working_set,
+		&root,
+		&mut visited,
+		&mut dependencies,
 	)
 	.await?;
-	let published_packages = im::HashMap::new();
-	let mut context = Context {
-		analysis,
-		published_packages,
-		path_dependencies,
-	};
-
-	// Solve.
-	let solution = solve(tg, &mut context, working_set).await?;
 
-	// Create the error report.
-	let errors = solution
-		.partial
-		.iter()
-		.filter_map(|(dependant, partial)| match partial {
-			Mark::Permanent(Err(e)) => Some((dependant.clone(), e.clone())),
-			_ => None,
-		})
-		.collect::<Vec<_>>();
-
-	// If the report is not empty, return an error.
-	if !errors.is_empty() {
-		let report = Report {
-			errors,
-			context,
-			solution,
+	// Get the registry dependencies of the packages at the overrides' paths.
+	for override_ in overrides {
+		let Some(package) = override_.package.as_ref() else {
+			continue;
 		};
-		return_error!("{report}");
+		let name = packages
+			.name(&package.package)
+			.await
+			.unwrap_or_else(|| override_.replacement.to_string());
+		scan_package_with_path_dependencies(
+			packages,
+			package,
+			&name,
+			&mut visited,
+			&mut dependencies,
+		)
+		.await?;
+	}
+
+	// Apply the overrides to the registry dependencies.
+	let source = Overridden {
+		packages,
+		overrides,
+	};
+	let mut overridden = Vec::new();
+	for (dependant, dependency) in dependencies {
+		if let Some(dependency) = source.apply_override(dependency).await? {
+			overridden.push((dependant, dependency));
+		}
 	}
 
+	// Solve.
+	let solution = solve::solve(&source, &root, overridden, locked).await?;
+
 	// Create the set of locks for all dependencies.
 	let mut locks = Vec::new();
-
+	let mut registry_locks = BTreeMap::new();
 	let root = create_lockfile_inner(
-		tg,
+		&source,
 		package_with_path_dependencies,
-		&context,
 		&solution,
 		&mut locks,
+		&mut registry_locks,
 	)
 	.await?;
 
-	Ok(tg::Lockfile { root, locks })
+	// Record the overrides and the locks of the packages at their paths.
+	let overrides = overrides
+		.iter()
+		.map(|override_| {
+			let lock = override_
+				.package
+				.as_ref()
+				.and_then(|_| registry_locks.get(&override_.replacement).copied());
+			let entry = tg::lockfile::Override {
+				replacement: override_.replacement.clone(),
+				lock,
+			};
+			(override_.dependency.clone(), entry)
+		})
+		.collect();
+
+	Ok((
+		tg::Lockfile {
+			root,
+			locks,
+			overrides,
+		},
+		solution,
+	))
 }
 
-#[allow(clippy::only_used_in_recursion)]
 #[async_recursion]
 async fn create_lockfile_inner(
-	tg: &dyn tg::Handle,
+	source: &Overridden<'_>,
 	package_with_path_dependencies: &PackageWithPathDependencies,
-	context: &Context,
-	solution: &Solution,
+	solution: &BTreeMap<String, semver::Version>,
 	locks: &mut Vec<tg::lockfile::Lock>,
+	registry_locks: &mut BTreeMap<tg::Dependency, usize>,
 ) -> Result<usize> {
-	// Get the cached analysis.
-	let package = package_with_path_dependencies.package.id(tg).await?.clone();
-	let analysis = context
-		.analysis
-		.get(&package)
-		.wrap_err("Missing package in solution.")?;
-	let path_dependencies = context.path_dependencies.get(&package);
-
-	// Recursively create locks.
-	let mut dependencies = BTreeMap::new();
-	for dependency in &analysis.dependencies {
-		let entry = match (dependency.path.as_ref(), path_dependencies) {
-			// Use the path dependencies from the context to check if this is a resolved path dependency.
-			(Some(path), Some(path_dependencies)) if path_dependencies.contains_key(path) => {
-				// Resolve by path.
-				let package_with_path_dependencies = package_with_path_dependencies
-					.path_dependencies
-					.iter()
-					.find_map(|(dependency, pwpd)| {
-						(dependency.path.as_ref().unwrap() == path).then_some(pwpd)
-					})
-					.unwrap();
-				let lock = create_lockfile_inner(
-					tg,
-					package_with_path_dependencies,
-					context,
-					solution,
-					locks,
-				)
-				.await?;
-				tg::lockfile::Entry {
-					package: None,
-					lock,
-				}
-			},
-			_ => {
-				// Resolve by dependant.
-				let dependant = Dependant {
-					package: package.clone(),
-					dependency: dependency.clone(),
-				};
-				let Some(Mark::Permanent(Ok(resolved))) = solution.partial.get(&dependant) else {
-					return_error!("Missing solution for {dependant:?}.");
-				};
-				let pwpd = PackageWithPathDependencies {
-					package: tg::Directory::with_id(resolved.clone()),
-					path_dependencies: BTreeMap::new(),
-				};
-				let lock = create_lockfile_inner(tg, &pwpd, context, solution, locks).await?;
-				tg::lockfile::Entry {
-					package: Some(resolved.clone()),
-					lock,
-				}
-			},
+	let dependencies = source
+		.packages
+		.package_dependencies(&package_with_path_dependencies.package)
+		.await?;
+	let mut entries = BTreeMap::new();
+	for dependency in dependencies {
+		let entry = if let Some(package_with_path_dependencies) = package_with_path_dependencies
+			.path_dependencies
+			.get(&dependency)
+		{
+			// Resolve by path.
+			let lock = create_lockfile_inner(
+				source,
+				package_with_path_dependencies,
+				solution,
+				locks,
+				registry_locks,
+			)
+			.await?;
+			tg::lockfile::Entry {
+				package: None,
+				lock,
+			}
+		} else {
+			// Resolve by the solution.
+			create_registry_lockfile_entry(source, &dependency, solution, locks, registry_locks)
+				.await?
 		};
-		dependencies.insert(dependency.clone(), entry);
+		entries.insert(dependency, entry);
 	}
+	let lock = tg::lockfile::Lock {
+		dependencies: entries,
+	};
+	Ok(insert_lock(locks, lock))
+}
+
+#[async_recursion]
+async fn create_registry_lockfile_entry(
+	source: &Overridden<'_>,
+	dependency: &tg::Dependency,
+	solution: &BTreeMap<String, semver::Version>,
+	locks: &mut Vec<tg::lockfile::Lock>,
+	registry_locks: &mut BTreeMap<tg::Dependency, usize>,
+) -> Result<tg::lockfile::Entry> {
+	// Apply the overrides.
+	let dependency = if let Some(override_) = source.find_override(dependency).await? {
+		// If the replacement is a path, then resolve by the path. Every dependency it replaces shares its lock.
+		if let Some(package) = override_.package.as_ref() {
+			let lock = if let Some(lock) = registry_locks.get(&override_.replacement) {
+				*lock
+			} else {
+				let lock =
+					create_lockfile_inner(source, package, solution, locks, registry_locks).await?;
+				registry_locks.insert(override_.replacement.clone(), lock);
+				lock
+			};
+			return Ok(tg::lockfile::Entry {
+				package: None,
+				lock,
+			});
+		}
+		&override_.replacement
+	} else {
+		dependency
+	};
+
+	let name = dependency
+		.name
+		.as_ref()
+		.wrap_err_with(|| format!(r#"The dependency "{dependency}" does not have a name."#))?;
+	let version = solution
+		.get(name)
+		.wrap_err_with(|| format!(r#"Missing a solution for "{dependency}"."#))?
+		.to_string();
+	let package = source.packages.package(name, &version).await?;
+
+	// Every dependency on a package resolves to the same version, so each package needs only one lock.
+	let key = tg::Dependency::with_name(name.clone());
+	let lock = if let Some(lock) = registry_locks.get(&key) {
+		*lock
+	} else {
+		let mut entries = BTreeMap::new();
+		for dependency in source.packages.dependencies(name, &version).await? {
+			let entry = create_registry_lockfile_entry(
+				source,
+				&dependency,
+				solution,
+				locks,
+				registry_locks,
+			)
+			.await?;
+			entries.insert(dependency, entry);
+		}
+		let lock = tg::lockfile::Lock {
+			dependencies: entries,
+		};
+		let lock = insert_lock(locks, lock);
+		registry_locks.insert(key, lock);
+		lock
+	};
+
+	Ok(tg::lockfile::Entry {
+		package: Some(package),
+		lock,
+	})
+}
 
-	// Insert the lock if it doesn't exist.
-	let lock = tg::lockfile::Lock { dependencies };
-	let index = if let Some(index) = locks.iter().position(|l| l == &lock) {
+/// Insert a lock if it doesn't exist and return its index.
+fn insert_lock(locks: &mut Vec<tg::lockfile::Lock>, lock: tg::lockfile::Lock) -> usize {
+	if let Some(index) = locks.iter().position(|l| l == &lock) {
 		index
 	} else {
 		locks.push(lock);
 		locks.len() - 1
-	};
-	Ok(index)
+	}
 }
 
+/// Collect the registry dependencies of a package and its path dependencies, each paired with the name of the package that has it.
 #[async_recursion]
 async fn scan_package_with_path_dependencies(
-	tg: &dyn tg::Handle,
+	packages: &dyn Packages,
 	package_with_path_dependencies: &PackageWithPathDependencies,
-	all_analysis: &mut BTreeMap<tg::directory::Id, Analysis>,
-	all_path_dependencies: &mut BTreeMap<tg::directory::Id, BTreeMap<tg::Path, tg::directory::Id>>,
-	working_set: &mut im::Vector<Dependant>,
+	name: &str,
+	visited: &mut BTreeSet<tg::directory::Id>,
+	registry_dependencies: &mut Vec<(String, tg::Dependency)>,
 ) -> Result<()> {
 	let PackageWithPathDependencies {
 		package,
 		path_dependencies,
 	} = package_with_path_dependencies;
-	let package_id = package.id(tg).await?.clone();
 
-	// Check if we've already visited this dependency.
-	if all_path_dependencies.contains_key(&package_id) {
+	// Check if we've already visited this package.
+	if !visited.insert(packages.id(package).await?) {
 		return Ok(());
 	}
-	// Update the path dependencies.
-	all_path_dependencies.insert(package_id.clone(), BTreeMap::new());
-
-	// Get the metadata and dependenencies of this package.
-	let dependency = tg::Dependency::with_id(package_id.clone());
-	let metadata = tg.get_package_metadata(&dependency).await?;
-	let dependencies = tg.get_package_dependencies(&dependency).await?;
-
-	// Convert dependencies to dependants and update the working set.
-	let dependants = dependencies.iter().map(|dependency| Dependant {
-		package: package_id.clone(),
-		dependency: dependency.clone(),
-	});
-	working_set.extend(dependants);
-
-	// Add the metadata and dependencies to the analysis cache.
-	let analysis = Analysis {
-		metadata,
-		dependencies,
-	};
-	all_analysis.insert(package_id.clone(), analysis);
+
+	// Add the dependencies that are not path dependencies.
+	for dependency in packages.package_dependencies(package).await? {
+		if !path_dependencies.contains_key(&dependency) {
+			registry_dependencies.push((name.to_owned(), dependency));
+		}
+	}
 
 	// Recurse.
 	for (dependency, package_with_path_dependencies) in path_dependencies {
-		let path = dependency.path.as_ref().unwrap();
-		let dependency_package_id = package_with_path_dependencies.package.id(tg).await?.clone();
-		all_path_dependencies
-			.get_mut(&package_id)
-			.unwrap()
-			.insert(path.clone(), dependency_package_id);
-
+		let name = packages
+			.name(&package_with_path_dependencies.package)
+			.await
+			.unwrap_or_else(|| dependency.to_string());
 		scan_package_with_path_dependencies(
-			tg,
+			packages,
 			package_with_path_dependencies,
-			all_analysis,
-			all_path_dependencies,
-			working_set,
+			&name,
+			visited,
+			registry_dependencies,
 		)
 		.await?;
 	}
@@ -642,575 +918,224 @@ async fn scan_package_with_path_dependencies(
 	Ok(())
 }
 
-#[allow(clippy::too_many_lines)]
-async fn solve(
-	tg: &dyn tg::Handle,
-	context: &mut Context,
-	working_set: im::Vector<Dependant>,
-) -> Result<Solution> {
-	// Create the first stack frame.
-	let solution = Solution::default();
-	let last_error = None;
-	let remaining_versions = None;
-	let mut current_frame = Frame {
-		solution,
-		working_set,
-		remaining_versions,
-		last_error,
-	};
-	let mut history = im::Vector::new();
-
-	// The main driver loop operates on the current stack frame, and iteratively tries to build up the next stack frame.
-	while let Some((new_working_set, dependant)) = current_frame.next_dependant() {
-		let mut next_frame = Frame {
-			working_set: new_working_set,
-			solution: current_frame.solution.clone(),
-			remaining_versions: None,
-			last_error: None,
-		};
-
-		let permanent = current_frame.solution.get_permanent(context, &dependant);
-
-		let partial = current_frame.solution.partial.get(&dependant);
-		match (permanent, partial) {
-			// Case 0: There is no solution for this package yet.
-			(None, None) => 'a: {
-				// Note: this bit is tricky. The next frame will always have an empty set of remaining versions, because by construction it will never have been tried before. However we need to get a list of versions to attempt, which we will push onto the stack.
-				if current_frame.remaining_versions.is_none() {
-					let all_versions = match context.get_all_versions(tg, &dependant).await {
-						Ok(all_versions) => all_versions,
-						Err(e) => {
-							tracing::error!(?dependant, ?e, "Failed to get versions of package.");
-
-							// We cannot solve this dependency.
-							next_frame.solution = current_frame.solution.mark_permanently(
-								context,
-								dependant,
-								Err(Error::Other(e)),
-							);
-
-							// This is ugly, but writing out the full match statemenet is uglier and we already have a deeply nested tree of branches.
-							break 'a;
-						},
-					};
-
-					let remaining_versions = all_versions
-						.into_iter()
-						.filter_map(|version| {
-							// TODO: handle the error here. If the published version cannot be parsed then we can continue the loop. If the dependency version cannot be parsed we need to issue a hard error and break out of the match statement.
-							let version = version.version.as_deref()?;
-							Context::matches(version, &dependant.dependency)
-								.ok()?
-								.then_some(version.to_owned())
-						})
-						.collect();
-					current_frame.remaining_versions = Some(remaining_versions);
-				}
-
-				// Try and pick a version.
-				let package = context
-					.try_resolve(
-						tg,
-						&dependant,
-						current_frame.remaining_versions.as_mut().unwrap(),
-					)
-					.await;
-
-				match package {
-					// We successfully popped a version.
-					Ok(package) => {
-						next_frame.solution = next_frame
-							.solution
-							.mark_temporarily(dependant.clone(), package.clone());
-
-						// Add this dependency to the top of the stack before adding all its dependencies.
-						next_frame.working_set.push_back(dependant.clone());
-
-						// Add all the dependencies to the stack.
-						for child_dependency in context.dependencies(tg, &package).await.unwrap() {
-							let dependant = Dependant {
-								package: package.clone(),
-								dependency: child_dependency.clone(),
-							};
-							next_frame.working_set.push_back(dependant);
-						}
-
-						// Update the solution
-						next_frame.solution =
-							next_frame.solution.mark_temporarily(dependant, package);
-
-						// Update the stack. If we backtrack, we use the next version in the version stack.
-						history.push_back(current_frame.clone());
-					},
-
-					Err(e) => {
-						tracing::error!(?dependant, ?e, "No solution exists.");
-						next_frame.solution =
-							next_frame
-								.solution
-								.mark_permanently(context, dependant, Err(e));
-					},
-				}
-			},
-
-			// Case 1: There exists a global version for the package but we haven't solved this dependency constraint.
-			(Some(permanent), None) => {
-				match permanent {
-					// Case 1.1: The happy path. Our version is solved and it matches this constraint.
-					Ok(package) => {
-						// Successful caches of the version will be memoized, so it's safe to  unwrap here. Annoyingly, borrowck fails here because it doesn't know that the result holds a mutable reference to the context.
-						let version = context.version(tg, package).await.unwrap().to_owned();
-
-						// Case 1.1: The happy path. Our version is solved and it matches this constraint.
-						match Context::matches(&version, &dependant.dependency) {
-							Ok(true) => {
-								next_frame.solution = next_frame.solution.mark_permanently(
-									context,
-									dependant,
-									Ok(package.clone()),
-								);
-							},
-							// Case 1.3: The unhappy path. We need to fail.
-							Ok(false) => {
-								let error = Error::PackageVersionConflict;
-								if let Some(frame_) = try_backtrack(
-									&history,
-									dependant.dependency.name.as_ref().unwrap(),
-									error.clone(),
-								) {
-									next_frame = frame_;
-								} else {
-									tracing::error!(?dependant, "No solution exists.");
-									// There is no solution for this package. Add an error.
-									next_frame.solution = next_frame.solution.mark_permanently(
-										context,
-										dependant,
-										Err(error),
-									);
-								}
-							},
-							Err(e) => {
-								tracing::error!(?dependant, ?e, "Existing solution is an error.");
-								next_frame.solution.mark_permanently(
-									context,
-									dependant,
-									Err(Error::Other(e)),
-								);
-							},
-						}
-					},
-					// Case 1.2: The less happy path. We know there's no solution to this package because we've already failed to satisfy some other set of constraints.
-					Err(e) => {
-						next_frame.solution = next_frame.solution.mark_permanently(
-							context,
-							dependant,
-							Err(e.clone()),
-						);
-					},
-				}
-			},
-
-			// Case 2: We only have a partial solution for this dependency and need to make sure we didn't create a cycle.
-			(_, Some(Mark::Temporary(package))) => {
-				// Note: it is safe to unwrap here because a successful query to context.dependencies is memoized.
-				let dependencies = context.dependencies(tg, package).await.unwrap();
-
-				let mut erroneous_children = vec![];
-				for child_dependency in dependencies {
-					let child_dependant = Dependant {
-						package: package.clone(),
-						dependency: child_dependency.clone(),
-					};
-
-					let child = next_frame.solution.partial.get(&child_dependant).unwrap();
-					match child {
-						// The child dependency has been solved.
-						Mark::Permanent(Ok(_)) => (),
-
-						// The child dependency has been solved, but it is an error.
-						Mark::Permanent(Err(e)) => {
-							let error = e.clone();
-							erroneous_children.push((child_dependant.dependency, error));
-						},
-
-						// The child dependency has not been solved.
-						Mark::Temporary(_version) => {
-							// Uh oh. We've detected a cycle. First try and backtrack. If backtracking fails, bail out.
-							let error = Error::PackageCycleExists {
-								dependant: dependant.clone(),
-							};
-							erroneous_children.push((child_dependant.dependency, error));
-						},
-					}
-				}
-
-				// If none of the children contain errors, we mark this edge permanently
-				if erroneous_children.is_empty() {
-					next_frame.solution = next_frame.solution.mark_permanently(
-						context,
-						dependant,
-						Ok(package.clone()),
-					);
-				} else {
-					// Successful lookups of the version are memoized, so it's safe to unwrap here.
-					let previous_version = context.version(tg, package).await.unwrap().into();
-					let error = Error::Backtrack {
-						package: package.clone(),
-						previous_version,
-						erroneous_dependencies: erroneous_children,
-					};
-
-					if let Some(frame_) = try_backtrack(
-						&history,
-						dependant.dependency.name.as_ref().unwrap(),
-						error.clone(),
-					) {
-						next_frame = frame_;
-					} else {
-						// This means that backtracking totally failed and we need to fail with an error
-						next_frame.solution =
-							next_frame
-								.solution
-								.mark_permanently(context, dependant, Err(error));
-					}
-				}
-			},
-
-			// Case 3: We've already solved this dependency. Continue.
-			(_, Some(Mark::Permanent(_complete))) => (),
-		}
-
-		// Replace the solution and working set if needed.
-		current_frame = next_frame;
-	}
-
-	Ok(current_frame.solution)
+/// Get the name of a package from its metadata, if it has one.
+async fn package_name(tg: &dyn tg::Handle, package: &tg::Directory) -> Option<String> {
+	metadata(tg, package).await.ok()?.name
 }
 
-impl Context {
-	// Check if the dependant can be resolved as a path dependency.
-	#[must_use]
-	fn is_path_dependency(&self, dependant: &Dependant) -> bool {
-		self.resolve_path_dependency(dependant).is_some()
-	}
-
-	#[must_use]
-	fn resolve_path_dependency(&self, dependant: &Dependant) -> Option<tg::directory::Id> {
-		let Dependant {
-			package,
-			dependency,
-		} = dependant;
-		let path = dependency.path.as_ref()?;
-		let path_dependencies = self.path_dependencies.get(package)?;
-		path_dependencies.get(path).cloned()
-	}
-
-	// Check if a package satisfies a dependency.
-	fn matches(version: &str, dependency: &tg::Dependency) -> Result<bool> {
-		let Some(constraint) = dependency.version.as_ref() else {
-			return Ok(true);
+/// Get the versions of the published packages in a lockfile.
+async fn locked_versions(
+	tg: &dyn tg::Handle,
+	lockfile: &tg::Lockfile,
+) -> BTreeMap<String, semver::Version> {
+	let packages: BTreeSet<tg::directory::Id> = lockfile
+		.locks
+		.iter()
+		.flat_map(|lock| lock.dependencies.values())
+		.filter_map(|entry| entry.package.clone())
+		.collect();
+	let mut versions = BTreeMap::new();
+	for package in packages {
+		let dependency = tg::Dependency::with_id(package.clone());
+		let metadata = match tg.get_package_metadata(&dependency).await {
+			Ok(metadata) => metadata,
+			Err(error) => {
+				tracing::warn!(
+					?package,
+					?error,
+					"Failed to get the metadata of a locked package."
+				);
+				continue;
+			},
 		};
-		let version: semver::Version = version.parse().map_err(|e| {
-			tracing::error!(?e, ?version, "Failed to parse metadata version.");
-			error!("Failed to parse version: {version}.")
-		})?;
-		let constraint: semver::VersionReq = constraint.parse().map_err(|e| {
-			tracing::error!(?e, ?dependency, "Failed to parse dependency version.");
-			error!("Failed to parse version.")
-		})?;
-		Ok(constraint.matches(&version))
+		let (Some(name), Some(version)) = (metadata.name, metadata.version) else {
+			continue;
+		};
+		let Ok(version) = version.parse() else {
+			continue;
+		};
+		versions.insert(name, version);
 	}
+	versions
+}
 
-	// Try and get the next version from a list of remaining ones. Returns an error if the list is empty.
-	async fn try_resolve(
-		&mut self,
-		tg: &dyn tg::Handle,
-		dependant: &Dependant,
-		remaining_versions: &mut im::Vector<String>,
-	) -> Result<tg::directory::Id, Error> {
-		// First attempt to resolve this as a path dependency.
-		if let Some(result) = self.resolve_path_dependency(dependant) {
-			return Ok(result);
-		}
-
-		let name = dependant.dependency.name.as_ref().unwrap();
-
-		// If the cache doesn't contain the package, we need to go out to the server to retrieve the ID. If this errors, we return immediately. If there is no package available for this version (which is extremely unlikely) we loop until we get the next version that's either in the cache, or available from the server.
-		loop {
-			let version = remaining_versions
-				.pop_back()
-				.ok_or(Error::PackageVersionConflict)?;
-			let metadata = tg::package::Metadata {
-				name: Some(name.into()),
-				version: Some(version.clone()),
-				description: None,
-			};
-			if let Some(package) = self.published_packages.get(&metadata) {
-				return Ok(package.clone());
-			}
-			let dependency = tg::Dependency::with_name_and_version(name.into(), version);
-			match tg.try_get_package(&dependency).await {
-				Err(error) => {
-					tracing::error!(?dependency, "Failed to get an artifact for the package.");
-					return Err(Error::Other(error));
-				},
-				Ok(Some(package)) => {
-					self.published_packages.insert(metadata, package.clone());
-					return Ok(package);
-				},
-				Ok(None) => continue,
-			}
+impl<'a> Registry<'a> {
+	fn new(tg: &'a dyn tg::Handle, locked: &'a BTreeMap<String, semver::Version>) -> Self {
+		Self {
+			tg,
+			locked,
+			versions: std::sync::Mutex::new(BTreeMap::new()),
+			packages: std::sync::Mutex::new(BTreeMap::new()),
+			dependencies: std::sync::Mutex::new(BTreeMap::new()),
 		}
 	}
+}
 
-	// Try to lookup the cached analysis for a package by its ID. If it is missing from the cache, we ask the server to analyze the package. If we cannot, we fail.
-	async fn try_get_analysis(
-		&mut self,
-		tg: &dyn tg::Handle,
-		package: &tg::directory::Id,
-	) -> Result<&'_ Analysis> {
-		if !self.analysis.contains_key(package) {
-			let dependency = tg::Dependency::with_id(package.clone());
-			let metadata = tg.get_package_metadata(&dependency).await?;
-			let dependencies = tg
-				.get_package_dependencies(&dependency)
-				.await?
-				.into_iter()
-				.filter(|dependency| {
-					!(self.path_dependencies.contains_key(package) && dependency.path.is_some())
-				})
-				.collect();
-			let analysis = Analysis {
-				metadata: metadata.clone(),
-				dependencies,
-			};
-			self.published_packages
-				.insert(metadata.clone(), package.clone());
-			self.analysis.insert(package.clone(), analysis);
+#[async_trait]
+impl solve::Source for Registry<'_> {
+	async fn versions(&self, name: &str) -> Result<Vec<String>> {
+		if let Some(versions) = self.versions.lock().unwrap().get(name) {
+			return Ok(versions.clone());
 		}
-		Ok(self.analysis.get(package).unwrap())
-	}
-
-	// Get a list of registry dependencies for a package given its metadata.
-	async fn dependencies(
-		&mut self,
-		tg: &dyn tg::Handle,
-		package: &tg::directory::Id,
-	) -> Result<&'_ [tg::Dependency]> {
-		Ok(&self.try_get_analysis(tg, package).await?.dependencies)
-	}
-
-	// Get the version of a package given its ID.
-	async fn version(&mut self, tg: &dyn tg::Handle, package: &tg::directory::Id) -> Result<&str> {
-		self.try_get_analysis(tg, package)
+		let dependency = tg::Dependency::with_name(name.to_owned());
+		let versions = self
+			.tg
+			.try_get_package_versions(&dependency)
 			.await?
-			.metadata
-			.version
-			.as_deref()
-			.wrap_err("Missing version for package.")
-	}
-
-	// Lookup all the versions we might use to solve this dependant.
-	async fn get_all_versions(
-		&mut self,
-		tg: &dyn Handle,
-		dependant: &Dependant,
-	) -> Result<Vec<tg::package::Metadata>> {
-		// If it is a path dependency, we don't care about the versions, which may not exist.
-		if self.is_path_dependency(dependant) {
-			return Ok(Vec::new());
+			.unwrap_or_default();
+
+		// Skip the yanked versions other than the locked version.
+		let yanked = match self.tg.try_get_package_status(&dependency).await {
+			Ok(status) => status.unwrap_or_default().yanked,
+			Err(error) => {
+				tracing::warn!(?name, ?error, "Failed to get the status of a package.");
+				Vec::new()
+			},
 		};
-		let name = dependant
-			.dependency
-			.name
-			.as_ref()
-			.wrap_err("Missing name for dependency.")?
-			.clone();
-		let dependency = tg::Dependency::with_name(name.clone());
-		let metadata = tg
-			.get_package_versions(&dependency)
-			.await?
+		let locked = self.locked.get(name).map(ToString::to_string);
+		let versions: Vec<String> = versions
 			.into_iter()
-			.map(|version| tg::package::Metadata {
-				name: Some(name.clone()),
-				version: Some(version),
-				description: None,
-			})
-			.collect::<Vec<_>>();
-		Ok(metadata)
+			.filter(|version| !yanked.contains(version) || locked.as_ref() == Some(version))
+			.collect();
+
+		self.versions
+			.lock()
+			.unwrap()
+			.insert(name.to_owned(), versions.clone());
+		Ok(versions)
 	}
-}
 
-fn try_backtrack(history: &im::Vector<Frame>, package: &str, error: Error) -> Option<Frame> {
-	let idx = history
-		.iter()
-		.take_while(|frame| !frame.solution.contains(package))
-		.count();
-	let mut frame = history.get(idx).cloned()?;
-	frame.last_error = Some(error);
-	Some(frame)
+	async fn dependencies(&self, name: &str, version: &str) -> Result<Vec<tg::Dependency>> {
+		let package = self.package(name, version).await?;
+		if let Some(dependencies) = self.dependencies.lock().unwrap().get(&package) {
+			return Ok(dependencies.clone());
+		}
+		let dependencies = self
+			.tg
+			.get_package_dependencies(&tg::Dependency::with_id(package.clone()))
+			.await?;
+		self.dependencies
+			.lock()
+			.unwrap()
+			.insert(package, dependencies.clone());
+		Ok(dependencies)
+	}
 }
 
-impl Solution {
-	// If there's an existing solution for this dependant, return it. Path dependencies are ignored.
-	fn get_permanent(
-		&self,
-		context: &Context,
-		dependant: &Dependant,
-	) -> Option<&Result<tg::directory::Id, Error>> {
-		if context.is_path_dependency(dependant) {
-			return None;
+#[async_trait]
+impl Packages for Registry<'_> {
+	async fn package(&self, name: &str, version: &str) -> Result<tg::directory::Id> {
+		let key = (name.to_owned(), version.to_owned());
+		if let Some(package) = self.packages.lock().unwrap().get(&key) {
+			return Ok(package.clone());
 		}
-		self.permanent.get(dependant.dependency.name.as_ref()?)
+		let dependency = tg::Dependency::with_name_and_version(key.0.clone(), key.1.clone());
+		let package = self
+			.tg
+			.try_get_package(&dependency)
+			.await?
+			.wrap_err_with(|| format!(r#"Could not find package "{dependency}"."#))?;
+		self.packages.lock().unwrap().insert(key, package.clone());
+		Ok(package)
 	}
 
-	/// Mark this dependant with a temporary solution.
-	fn mark_temporarily(&self, dependant: Dependant, package: tg::directory::Id) -> Self {
-		let mut solution = self.clone();
-		solution.partial.insert(dependant, Mark::Temporary(package));
-		solution
+	async fn id(&self, package: &tg::Directory) -> Result<tg::directory::Id> {
+		Ok(package.id(self.tg).await?.clone())
 	}
 
-	/// Mark the dependant permanently, adding it to the list of known solutions and the partial solutions.
-	fn mark_permanently(
-		&self,
-		context: &Context,
-		dependant: Dependant,
-		complete: Result<tg::directory::Id, Error>,
-	) -> Self {
-		let mut solution = self.clone();
-
-		// Update the global solution.
-		if !context.is_path_dependency(&dependant) {
-			solution
-				.permanent
-				.insert(dependant.dependency.name.clone().unwrap(), complete.clone());
-		}
-
-		// Update the local solution.
-		solution
-			.partial
-			.insert(dependant, Mark::Permanent(complete));
-
-		solution
+	async fn name(&self, package: &tg::Directory) -> Option<String> {
+		package_name(self.tg, package).await
 	}
 
-	fn contains(&self, package: &str) -> bool {
-		self.permanent.contains_key(package)
+	async fn package_dependencies(&self, package: &tg::Directory) -> Result<Vec<tg::Dependency>> {
+		dependencies(self.tg, package).await
 	}
 }
 
-impl Frame {
-	fn next_dependant(&self) -> Option<(im::Vector<Dependant>, Dependant)> {
-		let mut working_set = self.working_set.clone();
-		let dependant = working_set.pop_back()?;
-		Some((working_set, dependant))
+impl<'a> Overridden<'a> {
+	/// Find the override for a dependency. An override with a version requirement applies to the dependencies that allow a published version matching it.
+	async fn find_override(&self, dependency: &tg::Dependency) -> Result<Option<&'a Override>> {
+		let Some(name) = dependency.name.as_ref() else {
+			return Ok(None);
+		};
+		if dependency.path.is_some() {
+			return Ok(None);
+		}
+		for override_ in self.overrides {
+			if override_.dependency.name.as_ref() != Some(name) {
+				continue;
+			}
+			let Some(version) = override_.version.as_ref() else {
+				return Ok(Some(override_));
+			};
+			let req: Option<semver::VersionReq> = dependency
+				.version
+				.as_ref()
+				.map(|version| version.parse())
+				.transpose()
+				.wrap_err_with(|| format!(r#"Failed to parse the version of "{dependency}"."#))?;
+			let matches = self
+				.packages
+				.versions(name)
+				.await?
+				.iter()
+				.filter_map(|published| published.parse::<semver::Version>().ok())
+				.any(|published| {
+					version.matches(&published)
+						&& req.as_ref().map_or(true, |req| req.matches(&published))
+				});
+			if matches {
+				return Ok(Some(override_));
+			}
+		}
+		Ok(None)
 	}
-}
 
-impl std::fmt::Display for Report {
-	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		for (dependant, error) in &self.errors {
-			self.format(f, dependant, error)?;
+	/// Apply the overrides to a dependency. Returns `None` if the dependency is replaced by a path, since the package at the path is not published.
+	async fn apply_override(&self, dependency: tg::Dependency) -> Result<Option<tg::Dependency>> {
+		let Some(override_) = self.find_override(&dependency).await? else {
+			return Ok(Some(dependency));
+		};
+		if override_.package.is_some() {
+			return Ok(None);
 		}
-		Ok(())
+		Ok(Some(override_.replacement.clone()))
 	}
 }
 
-impl Report {
-	fn format(
-		&self,
-		f: &mut std::fmt::Formatter<'_>,
-		dependant: &Dependant,
-		error: &Error,
-	) -> std::fmt::Result {
-		let Dependant {
-			package,
-			dependency,
-		} = dependant;
-
-		let metadata = &self.context.analysis.get(package).unwrap().metadata;
-		let name = metadata.name.as_ref().unwrap();
-		let version = metadata.version.as_ref().unwrap();
-		write!(f, "{name} @ {version} requires {dependency}, but ")?;
-
-		match error {
-			Error::PackageCycleExists { dependant } => {
-				let metadata = &self
-					.context
-					.analysis
-					.get(&dependant.package)
-					.unwrap()
-					.metadata;
-				let name = metadata.name.as_ref().unwrap();
-				let version = metadata.version.as_ref().unwrap();
-				writeln!(f, "{name}@{version}, which creates a cycle.")
-			},
-			Error::PackageVersionConflict => {
-				writeln!(
-					f,
-					"no version could be found that satisfies the constraints."
-				)?;
-				let shared_dependants = self
-					.solution
-					.partial
-					.keys()
-					.filter(|dependant| dependant.dependency.name == dependency.name);
-				for shared in shared_dependants {
-					let Dependant {
-						package,
-						dependency,
-						..
-					} = shared;
-					let metadata = &self.context.analysis.get(package).unwrap().metadata;
-					let name = metadata.name.as_ref().unwrap();
-					let version = metadata.version.as_ref().unwrap();
-					writeln!(f, "{name} @ {version} requires {dependency}")?;
-				}
-				Ok(())
-			},
-			Error::Backtrack {
-				package,
-				previous_version,
-				erroneous_dependencies,
-			} => {
-				writeln!(
-					f,
-					"{} {previous_version} has errors:",
-					dependency.name.as_ref().unwrap()
-				)?;
-				for (child, error) in erroneous_dependencies {
-					let dependant = Dependant {
-						package: package.clone(),
-						dependency: child.clone(),
-					};
-					self.format(f, &dependant, error)?;
-				}
-				Ok(())
-			},
-			Error::Other(e) => writeln!(f, "{e}"),
-		}
+#[async_trait]
+impl solve::Source for Overridden<'_> {
+	async fn versions(&self, name: &str) -> Result<Vec<String>> {
+		self.packages.versions(name).await
 	}
-}
 
-impl std::fmt::Display for Mark {
-	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		match self {
-			Self::Permanent(complete) => write!(f, "Complete({complete:?})"),
-			Self::Temporary(version) => write!(f, "Incomplete({version})"),
+	async fn dependencies(&self, name: &str, version: &str) -> Result<Vec<tg::Dependency>> {
+		let mut dependencies = Vec::new();
+		for dependency in self.packages.dependencies(name, version).await? {
+			if let Some(dependency) = self.apply_override(dependency).await? {
+				dependencies.push(dependency);
+			}
 		}
+		Ok(dependencies)
 	}
 }
 
 fn create_lock(
 	package_with_path_dependencies: &PackageWithPathDependencies,
+	overrides: &[Override],
 	lockfile: &tg::Lockfile,
 ) -> Result<tg::Lock> {
-	create_lock_inner(package_with_path_dependencies, lockfile, lockfile.root)
+	create_lock_inner(
+		package_with_path_dependencies,
+		overrides,
+		lockfile,
+		lockfile.root,
+	)
 }
 
 fn create_lock_inner(
 	package_with_path_dependencies: &PackageWithPathDependencies,
+	overrides: &[Override],
 	lockfile: &tg::Lockfile,
 	index: usize,
 ) -> Result<tg::Lock> {
@@ -1221,15 +1146,42 @@ fn create_lock_inner(
 		.map(|(dependency, entry)| -> Result<_> {
 			let (package, lock) = if let Some(package) = entry.package.as_ref() {
 				let package = tg::Directory::with_id(package.clone());
-				let lock = create_lock_inner(package_with_path_dependencies, lockfile, entry.lock)?;
+				let lock = create_lock_inner(
+					package_with_path_dependencies,
+					overrides,
+					lockfile,
+					entry.lock,
+				)?;
 				(package, lock)
 			} else {
-				let package_with_path_dependencies = package_with_path_dependencies
-					.path_dependencies
-					.get(dependency)
-					.wrap_err("Missing path dependency.")?;
+				let package_with_path_dependencies = if let Some(package_with_path_dependencies) =
+					package_with_path_dependencies
+						.path_dependencies
+						.get(dependency)
+				{
+					package_with_path_dependencies
+				} else {
+					// Otherwise, the dependency was replaced by the package at an override's path.
+					let (overridden, _) = lockfile
+						.overrides
+						.iter()
+						.find(|(overridden, override_)| {
+							override_.lock == Some(entry.lock) && overridden.name == dependency.name
+						})
+						.wrap_err("Missing path dependency.")?;
+					overrides
+						.iter()
+						.find(|override_| &override_.dependency == overridden)
+						.and_then(|override_| override_.package.as_ref())
+						.wrap_err("Missing override.")?
+				};
 				let package = package_with_path_dependencies.package.clone();
-				let lock = create_lock_inner(package_with_path_dependencies, lockfile, entry.lock)?;
+				let lock = create_lock_inner(
+					package_with_path_dependencies,
+					overrides,
+					lockfile,
+					entry.lock,
+				)?;
 				(package, lock)
 			};
 			let entry = tg::lock::Entry { package, lock };
@@ -1306,6 +1258,16 @@ pub async fn metadata(
 	tg: &dyn tg::Handle,
 	package: &tg::Directory,
 ) -> Result<tg::package::Metadata> {
+	try_get_metadata(tg, package)
+		.await?
+		.wrap_err("Missing package metadata.")
+}
+
+/// Get a package's metadata, or `None` if its root module does not export any.
+async fn try_get_metadata(
+	tg: &dyn tg::Handle,
+	package: &tg::Directory,
+) -> Result<Option<tg::package::Metadata>> {
 	let path = tg::package::ROOT_MODULE_FILE_NAME.parse().unwrap();
 	let file = package
 		.get(tg, &path)
@@ -1315,396 +1277,293 @@ pub async fn metadata(
 		.wrap_err("Expected the module to be a file.")?;
 	let text = file.text(tg).await?;
 	let analysis = tangram_language::Module::analyze(text)?;
-	if let Some(metadata) = analysis.metadata {
-		Ok(metadata)
-	} else {
-		return_error!("Missing package metadata.")
-	}
+	Ok(analysis.metadata)
 }
 
-// #[cfg(test)]
-// mod tests {
-// 	use crate::ROOT_MODULE_FILE_NAME;
-// 	use tangram_client as tg;
-// 	use tangram_error::Result;
-
-// 	#[tokio::test]
-// 	async fn simple_diamond() {
-// 		let client: Box<dyn tg::Client> = todo!();
-
-// 		create_package(
-// 			client.as_ref(),
-// 			"simple_diamond_A",
-// 			"1.0.0",
-// 			&[
-// 				"simple_diamond_B@^1.0".parse().unwrap(),
-// 				"simple_diamond_C@^1.0".parse().unwrap(),
-// 			],
-// 		)
-// 		.await
-// 		.unwrap();
-
-// 		create_package(
-// 			client.as_ref(),
-// 			"simple_diamond_B",
-// 			"1.0.0",
-// 			&["simple_diamond_D@^1.0".parse().unwrap()],
-// 		)
-// 		.await
-// 		.unwrap();
-
-// 		create_package(
-// 			client.as_ref(),
-// 			"simple_diamond_C",
-// 			"1.0.0",
-// 			&["simple_diamond_D@^1.0".parse().unwrap()],
-// 		)
-// 		.await
-// 		.unwrap();
-
-// 		create_package(tg.as_ref(), "simple_diamond_D", "1.0.0", &[])
-// 			.await
-// 			.unwrap();
-// 	}
-
-// 	#[tokio::test]
-// 	async fn simple_backtrack() {
-// 		let client: Box<dyn tg::Client> = todo!();
-
-// 		create_package(
-// 			client.as_ref(),
-// 			"simple_backtrack_A",
-// 			"1.0.0",
-// 			&[
-// 				"simple_backtrack_B@^1.2.3".parse().unwrap(),
-// 				"simple_backtrack_C@<1.2.3".parse().unwrap(),
-// 			],
-// 		)
-// 		.await
-// 		.unwrap();
-
-// 		create_package(
-// 			client.as_ref(),
-// 			"simple_backtrack_B",
-// 			"1.2.3",
-// 			&["simple_backtrack_C@<1.2.3".parse().unwrap()],
-// 		)
-// 		.await
-// 		.unwrap();
-
-// 		create_package(tg.as_ref(), "simple_backtrack_C", "1.2.3", &[])
-// 			.await
-// 			.unwrap();
-
-// 		create_package(tg.as_ref(), "simple_backtrack_C", "1.2.2", &[])
-// 			.await
-// 			.unwrap();
-// 	}
-
-// 	#[tokio::test]
-// 	async fn diamond_backtrack() {
-// 		let client: Box<dyn tg::Client> = todo!();
-
-// 		create_package(
-// 			client.as_ref(),
-// 			"diamond_backtrack_A",
-// 			"1.0.0",
-// 			&[
-// 				"diamond_backtrack_B@1.0.0".parse().unwrap(),
-// 				"diamond_backtrack_C@1.0.0".parse().unwrap(),
-// 			],
-// 		)
-// 		.await
-// 		.unwrap();
-
-// 		create_package(
-// 			client.as_ref(),
-// 			"diamond_backtrack_B",
-// 			"1.0.0",
-// 			&["diamond_backtrack_D@<1.5.0".parse().unwrap()],
-// 		)
-// 		.await
-// 		.unwrap();
-
-// 		create_package(
-// 			client.as_ref(),
-// 			"diamond_backtrack_C",
-// 			"1.0.0",
-// 			&["diamond_backtrack_D@<1.3.0".parse().unwrap()],
-// 		)
-// 		.await
-// 		.unwrap();
-
-// 		create_package(tg.as_ref(), "diamond_backtrack_D", "1.1.0", &[])
-// 			.await
-// 			.unwrap();
-
-// 		create_package(tg.as_ref(), "diamond_backtrack_D", "1.2.0", &[])
-// 			.await
-// 			.unwrap();
-
-// 		create_package(tg.as_ref(), "diamond_backtrack_D", "1.3.0", &[])
-// 			.await
-// 			.unwrap();
-
-// 		create_package(tg.as_ref(), "diamond_backtrack_D", "1.4.0", &[])
-// 			.await
-// 			.unwrap();
-
-// 		create_package(tg.as_ref(), "diamond_backtrack_D", "1.5.0", &[])
-// 			.await
-// 			.unwrap();
-// 	}
-
-// 	#[tokio::test]
-// 	async fn cycle_exists() {
-// 		let client: Box<dyn tg::Client> = todo!();
-
-// 		create_package(
-// 			client.as_ref(),
-// 			"cycle_exists_A",
-// 			"1.0.0",
-// 			&["cycle_exists_B@1.0.0".parse().unwrap()],
-// 		)
-// 		.await
-// 		.unwrap();
-
-// 		create_package(
-// 			client.as_ref(),
-// 			"cycle_exists_B",
-// 			"1.0.0",
-// 			&["cycle_exists_C@1.0.0".parse().unwrap()],
-// 		)
-// 		.await
-// 		.unwrap();
-
-// 		create_package(
-// 			client.as_ref(),
-// 			"cycle_exists_C",
-// 			"1.0.0",
-// 			&["cycle_exists_B@1.0.0".parse().unwrap()],
-// 		)
-// 		.await
-// 		.unwrap();
-// 	}
-
-// 	#[tokio::test]
-// 	async fn diamond_incompatible_versions() {
-// 		let client: Box<dyn tg::Client> = todo!();
-// 		create_package(
-// 			client.as_ref(),
-// 			"diamond_incompatible_versions_A",
-// 			"1.0.0",
-// 			&[
-// 				"diamond_incompatible_versions_B@1.0.0".parse().unwrap(),
-// 				"diamond_incompatible_versions_C@1.0.0".parse().unwrap(),
-// 			],
-// 		)
-// 		.await
-// 		.unwrap();
-
-// 		create_package(
-// 			client.as_ref(),
-// 			"diamond_incompatible_versions_B",
-// 			"1.0.0",
-// 			&["diamond_incompatible_versions_D@<1.2.0".parse().unwrap()],
-// 		)
-// 		.await
-// 		.unwrap();
-
-// 		create_package(
-// 			client.as_ref(),
-// 			"diamond_incompatible_versions_C",
-// 			"1.0.0",
-// 			&["diamond_incompatible_versions_D@>1.3.0".parse().unwrap()],
-// 		)
-// 		.await
-// 		.unwrap();
-
-// 		create_package(
-// 			client.as_ref(),
-// 			"diamond_incompatible_versions_D",
-// 			"1.0.0",
-// 			&[],
-// 		)
-// 		.await
-// 		.unwrap();
-
-// 		create_package(
-// 			client.as_ref(),
-// 			"diamond_incompatible_versions_D",
-// 			"1.1.0",
-// 			&[],
-// 		)
-// 		.await
-// 		.unwrap();
-
-// 		create_package(
-// 			client.as_ref(),
-// 			"diamond_incompatible_versions_D",
-// 			"1.2.0",
-// 			&[],
-// 		)
-// 		.await
-// 		.unwrap();
-
-// 		create_package(
-// 			client.as_ref(),
-// 			"diamond_incompatible_versions_D",
-// 			"1.3.0",
-// 			&[],
-// 		)
-// 		.await
-// 		.unwrap();
-
-// 		create_package(
-// 			client.as_ref(),
-// 			"diamond_incompatible_versions_D",
-// 			"1.4.0",
-// 			&[],
-// 		)
-// 		.await
-// 		.unwrap();
-// 	}
-
-// 	#[tokio::test]
-// 	#[allow(clippy::similar_names)]
-// 	async fn diamond_with_path_dependencies() {
-// 		let client: Box<dyn tg::Client> = todo!();
-
-// 		let foo = r#"
-// 			export let metadata = {
-// 				name: "foo",
-// 				version: "1.0.0",
-// 			};
-
-// 			import bar from "tg:?path=./path/to/bar";
-// 			import baz from "tg:baz@^1";
-// 			export default tg.target(() => tg`foo ${bar} {baz}`);
-// 		"#;
-
-// 		let bar = r#"
-// 			export let metadata = {
-// 				name: "bar",
-// 				version: "1.0.0",
-// 			};
-
-// 			import * as baz from "tg:baz@=1.2.3";
-// 			export default tg.target(() => tg`bar ${baz}`);
-// 		"#;
-
-// 		let baz = r#"
-// 			export let metadata = {
-// 				name: "baz",
-// 				version: "1.2.3",
-// 			};
-
-// 			export default tg.target(() => "baz");
-// 		"#;
-// 	}
-
-// 	#[tokio::test]
-// 	async fn complex_diamond() {
-// 		let client: Box<dyn tg::Client> = todo!();
-
-// 		create_package(
-// 			client.as_ref(),
-// 			"complex_diamond_A",
-// 			"1.0.0",
-// 			&[
-// 				"complex_diamond_B@^1.0.0".parse().unwrap(),
-// 				"complex_diamond_E@^1.1.0".parse().unwrap(),
-// 				"complex_diamond_C@^1.0.0".parse().unwrap(),
-// 				"complex_diamond_D@^1.0.0".parse().unwrap(),
-// 			],
-// 		)
-// 		.await
-// 		.unwrap();
-
-// 		create_package(
-// 			client.as_ref(),
-// 			"complex_diamond_B",
-// 			"1.0.0",
-// 			&["complex_diamond_D@^1.0.0".parse().unwrap()],
-// 		)
-// 		.await
-// 		.unwrap();
-
-// 		create_package(
-// 			client.as_ref(),
-// 			"complex_diamond_C",
-// 			"1.0.0",
-// 			&[
-// 				"complex_diamond_D@^1.0.0".parse().unwrap(),
-// 				"complex_diamond_E@>1.0.0".parse().unwrap(),
-// 			],
-// 		)
-// 		.await
-// 		.unwrap();
-
-// 		create_package(
-// 			client.as_ref(),
-// 			"complex_diamond_D",
-// 			"1.3.0",
-// 			&["complex_diamond_E@=1.0.0".parse().unwrap()],
-// 		)
-// 		.await
-// 		.unwrap();
-
-// 		create_package(
-// 			client.as_ref(),
-// 			"complex_diamond_D",
-// 			"1.2.0",
-// 			&["complex_diamond_E@^1.0.0".parse().unwrap()],
-// 		)
-// 		.await
-// 		.unwrap();
-
-// 		create_package(tg.as_ref(), "complex_diamond_E", "1.0.0", &[])
-// 			.await
-// 			.unwrap();
-
-// 		create_package(tg.as_ref(), "complex_diamond_E", "1.1.0", &[])
-// 			.await
-// 			.unwrap();
-// 	}
-
-// 	async fn create_package(
-// 		client: &dyn tg::Client,
-// 		name: &str,
-// 		version: &str,
-// 		dependencies: &[tg::Dependency],
-// 	) -> Result<()> {
-// 		let imports = dependencies
-// 			.iter()
-// 			.map(|dep| {
-// 				let name = dep.name.as_ref().unwrap();
-// 				let version = dep.version.as_ref().unwrap();
-// 				format!(r#"import * as {name} from "tg:{name}@{version}";"#)
-// 			})
-// 			.collect::<Vec<_>>()
-// 			.join("\n");
-// 		let contents = format!(
-// 			r#"
-// 				{imports}
-// 				export let metadata = {{
-// 						name: "{name}",
-// 						version: "{version}",
-// 				}};
-// 				export default tg.target(() => `Hello, from "{name}"!`);
-// 			"#
-// 		);
-// 		let contents = tg::blob::Blob::with_reader(tg, contents.as_bytes())
-// 			.await
-// 			.unwrap();
-// 		let file = tg::File::builder(contents).build();
-// 		let package = tg::Directory::with_object(tg::directory::Object {
-// 			entries: [(ROOT_MODULE_FILE_NAME.to_owned(), file.into())]
-// 				.into_iter()
-// 				.collect(),
-// 		});
-// 		let id = package.id(tg).await?;
-// 		client.publish_package(None, &id.clone()).await?;
-// 		Ok(())
-// 	}
-// }
+#[cfg(test)]
+mod tests {
+	use super::{
+		create_lock, create_lockfile, lockfile_matches,
+		solve::{self, Source},
+		Override, PackageWithPathDependencies, Packages,
+	};
+	use async_trait::async_trait;
+	use std::collections::BTreeMap;
+	use tangram_client as tg;
+	use tangram_error::{Result, WrapErr};
+
+	/// A registry of published packages and packages at paths.
+	#[derive(Default)]
+	struct Registry {
+		registry: solve::tests::Registry,
+		paths: BTreeMap<tg::directory::Id, (String, Vec<tg::Dependency>)>,
+	}
+
+	impl Registry {
+		fn add(&mut self, name: &str, version: &str, dependencies: &[&str]) {
+			self.registry.add(name, version, dependencies);
+		}
+
+		fn add_path(
+			&mut self,
+			name: &str,
+			dependencies: &[&str],
+			path_dependencies: &[(&str, PackageWithPathDependencies)],
+		) -> PackageWithPathDependencies {
+			let mut dependencies: Vec<tg::Dependency> = dependencies
+				.iter()
+				.map(|dependency| dependency.parse().unwrap())
+				.collect();
+			let path_dependencies: BTreeMap<_, _> = path_dependencies
+				.iter()
+				.map(|(dependency, package)| (dependency.parse().unwrap(), package.clone()))
+				.collect();
+			dependencies.extend(path_dependencies.keys().cloned());
+			dependencies.sort();
+			let id = id(&format!("./{name}"));
+			self.paths
+				.insert(id.clone(), (name.to_owned(), dependencies));
+			PackageWithPathDependencies {
+				package: tg::Directory::with_id(id),
+				path_dependencies,
+			}
+		}
+	}
+
+	#[async_trait]
+	impl Source for Registry {
+		async fn versions(&self, name: &str) -> Result<Vec<String>> {
+			self.registry.versions(name).await
+		}
+
+		async fn dependencies(&self, name: &str, version: &str) -> Result<Vec<tg::Dependency>> {
+			self.registry.dependencies(name, version).await
+		}
+	}
+
+	#[async_trait]
+	impl Packages for Registry {
+		async fn package(&self, name: &str, version: &str) -> Result<tg::directory::Id> {
+			self.registry.dependencies(name, version).await?;
+			Ok(id(&format!("{name}@{version}")))
+		}
+
+		async fn id(&self, package: &tg::Directory) -> Result<tg::directory::Id> {
+			package
+				.state()
+				.read()
+				.unwrap()
+				.id
+				.clone()
+				.wrap_err("Expected the package to have an ID.")
+		}
+
+		async fn name(&self, package: &tg::Directory) -> Option<String> {
+			let id = self.id(package).await.ok()?;
+			Some(self.paths.get(&id)?.0.clone())
+		}
+
+		async fn package_dependencies(
+			&self,
+			package: &tg::Directory,
+		) -> Result<Vec<tg::Dependency>> {
+			let id = self.id(package).await?;
+			let (_, dependencies) = self
+				.paths
+				.get(&id)
+				.wrap_err("Failed to find the package.")?;
+			Ok(dependencies.clone())
+		}
+	}
+
+	fn id(name: &str) -> tg::directory::Id {
+		tg::Id::new_hashed(tg::id::Kind::Directory, name.as_bytes())
+			.try_into()
+			.unwrap()
+	}
+
+	fn dependency(dependency: &str) -> tg::Dependency {
+		dependency.parse().unwrap()
+	}
+
+	fn override_(
+		dependency: &str,
+		replacement: &str,
+		package: Option<PackageWithPathDependencies>,
+	) -> Override {
+		let dependency = self::dependency(dependency);
+		let version = dependency
+			.version
+			.as_ref()
+			.map(|version| version.parse().unwrap());
+		Override {
+			dependency,
+			version,
+			replacement: self::dependency(replacement),
+			package,
+		}
+	}
+
+	fn expected(versions: &[(&str, &str)]) -> BTreeMap<String, semver::Version> {
+		versions
+			.iter()
+			.map(|(name, version)| ((*name).to_owned(), version.parse().unwrap()))
+			.collect()
+	}
+
+	/// Get the entry for a dependency of the lock of a published package.
+	fn entry<'a>(
+		lockfile: &'a tg::Lockfile,
+		package: &str,
+		dependency: &str,
+	) -> &'a tg::lockfile::Entry {
+		let package = id(package);
+		let lock = lockfile
+			.locks
+			.iter()
+			.flat_map(|lock| lock.dependencies.values())
+			.find(|entry| entry.package.as_ref() == Some(&package))
+			.unwrap()
+			.lock;
+		&lockfile.locks[lock].dependencies[&self::dependency(dependency)]
+	}
+
+	#[tokio::test]
+	async fn override_by_name_applies_to_transitive_dependencies() {
+		let mut registry = Registry::default();
+		registry.add("b", "1.0.0", &["c@^1"]);
+		registry.add("c", "1.0.0", &[]);
+		registry.add("c-fork", "2.0.0", &[]);
+		let root = registry.add_path("root", &["b@^1"], &[]);
+		let overrides = [override_("c", "c-fork@^2", None)];
+		let (lockfile, solution) = create_lockfile(&registry, &root, &overrides, &BTreeMap::new())
+			.await
+			.unwrap();
+		assert_eq!(solution, expected(&[("b", "1.0.0"), ("c-fork", "2.0.0")]));
+		let entry = entry(&lockfile, "b@1.0.0", "c@^1");
+		assert_eq!(entry.package, Some(id("c-fork@2.0.0")));
+	}
+
+	#[tokio::test]
+	async fn override_by_name_and_version_applies_to_matching_dependencies() {
+		let mut registry = Registry::default();
+		registry.add("b", "1.0.0", &["c@^1"]);
+		registry.add("d", "1.0.0", &["c@^2"]);
+		registry.add("c", "1.0.0", &[]);
+		registry.add("c", "2.0.0", &[]);
+		registry.add("c-fork", "1.1.0", &[]);
+		let root = registry.add_path("root", &["b@^1", "d@^1"], &[]);
+		let overrides = [override_("c@^1", "c-fork@^1", None)];
+		let (lockfile, solution) = create_lockfile(&registry, &root, &overrides, &BTreeMap::new())
+			.await
+			.unwrap();
+		assert_eq!(
+			solution,
+			expected(&[
+				("b", "1.0.0"),
+				("c", "2.0.0"),
+				("c-fork", "1.1.0"),
+				("d", "1.0.0"),
+			])
+		);
+		let entry_b = entry(&lockfile, "b@1.0.0", "c@^1");
+		assert_eq!(entry_b.package, Some(id("c-fork@1.1.0")));
+		let entry_d = entry(&lockfile, "d@1.0.0", "c@^2");
+		assert_eq!(entry_d.package, Some(id("c@2.0.0")));
+	}
+
+	#[tokio::test]
+	async fn path_override_is_shared_by_its_dependants() {
+		let mut registry = Registry::default();
+		registry.add("b", "1.0.0", &["c@^1"]);
+		registry.add("d", "1.0.0", &["c@^1.0"]);
+		registry.add("c", "1.0.0", &[]);
+		registry.add("e", "1.0.0", &[]);
+		let local = registry.add_path("c-local", &["e@^1"], &[]);
+		let root = registry.add_path("root", &["b@^1", "d@^1"], &[]);
+		let overrides = [override_("c", "./c-local", Some(local))];
+		let (lockfile, solution) = create_lockfile(&registry, &root, &overrides, &BTreeMap::new())
+			.await
+			.unwrap();
+		assert_eq!(
+			solution,
+			expected(&[("b", "1.0.0"), ("d", "1.0.0"), ("e", "1.0.0")])
+		);
+		let lock = lockfile.overrides[&dependency("c")].lock.unwrap();
+		let entry_b = entry(&lockfile, "b@1.0.0", "c@^1");
+		let entry_d = entry(&lockfile, "d@1.0.0", "c@^1.0");
+		assert_eq!((entry_b.package.as_ref(), entry_b.lock), (None, lock));
+		assert_eq!((entry_d.package.as_ref(), entry_d.lock), (None, lock));
+		let entry_e = &lockfile.locks[lock].dependencies[&dependency("e@^1")];
+		assert_eq!(entry_e.package, Some(id("e@1.0.0")));
+
+		// The lock resolves the replaced dependencies to the package at the path.
+		let lock = create_lock(&root, &overrides, &lockfile).unwrap();
+		for dependencies in [["b@^1", "c@^1"], ["d@^1", "c@^1.0"]] {
+			let entry = lock_entry(&lock, &dependencies);
+			let package = entry.package.state().read().unwrap().id.clone();
+			assert_eq!(package, Some(id("./c-local")));
+		}
+	}
+
+	#[tokio::test]
+	async fn lockfile_round_trip_is_invalidated_when_the_overrides_change() {
+		let mut registry = Registry::default();
+		registry.add("b", "1.0.0", &["c@^1"]);
+		registry.add("c", "1.0.0", &[]);
+		registry.add("c-fork", "1.0.0", &[]);
+		registry.add("e", "1.0.0", &[]);
+		let local = registry.add_path("c-local", &["e@^1"], &[]);
+		let child = registry.add_path("child", &["b@^1"], &[]);
+		let root = registry.add_path("root", &["b@^1"], &[("./child", child)]);
+		let overrides = [override_("c", "./c-local", Some(local))];
+		let (lockfile, _) = create_lockfile(&registry, &root, &overrides, &BTreeMap::new())
+			.await
+			.unwrap();
+
+		// Write and read the lockfile.
+		let lockfile = serde_json::to_vec_pretty(&lockfile).unwrap();
+		let lockfile: tg::Lockfile = serde_json::from_slice(&lockfile).unwrap();
+
+		// The lockfile matches the overrides it was created with.
+		assert!(lockfile_matches(&registry, &root, &overrides, &lockfile)
+			.await
+			.unwrap());
+		create_lock(&root, &overrides, &lockfile).unwrap();
+
+		// The lockfile does not match when an override is removed, its replacement changes, or the package at its path changes.
+		assert!(!lockfile_matches(&registry, &root, &[], &lockfile)
+			.await
+			.unwrap());
+		let changed = [override_("c", "c-fork@^1", None)];
+		assert!(!lockfile_matches(&registry, &root, &changed, &lockfile)
+			.await
+			.unwrap());
+		let local = registry.add_path("c-local-changed", &[], &[]);
+		let changed = [override_("c", "./c-local", Some(local))];
+		assert!(!lockfile_matches(&registry, &root, &changed, &lockfile)
+			.await
+			.unwrap());
+	}
+
+	/// Get the entry of a lock at a path of dependencies.
+	fn lock_entry(lock: &tg::Lock, dependencies: &[&str]) -> tg::lock::Entry {
+		let mut lock = lock.clone();
+		let mut entry = None;
+		for dependency in dependencies {
+			let dependency = self::dependency(dependency);
+			let next = lock
+				.state()
+				.read()
+				.unwrap()
+				.object
+				.as_ref()
+				.unwrap()
+				.dependencies[&dependency]
+				.clone();
+			lock = next.lock.clone();
+			entry = Some(next);
+		}
+		entry.unwrap()
+	}
+}
@@ -0,0 +1,1217 @@
+//! A version solver based on the PubGrub algorithm. See <https://github.com/dart-lang/pub/blob/master/doc/solver.md> for a description of the algorithm.
+
+use async_trait::async_trait;
+use itertools::Itertools;
+use semver::Version;
+use std::collections::{BTreeMap, BTreeSet};
+use tangram_client as tg;
+use tangram_error::{error, Error, Result, WrapErr};
+
+/// A source of the versions and dependencies of published packages.
+#[async_trait]
+pub(crate) trait Source: Send + Sync {
+	/// Get the published versions of a package. If the package does not exist, then the versions are empty.
+	async fn versions(&self, name: &str) -> Result<Vec<String>>;
+
+	/// Get the dependencies of a published version of a package.
+	async fn dependencies(&self, name: &str, version: &str) -> Result<Vec<tg::Dependency>>;
+}
+
+/// Select a version of every package required by the root package. `dependencies` are the registry dependencies of the root package and its path dependencies, each paired with the name of the package that has it. The versions in `locked` are preferred when they are allowed.
+pub(crate) async fn solve(
+	source: &dyn Source,
+	root: &str,
+	dependencies: Vec<(String, tg::Dependency)>,
+	locked: &BTreeMap<String, Version>,
+) -> Result<BTreeMap<String, Version>> {
+	let mut solver = Solver::new(source, root, dependencies, locked);
+	solver.run().await
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Package {
+	/// The root package, which stands in for the root package and all of its path dependencies.
+	Root,
+
+	/// A published package.
+	Name(String),
+}
+
+/// A set of states of a package. A package is either selected at one of `versions`, or, if `absent` is true, not selected at all. The versions are always a subset of the package's known versions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Term {
+	versions: BTreeSet<Version>,
+	absent: bool,
+}
+
+#[derive(Clone, Debug)]
+struct Incompatibility {
+	/// The terms which may not all be true at once.
+	terms: BTreeMap<Package, Term>,
+	cause: Cause,
+}
+
+#[derive(Clone, Debug)]
+enum Cause {
+	/// The root package must be selected.
+	Root,
+
+	/// A package depends on a dependency.
+	Dependency {
+		dependant: String,
+		dependency: tg::Dependency,
+	},
+
+	/// The selected versions depend on each other in a cycle.
+	Cycle(Vec<(String, Version)>),
+
+	/// The incompatibility was derived from two others during conflict resolution.
+	Derived(usize, usize),
+}
+
+#[derive(Clone, Debug)]
+struct Assignment {
+	package: Package,
+	term: Term,
+	level: usize,
+
+	/// The incompatibility the assignment was derived from, or `None` if it is a decision.
+	cause: Option<usize>,
+}
+
+enum Relation {
+	Satisfied,
+	Contradicted,
+	AlmostSatisfied(Package),
+	Inconclusive,
+}
+
+/// Returned when the root package's dependencies cannot be solved, with the index of the incompatibility that proves it.
+struct Unsatisfiable(usize);
+
+struct Solver<'a> {
+	source: &'a dyn Source,
+	root: String,
+	root_dependencies: Vec<(String, tg::Dependency)>,
+	locked: &'a BTreeMap<String, Version>,
+
+	/// The known versions of each package.
+	universes: BTreeMap<Package, BTreeSet<Version>>,
+
+	/// A cache of the dependencies of each version of each package.
+	dependencies: BTreeMap<(String, Version), Vec<tg::Dependency>>,
+
+	incompatibilities: Vec<Incompatibility>,
+
+	/// The indices of the incompatibilities used for propagation, by package.
+	incompatibilities_by_package: BTreeMap<Package, Vec<usize>>,
+
+	/// The partial solution.
+	assignments: Vec<Assignment>,
+
+	/// The intersection of each package's assignments.
+	terms: BTreeMap<Package, Term>,
+
+	level: usize,
+}
+
+impl Term {
+	fn any(universe: &BTreeSet<Version>) -> Self {
+		Self {
+			versions: universe.clone(),
+			absent: true,
+		}
+	}
+
+	fn positive(versions: BTreeSet<Version>) -> Self {
+		Self {
+			versions,
+			absent: false,
+		}
+	}
+
+	fn is_positive(&self) -> bool {
+		!self.absent
+	}
+
+	fn is_any(&self, universe: &BTreeSet<Version>) -> bool {
+		self.absent && self.versions.len() == universe.len()
+	}
+
+	fn is_empty(&self) -> bool {
+		!self.absent && self.versions.is_empty()
+	}
+
+	fn intersection(&self, other: &Self) -> Self {
+		Self {
+			versions: self
+				.versions
+				.intersection(&other.versions)
+				.cloned()
+				.collect(),
+			absent: self.absent && other.absent,
+		}
+	}
+
+	fn complement(&self, universe: &BTreeSet<Version>) -> Self {
+		Self {
+			versions: universe.difference(&self.versions).cloned().collect(),
+			absent: !self.absent,
+		}
+	}
+
+	fn is_subset(&self, other: &Self) -> bool {
+		self.versions.is_subset(&other.versions) && (!self.absent || other.absent)
+	}
+
+	fn is_disjoint(&self, other: &Self) -> bool {
+		self.versions.is_disjoint(&other.versions) && !(self.absent && other.absent)
+	}
+}
+
+impl<'a> Solver<'a> {
+	fn new(
+		source: &'a dyn Source,
+		root: &str,
+		root_dependencies: Vec<(String, tg::Dependency)>,
+		locked: &'a BTreeMap<String, Version>,
+	) -> Self {
+		let universes = [(Package::Root, [root_version()].into())].into();
+		Self {
+			source,
+			root: root.to_owned(),
+			root_dependencies,
+			locked,
+			universes,
+			dependencies: BTreeMap::new(),
+			incompatibilities: Vec::new(),
+			incompatibilities_by_package: BTreeMap::new(),
+			assignments: Vec::new(),
+			terms: BTreeMap::new(),
+			level: 0,
+		}
+	}
+
+	async fn run(&mut self) -> Result<BTreeMap<String, Version>> {
+		// Require the root package.
+		let term =
+			Term::positive([root_version()].into()).complement(&self.universe(&Package::Root));
+		let index = self.add_incompatibility([(Package::Root, term)], Cause::Root);
+		self.register(index);
+
+		let mut next = Package::Root;
+		loop {
+			// Derive everything that follows from the partial solution, resolving conflicts along the way.
+			if let Err(Unsatisfiable(index)) = self.propagate(next) {
+				return Err(self.report(index));
+			}
+
+			// Decide the version of the next package.
+			if let Some(package) = self.decide().await? {
+				next = package;
+				continue;
+			}
+
+			// Every package has been decided. If the selected versions form a cycle, then forbid it and continue.
+			let Some(cycle) = self.find_cycle() else {
+				break;
+			};
+			let terms = cycle
+				.iter()
+				.map(|(name, version)| {
+					let term = Term::positive([version.clone()].into());
+					(Package::Name(name.clone()), term)
+				})
+				.collect_vec();
+			next = Package::Name(cycle[0].0.clone());
+			let index = self.add_incompatibility(terms, Cause::Cycle(cycle));
+			self.register(index);
+		}
+
+		// Return the decided versions.
+		let solution = self
+			.decisions()
+			.filter_map(|(package, version)| match package {
+				Package::Root => None,
+				Package::Name(name) => Some((name.clone(), version.clone())),
+			})
+			.collect();
+		Ok(solution)
+	}
+
+	fn universe(&self, package: &Package) -> BTreeSet<Version> {
+		self.universes.get(package).cloned().unwrap_or_default()
+	}
+
+	fn term(&self, package: &Package) -> Term {
+		self.terms
+			.get(package)
+			.cloned()
+			.unwrap_or_else(|| Term::any(&self.universe(package)))
+	}
+
+	fn decisions(&self) -> impl Iterator<Item = (&Package, &Version)> {
+		self.assignments
+			.iter()
+			.filter(|assignment| assignment.cause.is_none())
+			.filter_map(|assignment| {
+				let version = assignment.term.versions.first()?;
+				Some((&assignment.package, version))
+			})
+	}
+
+	/// Add an incompatibility, intersecting the terms that refer to the same package and removing the terms that allow any state.
+	fn add_incompatibility(
+		&mut self,
+		terms: impl IntoIterator<Item = (Package, Term)>,
+		cause: Cause,
+	) -> usize {
+		let mut merged: BTreeMap<Package, Term> = BTreeMap::new();
+		for (package, term) in terms {
+			let term = match merged.remove(&package) {
+				Some(existing) => existing.intersection(&term),
+				None => term,
+			};
+			merged.insert(package, term);
+		}
+		merged.retain(|package, term| !term.is_any(&self.universe(package)));
+		self.incompatibilities.push(Incompatibility {
+			terms: merged,
+			cause,
+		});
+		self.incompatibilities.len() - 1
+	}
+
+	/// Make an incompatibility available to unit propagation.
+	fn register(&mut self, index: usize) {
+		for package in self.incompatibilities[index].terms.keys() {
+			self.incompatibilities_by_package
+				.entry(package.clone())
+				.or_default()
+				.push(index);
+		}
+	}
+
+	fn assign(&mut self, package: Package, term: Term, cause: Option<usize>) {
+		let intersection = match self.terms.get(&package) {
+			Some(existing) => existing.intersection(&term),
+			None => term.clone(),
+		};
+		self.terms.insert(package.clone(), intersection);
+		self.assignments.push(Assignment {
+			package,
+			term,
+			level: self.level,
+			cause,
+		});
+	}
+
+	/// Assign the complement of an almost satisfied incompatibility's unsatisfied term.
+	fn derive(&mut self, package: Package, index: usize) {
+		let term =
+			self.incompatibilities[index].terms[&package].complement(&self.universe(&package));
+		self.assign(package, term, Some(index));
+	}
+
+	fn backtrack(&mut self, level: usize) {
+		self.assignments
+			.retain(|assignment| assignment.level <= level);
+		self.level = level;
+		self.terms.clear();
+		for assignment in &self.assignments {
+			let intersection = match self.terms.get(&assignment.package) {
+				Some(existing) => existing.intersection(&assignment.term),
+				None => assignment.term.clone(),
+			};
+			self.terms.insert(assignment.package.clone(), intersection);
+		}
+	}
+
+	fn relation(&self, index: usize) -> Relation {
+		let mut unsatisfied = None;
+		for (package, term) in &self.incompatibilities[index].terms {
+			let assigned = self.term(package);
+			if assigned.is_subset(term) {
+				continue;
+			}
+			if assigned.is_disjoint(term) {
+				return Relation::Contradicted;
+			}
+			if unsatisfied.is_some() {
+				return Relation::Inconclusive;
+			}
+			unsatisfied = Some(package.clone());
+		}
+		match unsatisfied {
+			None => Relation::Satisfied,
+			Some(package) => Relation::AlmostSatisfied(package),
+		}
+	}
+
+	fn propagate(&mut self, package: Package) -> Result<(), Unsatisfiable> {
+		let mut changed = vec![package];
+		while let Some(package) = changed.pop() {
+			let indices = self
+				.incompatibilities_by_package
+				.get(&package)
+				.cloned()
+				.unwrap_or_default();
+			for index in indices.into_iter().rev() {
+				match self.relation(index) {
+					Relation::Satisfied => {
+						let index = self.resolve_conflict(index)?;
+						let Relation::AlmostSatisfied(package) = self.relation(index) else {
+							return Err(Unsatisfiable(index));
+						};
+						self.derive(package.clone(), index);
+						changed.clear();
+						changed.push(package);
+						break;
+					},
+					Relation::AlmostSatisfied(package) => {
+						self.derive(package.clone(), index);
+						if !changed.contains(&package) {
+							changed.push(package);
+						}
+					},
+					Relation::Contradicted | Relation::Inconclusive => (),
+				}
+			}
+		}
+		Ok(())
+	}
+
+	/// Find the index of the earliest assignment to a package after which the intersection of `initial` and the package's assignments up to `end` is a subset of `term`.
+	fn find_satisfier(
+		&self,
+		package: &Package,
+		term: &Term,
+		initial: Term,
+		end: usize,
+	) -> Option<usize> {
+		let mut accumulated = initial;
+		if accumulated.is_subset(term) {
+			return None;
+		}
+		for (index, assignment) in self.assignments[..end].iter().enumerate() {
+			if &assignment.package != package {
+				continue;
+			}
+			accumulated = accumulated.intersection(&assignment.term);
+			if accumulated.is_subset(term) {
+				return Some(index);
+			}
+		}
+		None
+	}
+
+	/// Resolve a satisfied incompatibility by deriving its root cause and backtracking until the root cause is almost satisfied.
+	fn resolve_conflict(&mut self, mut index: usize) -> Result<usize, Unsatisfiable> {
+		let original = index;
+		loop {
+			if self.is_failure(index) {
+				return Err(Unsatisfiable(index));
+			}
+			let incompatibility = self.incompatibilities[index].clone();
+
+			// Find the satisfier of each term.
+			let mut satisfiers = BTreeMap::new();
+			for (package, term) in &incompatibility.terms {
+				let any = Term::any(&self.universe(package));
+				let satisfier = self
+					.find_satisfier(package, term, any, self.assignments.len())
+					.ok_or(Unsatisfiable(index))?;
+				satisfiers.insert(package.clone(), satisfier);
+			}
+
+			// Find the satisfier of the incompatibility, which is the latest of the terms' satisfiers.
+			let (package, satisfier) = satisfiers
+				.iter()
+				.max_by_key(|(_, satisfier)| **satisfier)
+				.map(|(package, satisfier)| (package.clone(), *satisfier))
+				.ok_or(Unsatisfiable(index))?;
+			let satisfier = self.assignments[satisfier].clone();
+			let term = &incompatibility.terms[&package];
+
+			// Find the level the incompatibility was satisfied at without the satisfier.
+			let mut previous_level = satisfiers
+				.iter()
+				.filter(|(other, _)| **other != package)
+				.map(|(_, satisfier)| self.assignments[*satisfier].level)
+				.fold(1, usize::max);
+			let end = satisfiers[&package];
+			if let Some(previous) = self.find_satisfier(&package, term, satisfier.term.clone(), end)
+			{
+				previous_level = previous_level.max(self.assignments[previous].level);
+			}
+
+			// If the satisfier is a decision or the incompatibility was satisfied at an earlier level, then backtrack so the incompatibility is almost satisfied.
+			let Some(cause) = satisfier.cause else {
+				return Ok(self.finish_conflict(original, index, previous_level));
+			};
+			if previous_level != satisfier.level {
+				return Ok(self.finish_conflict(original, index, previous_level));
+			}
+
+			// Otherwise, derive a new incompatibility from the incompatibility and the satisfier's cause.
+			let universe = self.universe(&package);
+			let mut terms = incompatibility
+				.terms
+				.iter()
+				.chain(self.incompatibilities[cause].terms.iter())
+				.filter(|(other, _)| **other != package)
+				.map(|(package, term)| (package.clone(), term.clone()))
+				.collect_vec();
+			if !satisfier.term.is_subset(term) {
+				let difference = satisfier.term.intersection(&term.complement(&universe));
+				terms.push((package.clone(), difference.complement(&universe)));
+			}
+			index = self.add_incompatibility(terms, Cause::Derived(index, cause));
+		}
+	}
+
+	fn finish_conflict(&mut self, original: usize, index: usize, level: usize) -> usize {
+		if index != original {
+			self.register(index);
+		}
+		self.backtrack(level);
+		index
+	}
+
+	/// Determine if an incompatibility proves that the root package cannot be selected.
+	fn is_failure(&self, index: usize) -> bool {
+		let terms = &self.incompatibilities[index].terms;
+		match terms.first_key_value() {
+			None => true,
+			Some((package, term)) => {
+				terms.len() == 1 && *package == Package::Root && term.is_positive()
+			},
+		}
+	}
+
+	/// Decide the version of the undecided package with the fewest allowed versions, and add incompatibilities for its dependencies. Returns `None` if every package has been decided.
+	async fn decide(&mut self) -> Result<Option<Package>> {
+		let decided: BTreeSet<&Package> = self.decisions().map(|(package, _)| package).collect();
+		let Some((package, term)) = self
+			.terms
+			.iter()
+			.filter(|(package, term)| term.is_positive() && !decided.contains(package))
+			.min_by_key(|(_, term)| term.versions.len())
+			.map(|(package, term)| (package.clone(), term.clone()))
+		else {
+			return Ok(None);
+		};
+
+		// Prefer the locked version, and otherwise the latest version.
+		let version = match &package {
+			Package::Root => root_version(),
+			Package::Name(name) => self
+				.locked
+				.get(name)
+				.filter(|version| term.versions.contains(version))
+				.or_else(|| term.versions.last())
+				.cloned()
+				.wrap_err_with(|| format!(r#"No version of "{name}" is allowed."#))?,
+		};
+
+		// Add an incompatibility for each dependency.
+		let dependencies = match &package {
+			Package::Root => self.root_dependencies.clone(),
+			Package::Name(name) => {
+				let dependant = format!("{name}@{version}");
+				self.get_dependencies(name, &version)
+					.await?
+					.into_iter()
+					.map(|dependency| (dependant.clone(), dependency))
+					.collect()
+			},
+		};
+		let mut added = Vec::new();
+		for (dependant, dependency) in dependencies {
+			let name = dependency.name.clone().wrap_err_with(|| {
+				format!(r#"The dependency "{dependency}" of {dependant} does not have a name."#)
+			})?;
+			let universe = self.get_universe(&name).await?;
+			let versions = matching_versions(&universe, &dependency)?;
+			let terms = [
+				(package.clone(), Term::positive([version.clone()].into())),
+				(
+					Package::Name(name),
+					Term::positive(versions).complement(&universe),
+				),
+			];
+			let index = self.add_incompatibility(
+				terms,
+				Cause::Dependency {
+					dependant,
+					dependency,
+				},
+			);
+
+			// An incompatibility with an empty term can never be satisfied, such as one for a package that depends on itself.
+			if self.incompatibilities[index]
+				.terms
+				.values()
+				.any(Term::is_empty)
+			{
+				continue;
+			}
+			self.register(index);
+			added.push(index);
+		}
+
+		// Make the decision unless it would satisfy one of the new incompatibilities, in which case propagation will rule the version out.
+		self.level += 1;
+		self.assign(package.clone(), Term::positive([version].into()), None);
+		if added
+			.iter()
+			.any(|index| matches!(self.relation(*index), Relation::Satisfied))
+		{
+			self.backtrack(self.level - 1);
+		}
+
+		Ok(Some(package))
+	}
+
+	async fn get_universe(&mut self, name: &str) -> Result<BTreeSet<Version>> {
+		let package = Package::Name(name.to_owned());
+		if let Some(universe) = self.universes.get(&package) {
+			return Ok(universe.clone());
+		}
+		let universe: BTreeSet<Version> = self
+			.source
+			.versions(name)
+			.await?
+			.into_iter()
+			.filter_map(|version| match version.parse() {
+				Ok(version) => Some(version),
+				Err(error) => {
+					tracing::warn!(?name, ?version, ?error, "Failed to parse the version.");
+					None
+				},
+			})
+			.collect();
+		self.universes.insert(package, universe.clone());
+		Ok(universe)
+	}
+
+	async fn get_dependencies(
+		&mut self,
+		name: &str,
+		version: &Version,
+	) -> Result<Vec<tg::Dependency>> {
+		let key = (name.to_owned(), version.clone());
+		if let Some(dependencies) = self.dependencies.get(&key) {
+			return Ok(dependencies.clone());
+		}
+		let dependencies = self.source.dependencies(name, &version.to_string()).await?;
+		self.dependencies.insert(key, dependencies.clone());
+		Ok(dependencies)
+	}
+
+	/// Find a cycle among the decided versions.
+	fn find_cycle(&self) -> Option<Vec<(String, Version)>> {
+		let decisions: BTreeMap<&str, &Version> = self
+			.decisions()
+			.filter_map(|(package, version)| match package {
+				Package::Root => None,
+				Package::Name(name) => Some((name.as_str(), version)),
+			})
+			.collect();
+		let mut stack = Vec::new();
+		let mut visited = BTreeSet::new();
+		decisions
+			.keys()
+			.find_map(|name| self.find_cycle_inner(name, &decisions, &mut stack, &mut visited))
+	}
+
+	fn find_cycle_inner<'b>(
+		&'b self,
+		name: &'b str,
+		decisions: &BTreeMap<&'b str, &'b Version>,
+		stack: &mut Vec<&'b str>,
+		visited: &mut BTreeSet<&'b str>,
+	) -> Option<Vec<(String, Version)>> {
+		if let Some(position) = stack.iter().position(|entry| *entry == name) {
+			let cycle = stack[position..]
+				.iter()
+				.map(|name| ((*name).to_owned(), decisions[name].clone()))
+				.collect();
+			return Some(cycle);
+		}
+		if !visited.insert(name) {
+			return None;
+		}
+		let version = decisions[name];
+		let dependencies = self.dependencies.get(&(name.to_owned(), version.clone()))?;
+		stack.push(name);
+		for dependency in dependencies {
+			let Some(child) = dependency.name.as_deref() else {
+				continue;
+			};
+			let Some((child, _)) = decisions.get_key_value(child) else {
+				continue;
+			};
+			if let Some(cycle) = self.find_cycle_inner(child, decisions, stack, visited) {
+				return Some(cycle);
+			}
+		}
+		stack.pop();
+		None
+	}
+
+	/// Create an error that explains why an incompatibility makes the root package's dependencies unsatisfiable.
+	fn report(&self, index: usize) -> Error {
+		// Count the derivations that refer to each incompatibility, so the ones referred to more than once can be given line numbers.
+		let mut references = BTreeMap::new();
+		let mut stack = vec![index];
+		while let Some(index) = stack.pop() {
+			if let Cause::Derived(first, second) = self.incompatibilities[index].cause {
+				for cause in [first, second] {
+					let count = references.entry(cause).or_insert(0);
+					*count += 1;
+					if *count == 1 {
+						stack.push(cause);
+					}
+				}
+			}
+		}
+
+		let mut lines = Vec::new();
+		if let Cause::Derived(..) = self.incompatibilities[index].cause {
+			let mut numbers = BTreeMap::new();
+			self.explain(index, &references, &mut lines, &mut numbers);
+		} else {
+			lines.push((
+				format!("Because {}, version solving failed.", self.describe(index)),
+				None,
+			));
+		}
+
+		// Indent the lines to align them after the line numbers.
+		let count = lines.iter().filter(|(_, number)| number.is_some()).count();
+		let width = if count == 0 {
+			0
+		} else {
+			count.to_string().len() + 3
+		};
+		let lines = lines
+			.into_iter()
+			.map(|(line, number)| {
+				let number = number
+					.map(|number| format!("({number})"))
+					.unwrap_or_default();
+				format!("{number:width$}{line}")
+			})
+			.join("\n");
+		error!("Failed to solve the package's dependencies.\n{lines}")
+	}
+
+	fn explain(
+		&self,
+		index: usize,
+		references: &BTreeMap<usize, usize>,
+		lines: &mut Vec<(String, Option<usize>)>,
+		numbers: &mut BTreeMap<usize, usize>,
+	) {
+		let Cause::Derived(first, second) = self.incompatibilities[index].cause else {
+			return;
+		};
+		let incompatibility = self.describe(index);
+		let is_derived =
+			|index: usize| matches!(self.incompatibilities[index].cause, Cause::Derived(..));
+		match (is_derived(first), is_derived(second)) {
+			(true, true) => {
+				let first_number = numbers.get(&first).copied();
+				let second_number = numbers.get(&second).copied();
+				match (first_number, second_number) {
+					(Some(first_number), Some(second_number)) => {
+						lines.push((
+							format!(
+								"Because {} ({first_number}) and {} ({second_number}), {incompatibility}.",
+								self.describe(first),
+								self.describe(second),
+							),
+							None,
+						));
+					},
+					(Some(number), None) | (None, Some(number)) => {
+						let (numbered, other) = if first_number.is_some() {
+							(first, second)
+						} else {
+							(second, first)
+						};
+						self.explain(other, references, lines, numbers);
+						lines.push((
+							format!(
+								"And because {} ({number}), {incompatibility}.",
+								self.describe(numbered),
+							),
+							None,
+						));
+					},
+					(None, None) => {
+						self.explain(first, references, lines, numbers);
+						let number = number(first, lines, numbers);
+						self.explain(second, references, lines, numbers);
+						lines.push((
+							format!(
+								"And because {} ({number}), {incompatibility}.",
+								self.describe(first),
+							),
+							None,
+						));
+					},
+				}
+			},
+			(true, false) | (false, true) => {
+				let (derived, external) = if is_derived(first) {
+					(first, second)
+				} else {
+					(second, first)
+				};
+				if let Some(number) = numbers.get(&derived) {
+					lines.push((
+						format!(
+							"Because {} and {} ({number}), {incompatibility}.",
+							self.describe(external),
+							self.describe(derived),
+						),
+						None,
+					));
+				} else {
+					self.explain(derived, references, lines, numbers);
+					lines.push((
+						format!(
+							"And because {}, {incompatibility}.",
+							self.describe(external)
+						),
+						None,
+					));
+				}
+			},
+			(false, false) => {
+				lines.push((
+					format!(
+						"Because {} and {}, {incompatibility}.",
+						self.describe(first),
+						self.describe(second),
+					),
+					None,
+				));
+			},
+		}
+
+		// Number the line if a later line will refer to it.
+		if references.get(&index).copied().unwrap_or_default() > 1 {
+			number(index, lines, numbers);
+		}
+	}
+
+	fn describe(&self, index: usize) -> String {
+		let incompatibility = &self.incompatibilities[index];
+		match &incompatibility.cause {
+			Cause::Root => format!("{} is required", self.root),
+			Cause::Dependency {
+				dependant,
+				dependency,
+			} => {
+				let matches = match &dependency.name {
+					Some(name) => incompatibility
+						.terms
+						.contains_key(&Package::Name(name.clone())),
+					None => true,
+				};
+				if matches {
+					format!("{dependant} depends on {dependency}")
+				} else {
+					format!("{dependant} depends on {dependency}, which matches no versions")
+				}
+			},
+			Cause::Cycle(cycle) => {
+				let cycle = cycle
+					.iter()
+					.chain(cycle.first())
+					.map(|(name, version)| format!("{name}@{version}"))
+					.join(" -> ");
+				format!("{cycle} form a cycle")
+			},
+			Cause::Derived(..) => self.describe_terms(index),
+		}
+	}
+
+	fn describe_terms(&self, index: usize) -> String {
+		if self.is_failure(index) {
+			return "version solving failed".to_owned();
+		}
+		let terms = &self.incompatibilities[index].terms;
+		let positive = terms
+			.iter()
+			.filter(|(_, term)| term.is_positive())
+			.map(|(package, term)| self.describe_term(package, term))
+			.collect_vec();
+		let negative = terms
+			.iter()
+			.filter(|(_, term)| !term.is_positive())
+			.map(|(package, term)| {
+				let term = term.complement(&self.universe(package));
+				self.describe_term(package, &term)
+			})
+			.collect_vec();
+		match (positive.as_slice(), negative.as_slice()) {
+			([positive], []) => format!("{positive} is forbidden"),
+			([], [negative]) => format!("{negative} is required"),
+			([positive], [negative]) => format!("{positive} requires {negative}"),
+			([first, second], []) => format!("{first} is incompatible with {second}"),
+			(positive, []) => format!("one of {} must be false", positive.join(", ")),
+			([], negative) => format!("one of {} is required", negative.join(", ")),
+			(positive, negative) => format!(
+				"if {} then one of {} is required",
+				positive.join(" and "),
+				negative.join(", "),
+			),
+		}
+	}
+
+	fn describe_term(&self, package: &Package, term: &Term) -> String {
+		let Package::Name(name) = package else {
+			return self.root.clone();
+		};
+		let universe = self.universe(package);
+		if term.versions.len() == 1 {
+			let version = term.versions.first().unwrap();
+			return format!("{name}@{version}");
+		}
+		if term.versions.len() == universe.len() {
+			return format!("any version of {name}");
+		}
+		if term.versions.is_empty() {
+			return format!("no version of {name}");
+		}
+
+		// Describe the versions as ranges of consecutive known versions.
+		let mut ranges = Vec::new();
+		let mut range: Option<(&Version, &Version)> = None;
+		for version in &universe {
+			if term.versions.contains(version) {
+				range = Some(range.map_or((version, version), |(start, _)| (start, version)));
+			} else if let Some(range) = range.take() {
+				ranges.push(range);
+			}
+		}
+		ranges.extend(range);
+		let first = universe.first();
+		let last = universe.last();
+		let ranges = ranges
+			.into_iter()
+			.map(|(start, end)| {
+				if start == end {
+					format!("={start}")
+				} else if Some(start) == first {
+					format!("<={end}")
+				} else if Some(end) == last {
+					format!(">={start}")
+				} else {
+					format!(">={start}, <={end}")
+				}
+			})
+			.join(" || ");
+		format!("{name}@{ranges}")
+	}
+}
+
+/// Give the last line a number so it can be referred to by a later line, and return the number.
+fn number(
+	index: usize,
+	lines: &mut [(String, Option<usize>)],
+	numbers: &mut BTreeMap<usize, usize>,
+) -> usize {
+	if let Some(number) = numbers.get(&index) {
+		return *number;
+	}
+	let number = numbers.len() + 1;
+	if let Some((_, line_number)) = lines.last_mut() {
+		*line_number = Some(number);
+	}
+	numbers.insert(index, number);
+	number
+}
+
+fn root_version() -> Version {
+	Version::new(0, 0, 0)
+}
+
+fn matching_versions(
+	universe: &BTreeSet<Version>,
+	dependency: &tg::Dependency,
+) -> Result<BTreeSet<Version>> {
+	let Some(constraint) = dependency.version.as_ref() else {
+		return Ok(universe.clone());
+	};
+	let constraint: semver::VersionReq = constraint
+		.parse()
+		.wrap_err_with(|| format!(r#"Failed to parse the version of "{dependency}"."#))?;
+	let versions = universe
+		.iter()
+		.filter(|version| constraint.matches(version))
+		.cloned()
+		.collect();
+	Ok(versions)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+	use super::{solve, Source};
+	use async_trait::async_trait;
+	use std::collections::BTreeMap;
+	use tangram_client as tg;
+	use tangram_error::{Result, WrapErr};
+
+	#[derive(Default)]
+	pub(crate) struct Registry {
+		packages: BTreeMap<String, BTreeMap<String, Vec<tg::Dependency>>>,
+	}
+
+	impl Registry {
+		pub(crate) fn add(&mut self, name: &str, version: &str, dependencies: &[&str]) {
+			let dependencies = dependencies
+				.iter()
+				.map(|dependency| dependency.parse().unwrap())
+				.collect();
+			self.packages
+				.entry(name.to_owned())
+				.or_default()
+				.insert(version.to_owned(), dependencies);
+		}
+	}
+
+	#[async_trait]
+	impl Source for Registry {
+		async fn versions(&self, name: &str) -> Result<Vec<String>> {
+			let versions = self
+				.packages
+				.get(name)
+				.map(|versions| versions.keys().cloned().collect())
+				.unwrap_or_default();
+			Ok(versions)
+		}
+
+		async fn dependencies(&self, name: &str, version: &str) -> Result<Vec<tg::Dependency>> {
+			let dependencies = self
+				.packages
+				.get(name)
+				.and_then(|versions| versions.get(version))
+				.wrap_err("Failed to find the package.")?;
+			Ok(dependencies.clone())
+		}
+	}
+
+	async fn solve_root(
+		registry: &Registry,
+		dependencies: &[(&str, &str)],
+		locked: &[(&str, &str)],
+	) -> Result<BTreeMap<String, String>> {
+		let dependencies = dependencies
+			.iter()
+			.map(|(dependant, dependency)| ((*dependant).to_owned(), dependency.parse().unwrap()))
+			.collect();
+		let locked = locked
+			.iter()
+			.map(|(name, version)| ((*name).to_owned(), version.parse().unwrap()))
+			.collect();
+		let solution = solve(registry, "root", dependencies, &locked).await?;
+		let solution = solution
+			.into_iter()
+			.map(|(name, version)| (name, version.to_string()))
+			.collect();
+		Ok(solution)
+	}
+
+	fn expected(versions: &[(&str, &str)]) -> BTreeMap<String, String> {
+		versions
+			.iter()
+			.map(|(name, version)| ((*name).to_owned(), (*version).to_owned()))
+			.collect()
+	}
+
+	#[tokio::test]
+	async fn simple_diamond() {
+		let mut registry = Registry::default();
+		registry.add("b", "1.0.0", &["d@^1.0"]);
+		registry.add("c", "1.0.0", &["d@^1.0"]);
+		registry.add("d", "1.0.0", &[]);
+		let solution = solve_root(&registry, &[("root", "b@^1.0"), ("root", "c@^1.0")], &[])
+			.await
+			.unwrap();
+		assert_eq!(
+			solution,
+			expected(&[("b", "1.0.0"), ("c", "1.0.0"), ("d", "1.0.0")])
+		);
+	}
+
+	#[tokio::test]
+	async fn simple_backtrack() {
+		let mut registry = Registry::default();
+		registry.add("b", "1.2.3", &["c@<1.2.3"]);
+		registry.add("c", "1.2.3", &[]);
+		registry.add("c", "1.2.2", &[]);
+		let solution = solve_root(
+			&registry,
+			&[("root", "b@^1.2.3"), ("root", "c@<1.2.3")],
+			&[],
+		)
+		.await
+		.unwrap();
+		assert_eq!(solution, expected(&[("b", "1.2.3"), ("c", "1.2.2")]));
+	}
+
+	#[tokio::test]
+	async fn diamond_backtrack() {
+		let mut registry = Registry::default();
+		registry.add("b", "1.0.0", &["d@<1.5.0"]);
+		registry.add("c", "1.0.0", &["d@<1.3.0"]);
+		for version in ["1.1.0", "1.2.0", "1.3.0", "1.4.0", "1.5.0"] {
+			registry.add("d", version, &[]);
+		}
+		let solution = solve_root(&registry, &[("root", "b@1.0.0"), ("root", "c@1.0.0")], &[])
+			.await
+			.unwrap();
+		assert_eq!(
+			solution,
+			expected(&[("b", "1.0.0"), ("c", "1.0.0"), ("d", "1.2.0")])
+		);
+	}
+
+	#[tokio::test]
+	async fn backtrack_to_older_dependant() {
+		let mut registry = Registry::default();
+		registry.add("b", "1.0.0", &["c@^1.0"]);
+		registry.add("b", "1.1.0", &["c@^2.0"]);
+		registry.add("c", "1.0.0", &[]);
+		registry.add("c", "2.0.0", &[]);
+		registry.add("d", "1.0.0", &["c@^1.0"]);
+		let solution = solve_root(&registry, &[("root", "b@^1.0"), ("root", "d@^1.0")], &[])
+			.await
+			.unwrap();
+		assert_eq!(
+			solution,
+			expected(&[("b", "1.0.0"), ("c", "1.0.0"), ("d", "1.0.0")])
+		);
+	}
+
+	#[tokio::test]
+	async fn cycle_exists() {
+		let mut registry = Registry::default();
+		registry.add("b", "1.0.0", &["c@1.0.0"]);
+		registry.add("c", "1.0.0", &["b@1.0.0"]);
+		let error = solve_root(&registry, &[("root", "b@1.0.0")], &[])
+			.await
+			.unwrap_err();
+		let message = error.to_string();
+		assert!(
+			message.contains("b@1.0.0 -> c@1.0.0 -> b@1.0.0 form a cycle"),
+			"{message}"
+		);
+	}
+
+	#[tokio::test]
+	async fn cycle_avoided() {
+		let mut registry = Registry::default();
+		registry.add("b", "1.0.0", &["c@^1.0"]);
+		registry.add("c", "1.0.0", &[]);
+		registry.add("c", "1.1.0", &["b@^1.0"]);
+		let solution = solve_root(&registry, &[("root", "b@^1.0")], &[])
+			.await
+			.unwrap();
+		assert_eq!(solution, expected(&[("b", "1.0.0"), ("c", "1.0.0")]));
+	}
+
+	#[tokio::test]
+	async fn diamond_incompatible_versions() {
+		let mut registry = Registry::default();
+		registry.add("b", "1.0.0", &["d@<1.2.0"]);
+		registry.add("c", "1.0.0", &["d@>1.3.0"]);
+		for version in ["1.0.0", "1.1.0", "1.2.0", "1.3.0", "1.4.0"] {
+			registry.add("d", version, &[]);
+		}
+		let error = solve_root(&registry, &[("root", "b@1.0.0"), ("root", "c@1.0.0")], &[])
+			.await
+			.unwrap_err();
+		let message = error.to_string();
+		let explanation = [
+			"Because b@1.0.0 depends on d@<1.2.0 and c@1.0.0 depends on d@>1.3.0, b@1.0.0 is incompatible with c@1.0.0.",
+			"And because root depends on b@1.0.0, root is incompatible with c@1.0.0.",
+			"And because root depends on c@1.0.0, version solving failed.",
+		]
+		.join("\n");
+		assert!(message.ends_with(&explanation), "{message}");
+	}
+
+	#[tokio::test]
+	async fn missing_version() {
+		let mut registry = Registry::default();
+		registry.add("b", "1.0.0", &[]);
+		let error = solve_root(&registry, &[("root", "b@^2.0")], &[])
+			.await
+			.unwrap_err();
+		let message = error.to_string();
+		assert!(
+			message.ends_with(
+				"Because root depends on b@^2.0, which matches no versions, version solving failed."
+			),
+			"{message}"
+		);
+	}
+
+	#[tokio::test]
+	async fn diamond_with_path_dependencies() {
+		let mut registry = Registry::default();
+		registry.add("c", "1.2.3", &[]);
+		registry.add("c", "1.3.0", &[]);
+		let solution = solve_root(&registry, &[("root", "c@^1.0"), ("a", "c@=1.2.3")], &[])
+			.await
+			.unwrap();
+		assert_eq!(solution, expected(&[("c", "1.2.3")]));
+	}
+
+	#[tokio::test]
+	async fn complex_diamond() {
+		let mut registry = Registry::default();
+		registry.add("b", "1.0.0", &["d@^1.0.0"]);
+		registry.add("c", "1.0.0", &["d@^1.0.0", "e@>1.0.0"]);
+		registry.add("d", "1.3.0", &["e@=1.0.0"]);
+		registry.add("d", "1.2.0", &["e@^1.0.0"]);
+		registry.add("e", "1.0.0", &[]);
+		registry.add("e", "1.1.0", &[]);
+		let dependencies = [
+			("root", "b@^1.0.0"),
+			("root", "e@^1.1.0"),
+			("root", "c@^1.0.0"),
+			("root", "d@^1.0.0"),
+		];
+		let solution = solve_root(&registry, &dependencies, &[]).await.unwrap();
+		assert_eq!(
+			solution,
+			expected(&[
+				("b", "1.0.0"),
+				("c", "1.0.0"),
+				("d", "1.2.0"),
+				("e", "1.1.0")
+			])
+		);
+	}
+
+	#[tokio::test]
+	async fn prefer_locked_versions() {
+		let mut registry = Registry::default();
+		registry.add("b", "1.0.0", &[]);
+		registry.add("b", "1.1.0", &[]);
+		registry.add("b", "1.2.0", &[]);
+		registry.add("c", "1.0.0", &[]);
+		registry.add("c", "2.0.0", &[]);
+		let dependencies = [("root", "b@^1.0"), ("root", "c@^1.0")];
+		let locked = [("b", "1.1.0"), ("c", "2.0.0")];
+		let solution = solve_root(&registry, &dependencies, &locked).await.unwrap();
+		assert_eq!(solution, expected(&[("b", "1.1.0"), ("c", "1.0.0")]));
+	}
+}
@@ -1,5 +1,4 @@
-use crate::util::render;
-use bytes::Bytes;
+use crate::util::{render, Redactor};
 use futures::{stream::FuturesOrdered, TryStreamExt};
 use indoc::writedoc;
 use std::{
@@ -19,6 +18,7 @@ pub async fn build(
 	build: &tg::Build,
 	_retry: tg::build::Retry,
 	server_directory_path: &Path,
+	secrets: &BTreeMap<String, String>,
 ) -> Result<tg::Value> {
 	// Get the target.
 	let target = build.target(tg).await?;
@@ -86,9 +86,19 @@ pub async fn build(
 		.try_collect()
 		.await?;
 
+	// Mounts are not supported by the sandbox.
+	if !target.mounts(tg).await?.is_empty() {
+		return_error!("Mounts are not supported on macOS.");
+	}
+
 	// Enable the network if a checksum was provided.
 	let network_enabled = target.checksum(tg).await?.is_some();
 
+	// Add the secrets to the env.
+	for (name, value) in secrets {
+		env.insert(name.clone(), value.clone());
+	}
+
 	// Set `$HOME`.
 	env.insert(
 		"HOME".to_owned(),
@@ -319,15 +329,24 @@ pub async fn build(
 	let log_task = tokio::task::spawn({
 		let build = build.clone();
 		let tg = tg.clone_box();
+		let mut redactor = Redactor::new(secrets.values());
 		async move {
 			let mut buf = [0; 512];
 			loop {
 				match stdout.read(&mut buf).await {
 					Err(error) => return Err(error.wrap("Failed to read from the log.")),
-					Ok(0) => return Ok(()),
+					Ok(0) => {
+						let log = redactor.finalize();
+						if !log.is_empty() {
+							build.add_log(tg.as_ref(), log).await?;
+						}
+						return Ok(());
+					},
 					Ok(size) => {
-						let log = Bytes::copy_from_slice(&buf[0..size]);
-						build.add_log(tg.as_ref(), log).await?;
+						let log = redactor.update(&buf[0..size]);
+						if !log.is_empty() {
+							build.add_log(tg.as_ref(), log).await?;
+						}
 					},
 				}
 			}
@@ -356,6 +375,23 @@ pub async fn build(
 		.await
 		.wrap_err("Failed to determine if the path exists.")?
 	{
+		// Check the output for references to host paths if requested.
+		if let Some(purity) = target.purity(tg).await? {
+			let server_directory_path = server_directory_path.to_str().unwrap();
+			let artifacts_directory_path = artifacts_directory_path.to_str().unwrap();
+			let mut forbidden = vec![
+				root_directory_path.to_str().unwrap().to_owned(),
+				output_parent_directory_path.to_str().unwrap().to_owned(),
+				format!("{server_directory_path}/"),
+			];
+			if let Some(home) = std::env::var("HOME").ok().filter(|home| home != "/") {
+				forbidden.push(home);
+			}
+			let allowed = vec![format!("{artifacts_directory_path}/")];
+			let rules = crate::purity::Rules { forbidden, allowed };
+			crate::purity::check(tg, build, purity, &output_path, rules).await?;
+		}
+
 		// Check in the output.
 		let artifact = tg::Artifact::check_in(tg, &output_path.clone().try_into()?)
 			.await
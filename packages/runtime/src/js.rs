@@ -1,34 +1,89 @@
 use self::{
 	convert::{from_v8, ToV8},
+	inspector::Inspector,
+	limits::Monitor,
 	syscall::syscall,
 };
+use base64::Engine as _;
 use futures::{future::LocalBoxFuture, stream::FuturesUnordered, StreamExt};
 use num::ToPrimitive;
 use sourcemap::SourceMap;
 use std::{
-	cell::RefCell, collections::BTreeMap, future::poll_fn, num::NonZeroI32, rc::Rc, str::FromStr,
+	cell::{OnceCell, RefCell},
+	collections::BTreeMap,
+	future::poll_fn,
+	net::SocketAddr,
+	num::NonZeroI32,
+	path::PathBuf,
+	rc::Rc,
+	str::FromStr,
 	task::Poll,
 };
 use tangram_client as tg;
 use tangram_error::{Result, WrapErr};
 
+pub use self::{
+	cache::ModuleCache,
+	limits::Limits,
+	profile::Profile,
+	repl::{Evaluation, Repl},
+};
+
+mod cache;
 mod convert;
 mod error;
+mod inspector;
+mod limits;
+mod profile;
+mod repl;
 mod syscall;
 
 const SNAPSHOT: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/runtime.heapsnapshot"));
 
 const SOURCE_MAP: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/main.js.map"));
 
+/// Options for a JS build.
+#[derive(Clone, Debug, Default)]
+pub struct Options {
+	/// The build's limits.
+	pub limits: Limits,
+
+	/// The address to listen for a debugger at. The build waits for a debugger to connect before it starts.
+	pub inspect: Option<SocketAddr>,
+
+	/// Whether to record a profile of the build.
+	pub profile: Option<tg::build::ProfileOptions>,
+
+	/// The cache to store transpiled modules in.
+	pub module_cache: Option<ModuleCache>,
+
+	/// The directory of vendored files that downloads may read with `file://` URLs. If this is `None`, then `file://` URLs are not allowed.
+	pub vendor_path: Option<PathBuf>,
+}
+
+/// The output of a JS build.
+#[derive(Debug)]
+pub struct Output {
+	/// The result of the build.
+	pub result: Result<tg::Value>,
+
+	/// The build's profile, if one was requested.
+	pub profile: Option<Profile>,
+}
+
 struct State {
-	build: tg::Build,
+	/// The build, which is `None` in a REPL.
+	build: Option<tg::Build>,
 	depth: u64,
 	futures: RefCell<Futures>,
 	global_source_map: Option<SourceMap>,
+	inspect: bool,
+	module_cache: Option<ModuleCache>,
 	modules: RefCell<Vec<Module>>,
 	main_runtime_handle: tokio::runtime::Handle,
 	retry: tg::build::Retry,
 	tg: Box<dyn tg::Handle>,
+	vendor_path: Option<PathBuf>,
 }
 
 type Futures = FuturesUnordered<
@@ -37,196 +92,328 @@ type Futures = FuturesUnordered<
 
 struct Module {
 	module: tangram_language::Module,
-	source_map: Option<SourceMap>,
+	source_map: OnceCell<Option<SourceMap>>,
+	source_map_text: String,
 	metadata: Option<tg::package::Metadata>,
 	v8_identity_hash: NonZeroI32,
 	v8_module: v8::Global<v8::Module>,
 }
 
-std::thread_local! {
-	pub static THREAD_LOCAL_ISOLATE: Rc<RefCell<v8::OwnedIsolate>> = {
-		// Create the isolate params.
-		let params = v8::CreateParams::default().snapshot_blob(SNAPSHOT);
-
-		// Create the isolate.
-		let mut isolate = v8::Isolate::new(params);
-
-		// Set the host import module dynamically callback.
-		isolate.set_host_import_module_dynamically_callback(host_import_module_dynamically_callback);
-
-		// Set the host initialize import meta object callback.
-		isolate.set_host_initialize_import_meta_object_callback(
-			host_initialize_import_meta_object_callback,
-		);
+impl Module {
+	/// Get the module's source map, which is parsed the first time it is used.
+	fn source_map(&self) -> Option<&SourceMap> {
+		self.source_map
+			.get_or_init(|| SourceMap::from_slice(self.source_map_text.as_bytes()).ok())
+			.as_ref()
+	}
+}
 
-		Rc::new(RefCell::new(isolate))
-	};
+pub async fn build(
+	tg: &dyn tg::Handle,
+	build: &tg::Build,
+	depth: u64,
+	retry: tg::build::Retry,
+	options: Options,
+	main_runtime_handle: tokio::runtime::Handle,
+) -> Output {
+	let mut profile = None;
+	let result = build_inner(
+		tg,
+		build,
+		depth,
+		retry,
+		options,
+		main_runtime_handle,
+		&mut profile,
+	)
+	.await;
+	Output { result, profile }
 }
 
 #[allow(clippy::too_many_lines)]
-pub async fn build(
+async fn build_inner(
 	tg: &dyn tg::Handle,
 	build: &tg::Build,
 	depth: u64,
 	retry: tg::build::Retry,
+	options: Options,
 	main_runtime_handle: tokio::runtime::Handle,
+	profile: &mut Option<Profile>,
 ) -> Result<tg::Value> {
+	let Options {
+		limits,
+		inspect,
+		profile: profile_options,
+		module_cache,
+		vendor_path,
+	} = options;
+
 	// Get the target.
 	let target = build.target(tg).await?;
 
+	// Create the isolate.
+	let mut isolate = create_isolate(&limits);
+
+	// Start the monitor. It must be declared before the isolate so that it is dropped after it.
+	let monitor = Monitor::start(&mut isolate, limits, &main_runtime_handle);
+	let isolate = Rc::new(RefCell::new(isolate));
+
+	// Create the inspector if the build will be inspected or profiled. It must be declared after the isolate so that it is dropped before it.
+	let mut inspector = (inspect.is_some() || profile_options.is_some())
+		.then(|| Inspector::new(isolate.borrow_mut().as_mut()));
+
+	// Start listening for a debugger if requested.
+	if let (Some(inspector), Some(addr)) = (inspector.as_mut(), inspect) {
+		inspector.start_debugger(build, addr, &main_runtime_handle)?;
+	}
+
 	// Create the state.
 	let state = Rc::new(State {
-		build: build.clone(),
+		build: Some(build.clone()),
 		tg: tg.clone_box(),
 		depth,
 		futures: RefCell::new(FuturesUnordered::new()),
 		global_source_map: Some(SourceMap::from_slice(SOURCE_MAP).unwrap()),
+		inspect: inspect.is_some(),
+		module_cache,
 		modules: RefCell::new(Vec::new()),
 		main_runtime_handle,
 		retry,
+		vendor_path,
 	});
 
 	// Create the context.
-	let context = {
-		// Create and enter the context.
-		let isolate = THREAD_LOCAL_ISOLATE.with(Rc::clone);
-		let mut isolate = isolate.borrow_mut();
-		let isolate = isolate.as_mut();
-		let scope = &mut v8::HandleScope::new(isolate);
-		let context = v8::Context::new(scope);
-		let scope = &mut v8::ContextScope::new(scope, context);
-
-		// Set the state on the context.
-		context.set_slot(scope, state.clone());
-
-		// Create the syscall function.
-		let syscall_string =
-			v8::String::new_external_onebyte_static(scope, "syscall".as_bytes()).unwrap();
-		let syscall = v8::Function::new(scope, syscall).unwrap();
-		let global = context.global(scope);
-		global
-			.set(scope, syscall_string.into(), syscall.into())
-			.unwrap();
-
-		v8::Global::new(scope, context)
-	};
-
-	let value = {
-		// Enter the context.
-		let isolate = THREAD_LOCAL_ISOLATE.with(Rc::clone);
-		let mut isolate = isolate.borrow_mut();
-		let isolate = isolate.as_mut();
-		let scope = &mut v8::HandleScope::new(isolate);
-		let context = v8::Local::new(scope, context.clone());
-		let scope = &mut v8::ContextScope::new(scope, context);
-
-		// Get the tg global.
-		let global = context.global(scope);
-		let tg = v8::String::new_external_onebyte_static(scope, "tg".as_bytes()).unwrap();
-		let tg = global.get(scope, tg.into()).unwrap();
-		let tg = v8::Local::<v8::Object>::try_from(tg).unwrap();
-
-		// Get the start function.
-		let start = v8::String::new_external_onebyte_static(scope, "start".as_bytes()).unwrap();
-		let start = tg.get(scope, start.into()).unwrap();
-		let start = v8::Local::<v8::Function>::try_from(start).unwrap();
-
-		// Call the start function.
-		let undefined = v8::undefined(scope);
-		let target = target.to_v8(scope).unwrap();
-		let value = start.call(scope, undefined.into(), &[target]).unwrap();
-
-		v8::Global::new(scope, value)
-	};
-
-	// Await the output.
-	let value = poll_fn(|cx| {
-		loop {
-			// Poll the futures.
-			let (result, promise_resolver) = match state.futures.borrow_mut().poll_next_unpin(cx) {
-				// If there is a result, then resolve or reject the promise.
-				Poll::Ready(Some((result, promise_resolver))) => (result, promise_resolver),
-
-				// If there are no more results, then break.
-				Poll::Ready(None) => break,
+	let context = create_context(isolate.borrow_mut().as_mut(), &state, inspector.as_mut());
+
+	// Wait for a debugger to connect to the inspector.
+	if let Some(inspector) = inspector.as_ref() {
+		if let Some(url) = inspector.url() {
+			build
+				.add_log(tg, format!("Waiting for a debugger at {url}.\n").into())
+				.await?;
+			inspector.wait_for_debugger().await?;
+		}
+	}
 
-				// If the futures are not ready, then return pending.
-				Poll::Pending => return Poll::Pending,
-			};
+	// Start the profiler if requested.
+	if let (Some(inspector), Some(_)) = (inspector.as_mut(), profile_options) {
+		inspector.start_profiler()?;
+	}
 
+	// Run the build in an async block so that the profile is recorded even if the build fails.
+	let mut result = async {
+		let value = {
 			// Enter the context.
-			let isolate = THREAD_LOCAL_ISOLATE.with(Rc::clone);
 			let mut isolate = isolate.borrow_mut();
 			let isolate = isolate.as_mut();
 			let scope = &mut v8::HandleScope::new(isolate);
 			let context = v8::Local::new(scope, context.clone());
 			let scope = &mut v8::ContextScope::new(scope, context);
 
-			// Resolve or reject the promise.
-			let promise_resolver = v8::Local::new(scope, promise_resolver);
-			match result.and_then(|value| value.to_v8(scope)) {
-				Ok(value) => {
-					// Resolve the promise.
-					promise_resolver.resolve(scope, value);
-				},
-				Err(error) => {
-					// Reject the promise.
-					let exception = error::to_exception(scope, &error);
-					promise_resolver.reject(scope, exception);
-				},
+			// Get the tg global.
+			let global = context.global(scope);
+			let tg = v8::String::new_external_onebyte_static(scope, "tg".as_bytes()).unwrap();
+			let tg = global.get(scope, tg.into()).unwrap();
+			let tg = v8::Local::<v8::Object>::try_from(tg).unwrap();
+
+			// Get the start function.
+			let start = v8::String::new_external_onebyte_static(scope, "start".as_bytes()).unwrap();
+			let start = tg.get(scope, start.into()).unwrap();
+			let start = v8::Local::<v8::Function>::try_from(start).unwrap();
+
+			// Call the start function.
+			let undefined = v8::undefined(scope);
+			let target = target.to_v8(scope).unwrap();
+			let value = {
+				let _guard = monitor.enter();
+				start.call(scope, undefined.into(), &[target])
 			};
-		}
 
-		// Get the result from the value.
-		let isolate = THREAD_LOCAL_ISOLATE.with(Rc::clone);
-		let mut isolate = isolate.borrow_mut();
-		let isolate = isolate.as_mut();
-		let scope = &mut v8::HandleScope::new(isolate);
-		let context = v8::Local::new(scope, context.clone());
-		let scope = &mut v8::ContextScope::new(scope, context);
-		let value = v8::Local::new(scope, value.clone());
-		let result = if let Ok(promise) = v8::Local::<v8::Promise>::try_from(value) {
-			// If the output is a promise, check its state.
-			match promise.state() {
-				// If the promise is fulfilled, then return the result.
-				v8::PromiseState::Fulfilled => {
-					let output = promise.result(scope);
-					let output = match from_v8(scope, output) {
-						Ok(output) => output,
-						Err(error) => {
-							return Poll::Ready(Err(error));
-						},
-					};
-					Ok(output)
-				},
-
-				// If the promise is rejected, then return the error.
-				v8::PromiseState::Rejected => {
-					let exception = promise.result(scope);
-					let state = state.clone();
-					let error = self::error::from_exception(&state, scope, exception);
-					Err(error)
-				},
-
-				// At this point, the promise must not be pending.
-				v8::PromiseState::Pending => unreachable!(),
+			// If execution was terminated because a limit was exceeded, then return an error.
+			let Some(value) = value else {
+				return Err(monitor.error().unwrap_or_else(|| {
+					tangram_error::error!("Failed to call the start function.")
+				}));
+			};
+
+			v8::Global::new(scope, value)
+		};
+
+		// Await the output.
+		let future = poll_fn(|cx| {
+			// Dispatch messages from the debugger.
+			if let Some(inspector) = inspector.as_ref() {
+				inspector.poll(cx);
 			}
-		} else {
-			// If the output is not a promise, then return it.
-			let output = match from_v8(scope, value) {
-				Ok(output) => output,
-				Err(error) => {
+
+			loop {
+				// Poll the futures.
+				let (result, promise_resolver) =
+					match state.futures.borrow_mut().poll_next_unpin(cx) {
+						// If there is a result, then resolve or reject the promise.
+						Poll::Ready(Some((result, promise_resolver))) => (result, promise_resolver),
+
+						// If there are no more results, then break.
+						Poll::Ready(None) => break,
+
+						// If the futures are not ready, then return pending.
+						Poll::Pending => return Poll::Pending,
+					};
+
+				// Enter the context.
+				let mut isolate = isolate.borrow_mut();
+				let isolate = isolate.as_mut();
+				let scope = &mut v8::HandleScope::new(isolate);
+				let context = v8::Local::new(scope, context.clone());
+				let scope = &mut v8::ContextScope::new(scope, context);
+
+				// Resolve or reject the promise.
+				let promise_resolver = v8::Local::new(scope, promise_resolver);
+				let guard = monitor.enter();
+				match result.and_then(|value| value.to_v8(scope)) {
+					Ok(value) => {
+						// Resolve the promise.
+						promise_resolver.resolve(scope, value);
+					},
+					Err(error) => {
+						// Reject the promise.
+						let exception = error::to_exception(scope, &error);
+						promise_resolver.reject(scope, exception);
+					},
+				};
+				drop(guard);
+
+				// If execution was terminated because a limit was exceeded, then return an error.
+				if let Some(error) = monitor.error() {
 					return Poll::Ready(Err(error));
-				},
+				}
+			}
+
+			// Get the result from the value.
+			let mut isolate = isolate.borrow_mut();
+			let isolate = isolate.as_mut();
+			let scope = &mut v8::HandleScope::new(isolate);
+			let context = v8::Local::new(scope, context.clone());
+			let scope = &mut v8::ContextScope::new(scope, context);
+			let value = v8::Local::new(scope, value.clone());
+			let result = if let Ok(promise) = v8::Local::<v8::Promise>::try_from(value) {
+				// If the output is a promise, check its state.
+				match promise.state() {
+					// If the promise is fulfilled, then return the result.
+					v8::PromiseState::Fulfilled => {
+						let output = promise.result(scope);
+						let output = match from_v8(scope, output) {
+							Ok(output) => output,
+							Err(error) => {
+								return Poll::Ready(Err(error));
+							},
+						};
+						Ok(output)
+					},
+
+					// If the promise is rejected, then return the error.
+					v8::PromiseState::Rejected => {
+						let exception = promise.result(scope);
+						let state = state.clone();
+						let error = self::error::from_exception(&state, scope, exception);
+						Err(error)
+					},
+
+					// At this point, the promise must not be pending.
+					v8::PromiseState::Pending => unreachable!(),
+				}
+			} else {
+				// If the output is not a promise, then return it.
+				let output = match from_v8(scope, value) {
+					Ok(output) => output,
+					Err(error) => {
+						return Poll::Ready(Err(error));
+					},
+				};
+				Ok(output)
 			};
-			Ok(output)
+
+			Poll::Ready(result)
+		});
+
+		// Await the output, failing if the time limit is exceeded while waiting.
+		let value = if let Some(remaining_time) = monitor.remaining_time() {
+			tokio::time::timeout(remaining_time, future)
+				.await
+				.map_err(|_| monitor.time_exceeded())??
+		} else {
+			future.await?
 		};
 
-		Poll::Ready(result)
-	})
-	.await?;
+		Ok(value)
+	}
+	.await;
+
+	// Stop the profiler and record the profile.
+	if let (Some(inspector), Some(options)) = (inspector.as_mut(), profile_options) {
+		let mut isolate = isolate.borrow_mut();
+		match profile::record(&state, isolate.as_mut(), inspector, options) {
+			Ok(output) => {
+				profile.replace(output);
+			},
+			Err(error) => {
+				result = result.and(Err(error));
+			},
+		}
+	}
+
+	result
+}
+
+/// Create an isolate from the runtime's snapshot.
+fn create_isolate(limits: &Limits) -> v8::OwnedIsolate {
+	// Create the isolate params.
+	let params = v8::CreateParams::default().snapshot_blob(SNAPSHOT);
+	let params = limits.create_params(params);
+
+	// Create the isolate.
+	let mut isolate = v8::Isolate::new(params);
+
+	// Set the host import module dynamically callback.
+	isolate.set_host_import_module_dynamically_callback(host_import_module_dynamically_callback);
+
+	// Set the host initialize import meta object callback.
+	isolate.set_host_initialize_import_meta_object_callback(
+		host_initialize_import_meta_object_callback,
+	);
 
-	Ok(value)
+	isolate
+}
+
+/// Create a context with the state and the syscall function.
+fn create_context(
+	isolate: &mut v8::Isolate,
+	state: &Rc<State>,
+	inspector: Option<&mut Inspector>,
+) -> v8::Global<v8::Context> {
+	// Create and enter the context.
+	let scope = &mut v8::HandleScope::new(isolate);
+	let context = v8::Context::new(scope);
+	let scope = &mut v8::ContextScope::new(scope, context);
+
+	// Set the state on the context.
+	context.set_slot(scope, state.clone());
+
+	// Create the syscall function.
+	let syscall_string =
+		v8::String::new_external_onebyte_static(scope, "syscall".as_bytes()).unwrap();
+	let syscall = v8::Function::new(scope, syscall).unwrap();
+	let global = context.global(scope);
+	global
+		.set(scope, syscall_string.into(), syscall.into())
+		.unwrap();
+
+	// Notify the inspector that the context was created.
+	if let Some(inspector) = inspector {
+		inspector.context_created(context);
+	}
+
+	v8::Global::new(scope, context)
 }
 
 /// Implement V8's dynamic import callback.
@@ -241,6 +428,10 @@ fn host_import_module_dynamically_callback<'s>(
 	let resource_name = resource_name.to_string(scope).unwrap();
 	let resource_name = resource_name.to_rust_string_lossy(scope);
 
+	// Get the state.
+	let context = scope.get_current_context();
+	let state = context.get_slot::<Rc<State>>(scope).unwrap().clone();
+
 	// Get the module.
 	let module = if resource_name == "[runtime]" {
 		let module = specifier.to_rust_string_lossy(scope);
@@ -252,6 +443,10 @@ fn host_import_module_dynamically_callback<'s>(
 				return None;
 			},
 		}
+	} else if resource_name.is_empty() && state.build.is_none() {
+		// Resolve an import from the REPL.
+		let specifier = specifier.to_rust_string_lossy(scope);
+		repl::resolve_module(scope, &specifier)?
 	} else {
 		// Get the module.
 		let module = match tangram_language::Module::from_str(&resource_name) {
@@ -264,7 +459,7 @@ fn host_import_module_dynamically_callback<'s>(
 		};
 
 		// Parse the import.
-		let Some(import) = parse_import(scope, specifier, attributes) else {
+		let Some(import) = parse_import(scope, specifier, attributes, 2) else {
 			return None;
 		};
 
@@ -327,7 +522,7 @@ fn resolve_module_callback<'s>(
 	};
 
 	// Parse the import.
-	let Some(import) = parse_import(scope, specifier, attributes) else {
+	let Some(import) = parse_import(scope, specifier, attributes, 3) else {
 		return None;
 	};
 
@@ -400,29 +595,6 @@ fn load_module<'s>(
 		return Some(module);
 	}
 
-	// Define the module's origin.
-	let resource_name = v8::String::new(scope, &module.to_string()).unwrap();
-	let resource_line_offset = 0;
-	let resource_column_offset = 0;
-	let resource_is_shared_cross_origin = false;
-	let script_id = state.modules.borrow().len().to_i32().unwrap() + 1;
-	let source_map_url = v8::undefined(scope).into();
-	let resource_is_opaque = true;
-	let is_wasm = false;
-	let is_module = true;
-	let origin = v8::ScriptOrigin::new(
-		scope,
-		resource_name.into(),
-		resource_line_offset,
-		resource_column_offset,
-		resource_is_shared_cross_origin,
-		script_id,
-		source_map_url,
-		resource_is_opaque,
-		is_wasm,
-		is_module,
-	);
-
 	// Load the module.
 	let (sender, receiver) = std::sync::mpsc::channel();
 	state.main_runtime_handle.spawn({
@@ -446,12 +618,23 @@ fn load_module<'s>(
 		},
 	};
 
-	// Transpile the module.
-	let tangram_language::transpile::Output {
+	// Transpile the module, or get its transpiled text and source map from the cache.
+	let key = ModuleCache::key(&text);
+	let cached = state.module_cache.as_ref().and_then(|cache| {
+		cache
+			.get(&key)
+			.inspect_err(|error| tracing::warn!(?error, "Failed to read the module cache."))
+			.ok()
+			.flatten()
+	});
+	let cache::Entry {
 		transpiled_text,
 		source_map,
-	} =
-		match tangram_language::Module::transpile(text).wrap_err("Failed to transpile the module.")
+	} = if let Some(entry) = cached {
+		entry
+	} else {
+		let output = match tangram_language::Module::transpile(text)
+			.wrap_err("Failed to transpile the module.")
 		{
 			Ok(output) => output,
 			Err(error) => {
@@ -460,32 +643,121 @@ fn load_module<'s>(
 				return None;
 			},
 		};
+		let entry = cache::Entry {
+			transpiled_text: output.transpiled_text,
+			source_map: output.source_map,
+		};
+		if let Some(cache) = state.module_cache.as_ref() {
+			if let Err(error) = cache.put(&key, &entry) {
+				tracing::warn!(?error, "Failed to write the module cache.");
+			}
+		}
+		entry
+	};
 
-	// Parse the source map.
-	let source_map = match SourceMap::from_slice(source_map.as_bytes())
-		.wrap_err("Failed to parse the source map.")
-	{
-		Ok(source_map) => source_map,
-		Err(error) => {
+	// If the inspector is enabled, then give the source map to the debugger as a data URL.
+	let source_map_url: v8::Local<v8::Value> = if state.inspect {
+		let mut source_map = match SourceMap::from_slice(source_map.as_bytes())
+			.wrap_err("Failed to parse the source map.")
+		{
+			Ok(source_map) => source_map,
+			Err(error) => {
+				let exception = error::to_exception(scope, &error);
+				scope.throw_exception(exception);
+				return None;
+			},
+		};
+		if let tangram_language::Module::Normal(module) = module {
+			source_map.set_source(0, &module.path.to_string());
+		}
+		let mut bytes = Vec::new();
+		if let Err(error) = source_map
+			.to_writer(&mut bytes)
+			.wrap_err("Failed to serialize the source map.")
+		{
 			let exception = error::to_exception(scope, &error);
 			scope.throw_exception(exception);
 			return None;
-		},
+		}
+		let data = base64::engine::general_purpose::STANDARD.encode(bytes);
+		let url = format!("data:application/json;base64,{data}");
+		v8::String::new(scope, &url).unwrap().into()
+	} else {
+		v8::undefined(scope).into()
 	};
 
-	// Compile the module.
+	// Define the module's origin.
+	let resource_name = v8::String::new(scope, &module.to_string()).unwrap();
+	let resource_line_offset = 0;
+	let resource_column_offset = 0;
+	let resource_is_shared_cross_origin = false;
+	let script_id = state.modules.borrow().len().to_i32().unwrap() + 1;
+	let resource_is_opaque = true;
+	let is_wasm = false;
+	let is_module = true;
+	let origin = v8::ScriptOrigin::new(
+		scope,
+		resource_name.into(),
+		resource_line_offset,
+		resource_column_offset,
+		resource_is_shared_cross_origin,
+		script_id,
+		source_map_url,
+		resource_is_opaque,
+		is_wasm,
+		is_module,
+	);
+
+	// Compile the module, consuming the code cache data if it is available. V8 falls back to compiling the source if the data is rejected.
+	let code_cache = state.module_cache.as_ref().and_then(|cache| {
+		cache
+			.get_code_cache(&key)
+			.inspect_err(|error| tracing::warn!(?error, "Failed to read the code cache."))
+			.ok()
+			.flatten()
+	});
 	let source = v8::String::new(scope, &transpiled_text).unwrap();
-	let source = v8::script_compiler::Source::new(source, Some(&origin));
-	let Some(v8_module) = v8::script_compiler::compile_module(scope, source) else {
+	let v8_module = if let Some(code_cache) = code_cache.as_ref() {
+		let cached_data = v8::script_compiler::CachedData::new(code_cache);
+		let source =
+			v8::script_compiler::Source::new_with_cached_data(source, Some(&origin), cached_data);
+		v8::script_compiler::compile_module2(
+			scope,
+			source,
+			v8::script_compiler::CompileOptions::ConsumeCodeCache,
+			v8::script_compiler::NoCacheReason::NoReason,
+		)
+	} else {
+		let source = v8::script_compiler::Source::new(source, Some(&origin));
+		v8::script_compiler::compile_module(scope, source)
+	};
+	let Some(v8_module) = v8_module else {
 		return None;
 	};
 
+	// Store the code cache data if it was not available or if V8 rejected it. The bindings do not expose whether the data was rejected, so the entry is rewritten whenever the compiled module's data differs from the stored data.
+	if let Some(cache) = state.module_cache.as_ref() {
+		let unbound_module_script = v8_module.get_unbound_module_script(scope);
+		if let Some(new_code_cache) = unbound_module_script.create_code_cache() {
+			if code_cache.as_deref() != Some(&**new_code_cache) {
+				if let Err(error) = cache.put_code_cache(&key, &new_code_cache) {
+					tracing::warn!(?error, "Failed to write the code cache.");
+				}
+			}
+		}
+	}
+
 	// Get the metadata.
 	let (sender, receiver) = std::sync::mpsc::channel();
 	state.main_runtime_handle.spawn({
 		let tg = state.tg.clone_box();
 		let module = module.clone();
 		async move {
+			// The metadata of a file is the metadata of the package that contains it.
+			let module = match module {
+				tangram_language::Module::File(file) => *file.module,
+				module => module,
+			};
 			let module = module.unwrap_normal_ref();
 			let package = tg::Directory::with_id(module.package.clone());
 			let metadata = tangram_package::metadata(tg.as_ref(), &package).await.ok();
@@ -498,7 +770,8 @@ fn load_module<'s>(
 	state.modules.borrow_mut().push(Module {
 		module: module.clone(),
 		metadata,
-		source_map: Some(source_map),
+		source_map: OnceCell::new(),
+		source_map_text: source_map,
 		v8_identity_hash: v8_module.get_identity_hash(),
 		v8_module: v8::Global::new(scope, v8_module),
 	});
@@ -535,12 +808,14 @@ extern "C" fn host_initialize_import_meta_object_callback(
 	meta.set(scope, key.into(), value.into()).unwrap();
 }
 
+/// Parse an import. The attributes are a list of keys and values, each followed by a source offset if the import is static.
 fn parse_import<'s>(
 	scope: &mut v8::HandleScope<'s>,
 	specifier: v8::Local<'s, v8::String>,
 	attributes: v8::Local<'s, v8::FixedArray>,
+	entry_size: usize,
 ) -> Option<tangram_language::Import> {
-	match parse_import_inner(scope, specifier, attributes) {
+	match parse_import_inner(scope, specifier, attributes, entry_size) {
 		Ok(import) => Some(import),
 		Err(error) => {
 			let exception = error::to_exception(scope, &error);
@@ -554,6 +829,7 @@ fn parse_import_inner<'s>(
 	scope: &mut v8::HandleScope<'s>,
 	specifier: v8::Local<'s, v8::String>,
 	attributes: v8::Local<'s, v8::FixedArray>,
+	entry_size: usize,
 ) -> Result<tangram_language::Import> {
 	// Get the specifier.
 	let specifier = specifier.to_rust_string_lossy(scope);
@@ -561,22 +837,19 @@ fn parse_import_inner<'s>(
 	// Get the attributes.
 	let attributes = if attributes.length() > 0 {
 		let mut map = BTreeMap::default();
-		let mut i = 0;
-		while i < attributes.length() % 2 {
+		for i in (0..attributes.length()).step_by(entry_size) {
 			let key = attributes
 				.get(scope, i)
 				.wrap_err("Failed to get the key.")?;
 			let key =
 				v8::Local::<v8::Value>::try_from(key).wrap_err("Failed to convert the key.")?;
 			let key = key.to_rust_string_lossy(scope);
-			i += 1;
 			let value = attributes
-				.get(scope, i)
+				.get(scope, i + 1)
 				.wrap_err("Failed to get the value.")?;
 			let value =
 				v8::Local::<v8::Value>::try_from(value).wrap_err("Failed to convert the value.")?;
 			let value = value.to_rust_string_lossy(scope);
-			i += 1;
 			map.insert(key, value);
 		}
 		Some(map)
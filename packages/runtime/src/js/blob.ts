@@ -15,7 +15,7 @@ export let blob = async (...args: Args<Blob.Arg>) => {
 };
 
 export let download = async (
-	url: string,
+	url: string | Array<string>,
 	checksum: Checksum,
 ): Promise<Blob> => {
 	return await Blob.download(url, checksum);
@@ -33,7 +33,15 @@ export namespace Blob {
 
 	export type Object_ = Array<[Blob, number]> | Uint8Array;
 
-	export type ArchiveFormat = ".tar" | ".zip";
+	export type ArchiveFormat = ".ar" | ".cpio" | ".tar" | ".zip";
+
+	export type ArchiveOptions = {
+		references?: boolean;
+	};
+
+	export type ExtractOptions = {
+		stripComponents?: number;
+	};
 
 	export type CompressionFormat =
 		| ".bz2"
@@ -113,16 +121,18 @@ export namespace Blob {
 	};
 
 	export let download = async (
-		url: string,
+		url: string | Array<string>,
 		checksum: Checksum,
 	): Promise<Blob> => {
-		return await syscall.download(url, checksum);
+		let urls = typeof url === "string" ? [url] : url;
+		return await syscall.download(urls, checksum);
 	};
 
 	export let archive = async (
 		artifact: Artifact,
 		format: Blob.ArchiveFormat,
+		options?: Blob.ArchiveOptions,
 	): Promise<Blob> => {
-		return await syscall.archive(artifact, format);
+		return await syscall.archive(artifact, format, options);
 	};
 }
@@ -0,0 +1,189 @@
+use std::{
+	io::Write,
+	path::{Path, PathBuf},
+};
+use tangram_error::{Result, WrapErr};
+
+/// A persistent cache of transpiled modules and their V8 code cache data, which is shared between builds.
+#[derive(Clone, Debug)]
+pub struct ModuleCache {
+	path: PathBuf,
+}
+
+/// The transpiled text and source map of a module.
+pub(super) struct Entry {
+	pub(super) transpiled_text: String,
+	pub(super) source_map: String,
+}
+
+impl ModuleCache {
+	/// Create a module cache that stores its entries in the directory at `path`.
+	#[must_use]
+	pub fn new(path: impl Into<PathBuf>) -> Self {
+		Self { path: path.into() }
+	}
+
+	/// Get the key of a module's text. The key includes the versions of the transpiler and V8 so that entries are invalidated when either changes.
+	pub(super) fn key(text: &str) -> String {
+		let mut hasher = blake3::Hasher::new();
+		hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+		hasher.update(b"\0");
+		hasher.update(&tangram_language::transpile::VERSION.to_le_bytes());
+		hasher.update(b"\0");
+		hasher.update(v8::V8::get_version().as_bytes());
+		hasher.update(b"\0");
+		hasher.update(text.as_bytes());
+		hasher.finalize().to_hex().to_string()
+	}
+
+	/// Get the transpiled text and source map for a key.
+	pub(super) fn get(&self, key: &str) -> Result<Option<Entry>> {
+		let Some(transpiled_text) = read(&self.path.join(format!("{key}.js")))? else {
+			return Ok(None);
+		};
+		let Some(source_map) = read(&self.path.join(format!("{key}.js.map")))? else {
+			return Ok(None);
+		};
+		let transpiled_text = String::from_utf8(transpiled_text)
+			.wrap_err("The transpiled text is not valid UTF-8.")?;
+		let source_map =
+			String::from_utf8(source_map).wrap_err("The source map is not valid UTF-8.")?;
+		Ok(Some(Entry {
+			transpiled_text,
+			source_map,
+		}))
+	}
+
+	/// Store the transpiled text and source map for a key.
+	pub(super) fn put(&self, key: &str, entry: &Entry) -> Result<()> {
+		// Write the source map first, because an entry is only read if its transpiled text exists.
+		write(
+			&self.path,
+			&format!("{key}.js.map"),
+			entry.source_map.as_bytes(),
+		)?;
+		write(
+			&self.path,
+			&format!("{key}.js"),
+			entry.transpiled_text.as_bytes(),
+		)?;
+		Ok(())
+	}
+
+	/// Get the V8 code cache data for a key.
+	pub(super) fn get_code_cache(&self, key: &str) -> Result<Option<Vec<u8>>> {
+		read(&self.path.join(format!("{key}.code_cache")))
+	}
+
+	/// Store the V8 code cache data for a key.
+	pub(super) fn put_code_cache(&self, key: &str, bytes: &[u8]) -> Result<()> {
+		write(&self.path, &format!("{key}.code_cache"), bytes)
+	}
+}
+
+/// Read a file, returning `None` if it does not exist.
+fn read(path: &Path) -> Result<Option<Vec<u8>>> {
+	match std::fs::read(path) {
+		Ok(bytes) => Ok(Some(bytes)),
+		Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+		Err(error) => {
+			Err(error).wrap_err_with(|| format!(r#"Failed to read "{}"."#, path.display()))
+		},
+	}
+}
+
+/// Write a file atomically, so that concurrent builds never read a partially written file.
+fn write(directory: &Path, name: &str, bytes: &[u8]) -> Result<()> {
+	std::fs::create_dir_all(directory).wrap_err("Failed to create the cache directory.")?;
+	let mut file =
+		tempfile::NamedTempFile::new_in(directory).wrap_err("Failed to create the file.")?;
+	file.write_all(bytes)
+		.wrap_err("Failed to write the file.")?;
+	file.persist(directory.join(name))
+		.wrap_err("Failed to persist the file.")?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn entry(transpiled_text: &str, source_map: &str) -> Entry {
+		Entry {
+			transpiled_text: transpiled_text.to_owned(),
+			source_map: source_map.to_owned(),
+		}
+	}
+
+	#[test]
+	fn key_is_stable() {
+		let key = ModuleCache::key("export default 1;");
+		assert_eq!(key, ModuleCache::key("export default 1;"));
+		assert_eq!(key.len(), 64);
+		assert!(key.chars().all(|c| c.is_ascii_hexdigit()));
+		assert_ne!(key, ModuleCache::key("export default 2;"));
+	}
+
+	#[test]
+	fn put_and_get() {
+		let directory = tempfile::TempDir::new().unwrap();
+		let cache = ModuleCache::new(directory.path().join("cache"));
+		let key = ModuleCache::key("export default 1;");
+		assert!(cache.get(&key).unwrap().is_none());
+		assert!(cache.get_code_cache(&key).unwrap().is_none());
+
+		cache.put(&key, &entry("text", "map")).unwrap();
+		cache.put_code_cache(&key, b"code cache").unwrap();
+		let Entry {
+			transpiled_text,
+			source_map,
+		} = cache.get(&key).unwrap().unwrap();
+		assert_eq!(transpiled_text, "text");
+		assert_eq!(source_map, "map");
+		assert_eq!(cache.get_code_cache(&key).unwrap().unwrap(), b"code cache");
+	}
+
+	#[test]
+	fn writes_replace_entries_without_leaving_temporary_files() {
+		let directory = tempfile::TempDir::new().unwrap();
+		let cache = ModuleCache::new(directory.path());
+		let key = ModuleCache::key("export default 1;");
+
+		cache.put(&key, &entry("old text", "old map")).unwrap();
+		cache.put_code_cache(&key, b"old code cache").unwrap();
+		cache.put(&key, &entry("new text", "new map")).unwrap();
+		cache.put_code_cache(&key, b"new code cache").unwrap();
+
+		let entry = cache.get(&key).unwrap().unwrap();
+		assert_eq!(entry.transpiled_text, "new text");
+		assert_eq!(entry.source_map, "new map");
+		assert_eq!(
+			cache.get_code_cache(&key).unwrap().unwrap(),
+			b"new code cache"
+		);
+
+		// Only the entry's files are in the directory.
+		let mut names = std::fs::read_dir(directory.path())
+			.unwrap()
+			.map(|entry| entry.unwrap().file_name().into_string().unwrap())
+			.collect::<Vec<_>>();
+		names.sort();
+		assert_eq!(
+			names,
+			[
+				format!("{key}.code_cache"),
+				format!("{key}.js"),
+				format!("{key}.js.map"),
+			]
+		);
+	}
+
+	#[test]
+	fn entry_without_source_map_is_missing() {
+		let directory = tempfile::TempDir::new().unwrap();
+		let cache = ModuleCache::new(directory.path());
+		let key = ModuleCache::key("export default 1;");
+		write(directory.path(), &format!("{key}.js"), b"text").unwrap();
+		assert!(cache.get(&key).unwrap().is_none());
+	}
+}
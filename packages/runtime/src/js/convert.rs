@@ -391,6 +391,42 @@ where
 	}
 }
 
+impl<T1, T2, T3> ToV8 for (T1, T2, T3)
+where
+	T1: ToV8,
+	T2: ToV8,
+	T3: ToV8,
+{
+	fn to_v8<'a>(&self, scope: &mut v8::HandleScope<'a>) -> Result<v8::Local<'a, v8::Value>> {
+		let value0 = self.0.to_v8(scope)?;
+		let value1 = self.1.to_v8(scope)?;
+		let value2 = self.2.to_v8(scope)?;
+		let value = v8::Array::new_with_elements(scope, &[value0, value1, value2]);
+		Ok(value.into())
+	}
+}
+
+impl<T1, T2, T3> FromV8 for (T1, T2, T3)
+where
+	T1: FromV8,
+	T2: FromV8,
+	T3: FromV8,
+{
+	fn from_v8<'a>(
+		scope: &mut v8::HandleScope<'a>,
+		value: v8::Local<'a, v8::Value>,
+	) -> Result<Self> {
+		let value = v8::Local::<v8::Array>::try_from(value).wrap_err("Expected an array.")?;
+		let value0 = value.get_index(scope, 0).wrap_err("Expected a value.")?;
+		let value1 = value.get_index(scope, 1).wrap_err("Expected a value.")?;
+		let value2 = value.get_index(scope, 2).wrap_err("Expected a value.")?;
+		let value0 = from_v8(scope, value0)?;
+		let value1 = from_v8(scope, value1)?;
+		let value2 = from_v8(scope, value2)?;
+		Ok((value0, value1, value2))
+	}
+}
+
 impl<T> ToV8 for &[T]
 where
 	T: ToV8,
@@ -1749,6 +1785,18 @@ impl ToV8 for tg::target::Object {
 		let value = self.checksum.to_v8(scope)?;
 		object.set(scope, key.into(), value);
 
+		let key = v8::String::new_external_onebyte_static(scope, "purity".as_bytes()).unwrap();
+		let value = self.purity.to_v8(scope)?;
+		object.set(scope, key.into(), value);
+
+		let key = v8::String::new_external_onebyte_static(scope, "mounts".as_bytes()).unwrap();
+		let value = self.mounts.to_v8(scope)?;
+		object.set(scope, key.into(), value);
+
+		let key = v8::String::new_external_onebyte_static(scope, "secrets".as_bytes()).unwrap();
+		let value = self.secrets.to_v8(scope)?;
+		object.set(scope, key.into(), value);
+
 		Ok(object.into())
 	}
 }
@@ -1790,6 +1838,18 @@ impl FromV8 for tg::target::Object {
 		let checksum = value.get(scope, checksum.into()).unwrap();
 		let checksum = from_v8(scope, checksum)?;
 
+		let purity = v8::String::new_external_onebyte_static(scope, "purity".as_bytes()).unwrap();
+		let purity = value.get(scope, purity.into()).unwrap();
+		let purity = from_v8(scope, purity)?;
+
+		let mounts = v8::String::new_external_onebyte_static(scope, "mounts".as_bytes()).unwrap();
+		let mounts = value.get(scope, mounts.into()).unwrap();
+		let mounts = from_v8(scope, mounts)?;
+
+		let secrets = v8::String::new_external_onebyte_static(scope, "secrets".as_bytes()).unwrap();
+		let secrets = value.get(scope, secrets.into()).unwrap();
+		let secrets = from_v8(scope, secrets)?;
+
 		Ok(Self {
 			host,
 			executable,
@@ -1798,10 +1858,64 @@ impl FromV8 for tg::target::Object {
 			env,
 			args,
 			checksum,
+			purity,
+			mounts,
+			secrets,
 		})
 	}
 }
 
+impl ToV8 for tg::target::Mount {
+	fn to_v8<'a>(&self, scope: &mut v8::HandleScope<'a>) -> Result<v8::Local<'a, v8::Value>> {
+		let object = v8::Object::new(scope);
+
+		let key = v8::String::new_external_onebyte_static(scope, "artifact".as_bytes()).unwrap();
+		let value = self.artifact.to_v8(scope)?;
+		object.set(scope, key.into(), value);
+
+		let key = v8::String::new_external_onebyte_static(scope, "path".as_bytes()).unwrap();
+		let value = self.path.to_string().to_v8(scope)?;
+		object.set(scope, key.into(), value);
+
+		Ok(object.into())
+	}
+}
+
+impl FromV8 for tg::target::Mount {
+	fn from_v8<'a>(
+		scope: &mut v8::HandleScope<'a>,
+		value: v8::Local<'a, v8::Value>,
+	) -> Result<Self> {
+		let value = value.to_object(scope).unwrap();
+
+		let artifact =
+			v8::String::new_external_onebyte_static(scope, "artifact".as_bytes()).unwrap();
+		let artifact = value.get(scope, artifact.into()).unwrap();
+		let artifact = from_v8(scope, artifact)?;
+
+		let path = v8::String::new_external_onebyte_static(scope, "path".as_bytes()).unwrap();
+		let path = value.get(scope, path.into()).unwrap();
+		let path = String::from_v8(scope, path)?.parse()?;
+
+		Ok(Self { artifact, path })
+	}
+}
+
+impl ToV8 for tg::target::Purity {
+	fn to_v8<'a>(&self, scope: &mut v8::HandleScope<'a>) -> Result<v8::Local<'a, v8::Value>> {
+		serde_v8::to_v8(scope, self).wrap_err("Failed to serialize the purity.")
+	}
+}
+
+impl FromV8 for tg::target::Purity {
+	fn from_v8<'a>(
+		scope: &mut v8::HandleScope<'a>,
+		value: v8::Local<'a, v8::Value>,
+	) -> Result<Self> {
+		serde_v8::from_v8(scope, value).wrap_err("Failed to deserialize the purity.")
+	}
+}
+
 impl ToV8 for tg::template::Component {
 	fn to_v8<'a>(&self, scope: &mut v8::HandleScope<'a>) -> Result<v8::Local<'a, v8::Value>> {
 		match self {
@@ -1977,6 +2091,36 @@ impl FromV8 for tg::blob::ArchiveFormat {
 	}
 }
 
+impl ToV8 for tg::blob::ArchiveOptions {
+	fn to_v8<'a>(&self, scope: &mut v8::HandleScope<'a>) -> Result<v8::Local<'a, v8::Value>> {
+		serde_v8::to_v8(scope, self).wrap_err("Failed to serialize the archive options.")
+	}
+}
+
+impl FromV8 for tg::blob::ArchiveOptions {
+	fn from_v8<'a>(
+		scope: &mut v8::HandleScope<'a>,
+		value: v8::Local<'a, v8::Value>,
+	) -> Result<Self> {
+		serde_v8::from_v8(scope, value).wrap_err("Failed to deserialize the archive options.")
+	}
+}
+
+impl ToV8 for tg::blob::ExtractOptions {
+	fn to_v8<'a>(&self, scope: &mut v8::HandleScope<'a>) -> Result<v8::Local<'a, v8::Value>> {
+		serde_v8::to_v8(scope, self).wrap_err("Failed to serialize the extract options.")
+	}
+}
+
+impl FromV8 for tg::blob::ExtractOptions {
+	fn from_v8<'a>(
+		scope: &mut v8::HandleScope<'a>,
+		value: v8::Local<'a, v8::Value>,
+	) -> Result<Self> {
+		serde_v8::from_v8(scope, value).wrap_err("Failed to deserialize the extract options.")
+	}
+}
+
 impl ToV8 for tg::blob::CompressionFormat {
 	fn to_v8<'a>(&self, scope: &mut v8::HandleScope<'a>) -> Result<v8::Local<'a, v8::Value>> {
 		self.to_string().to_v8(scope)
@@ -2171,3 +2315,33 @@ impl FromV8 for Url {
 			.wrap_err("Failed to parse the string as a URL.")
 	}
 }
+
+impl ToV8 for crate::walk::Options {
+	fn to_v8<'a>(&self, scope: &mut v8::HandleScope<'a>) -> Result<v8::Local<'a, v8::Value>> {
+		serde_v8::to_v8(scope, self).wrap_err("Failed to serialize the walk options.")
+	}
+}
+
+impl FromV8 for crate::walk::Options {
+	fn from_v8<'a>(
+		scope: &mut v8::HandleScope<'a>,
+		value: v8::Local<'a, v8::Value>,
+	) -> Result<Self> {
+		serde_v8::from_v8(scope, value).wrap_err("Failed to deserialize the walk options.")
+	}
+}
+
+impl ToV8 for crate::patch::Options {
+	fn to_v8<'a>(&self, scope: &mut v8::HandleScope<'a>) -> Result<v8::Local<'a, v8::Value>> {
+		serde_v8::to_v8(scope, self).wrap_err("Failed to serialize the patch options.")
+	}
+}
+
+impl FromV8 for crate::patch::Options {
+	fn from_v8<'a>(
+		scope: &mut v8::HandleScope<'a>,
+		value: v8::Local<'a, v8::Value>,
+	) -> Result<Self> {
+		serde_v8::from_v8(scope, value).wrap_err("Failed to deserialize the patch options.")
+	}
+}
@@ -204,15 +204,18 @@ export class Directory {
 		return await syscall.bundle(this);
 	}
 
-	async *walk(): AsyncIterableIterator<[Path, Artifact]> {
-		for await (let [name, artifact] of this) {
-			yield [Path.new(name), artifact];
-			if (Directory.is(artifact)) {
-				for await (let [entryName, entryArtifact] of artifact.walk()) {
-					yield [Path.new(name).join(entryName), entryArtifact];
-				}
-			}
-		}
+	async *walk(
+		options?: Directory.WalkOptions,
+	): AsyncIterableIterator<[string, Artifact]> {
+		yield* await syscall.walk(this, options);
+	}
+
+	async *glob(
+		pattern: string | Array<string>,
+		options?: Directory.WalkOptions,
+	): AsyncIterableIterator<[string, Artifact]> {
+		let patterns = pattern instanceof Array ? pattern : [pattern];
+		yield* await syscall.glob(this, patterns, options);
 	}
 
 	async *[Symbol.asyncIterator](): AsyncIterator<[string, Artifact]> {
@@ -238,6 +241,11 @@ export namespace Directory {
 
 	export type Id = string;
 
+	export type WalkOptions = {
+		followSymlinks?: boolean;
+		maxDepth?: number;
+	};
+
 	export type Object_ = {
 		entries: Record<string, Artifact>;
 	};
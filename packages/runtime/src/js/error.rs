@@ -116,7 +116,7 @@ pub(super) fn from_exception<'s>(
 	}
 }
 
-fn get_location(
+pub(super) fn get_location(
 	state: &State,
 	file: Option<&str>,
 	line: u32,
@@ -124,8 +124,8 @@ fn get_location(
 ) -> Option<tangram_error::Location> {
 	if file.map_or(false, |resource_name| resource_name == "[runtime]") {
 		if let Some(global_source_map) = state.global_source_map.as_ref() {
-			let token = global_source_map.lookup_token(line, column).unwrap();
-			let source = token.get_source().unwrap().to_owned();
+			let token = global_source_map.lookup_token(line, column)?;
+			let source = token.get_source()?.to_owned();
 			let source = format!("[runtime]:{source}");
 			let line = token.get_src_line();
 			let column = token.get_src_col();
@@ -153,16 +153,17 @@ fn get_location(
 			.unwrap_or("<unknown>");
 		let path = module.as_ref().map_or_else(
 			|| "<unknown>".to_owned(),
-			|module| module.module.unwrap_normal_ref().path.to_string(),
+			|module| module.module.path().to_string(),
 		);
 		let source = format!("{name}@{version}:{path}");
-		let (line, column) =
-			if let Some(source_map) = module.and_then(|module| module.source_map.as_ref()) {
-				let token = source_map.lookup_token(line, column).unwrap();
-				(token.get_src_line(), token.get_src_col())
-			} else {
-				(line, column)
-			};
+		let (line, column) = if let Some(token) = module
+			.and_then(|module| module.source_map())
+			.and_then(|source_map| source_map.lookup_token(line, column))
+		{
+			(token.get_src_line(), token.get_src_col())
+		} else {
+			(line, column)
+		};
 		Some(tangram_error::Location {
 			source,
 			line,
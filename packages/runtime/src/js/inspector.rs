@@ -0,0 +1,741 @@
+use futures::{SinkExt, StreamExt};
+use num::ToPrimitive;
+use std::{
+	cell::{Cell, RefCell},
+	net::SocketAddr,
+	ptr::{addr_of, NonNull},
+	rc::Rc,
+	sync::Arc,
+	task::{Context, Poll},
+};
+use tangram_client as tg;
+use tangram_error::{error, return_error, Result, WrapErr};
+use tokio::{
+	io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+	net::{TcpListener, TcpStream},
+	sync::mpsc,
+};
+use tokio_tungstenite::{tungstenite, WebSocketStream};
+use v8::inspector::{
+	ChannelBase, ChannelImpl, StringBuffer, StringView, V8Inspector, V8InspectorClientBase,
+	V8InspectorClientImpl, V8InspectorClientTrustLevel, V8InspectorSession,
+};
+
+/// The ID of the inspector's only context group.
+const CONTEXT_GROUP_ID: i32 = 1;
+
+/// The maximum number of lines in the head of an HTTP request.
+const MAX_REQUEST_HEAD_LINES: usize = 100;
+
+/// The maximum length in bytes of a line in the head of an HTTP request.
+const MAX_REQUEST_LINE_LENGTH: u64 = 8192;
+
+/// A V8 inspector for a build, with a session for a debugger that connects with a WebSocket and a session for the profiler. The sessions are declared first so that they are dropped before the inspector.
+pub(super) struct Inspector {
+	debugger: Option<Debugger>,
+	profiler: Option<Session>,
+	inspector: v8::UniqueRef<V8Inspector>,
+	shared: Rc<Shared>,
+	_client: Box<Client>,
+}
+
+struct Debugger {
+	_session: v8::UniqueRef<V8InspectorSession>,
+	_channel: Box<Channel>,
+	url: String,
+	task: tokio::task::JoinHandle<()>,
+}
+
+/// A session that the runtime uses to call methods itself. It must be dropped before the inspector.
+pub(super) struct Session {
+	session: v8::UniqueRef<V8InspectorSession>,
+	_channel: Box<Channel>,
+	receiver: mpsc::UnboundedReceiver<String>,
+	next_id: u64,
+}
+
+/// State shared between the inspector and its client, which V8 calls reentrantly while dispatching messages from the debugger.
+struct Shared {
+	session: Cell<Option<NonNull<V8InspectorSession>>>,
+	receiver: RefCell<Option<mpsc::UnboundedReceiver<String>>>,
+	paused: Cell<bool>,
+	waiting: Cell<bool>,
+}
+
+struct Client {
+	base: V8InspectorClientBase,
+	shared: Rc<Shared>,
+}
+
+struct Channel {
+	base: ChannelBase,
+	sender: mpsc::UnboundedSender<String>,
+}
+
+impl Inspector {
+	/// Create an inspector for an isolate. The inspector must be dropped before the isolate.
+	pub(super) fn new(isolate: &mut v8::Isolate) -> Self {
+		let shared = Rc::new(Shared {
+			session: Cell::new(None),
+			receiver: RefCell::new(None),
+			paused: Cell::new(false),
+			waiting: Cell::new(false),
+		});
+		let mut client = Box::new(Client {
+			base: V8InspectorClientBase::new::<Client>(),
+			shared: shared.clone(),
+		});
+		let inspector = V8Inspector::create(isolate, &mut *client);
+		Self {
+			debugger: None,
+			profiler: None,
+			inspector,
+			shared,
+			_client: client,
+		}
+	}
+
+	/// Start listening for a debugger at `addr`, which must be a loopback address so that only local clients can connect.
+	pub(super) fn start_debugger(
+		&mut self,
+		build: &tg::Build,
+		addr: SocketAddr,
+		main_runtime_handle: &tokio::runtime::Handle,
+	) -> Result<()> {
+		// The debugger has full control of the build, so only allow local clients to connect.
+		if !addr.ip().is_loopback() {
+			return_error!(r#"The inspector's address "{addr}" must be a loopback address."#);
+		}
+
+		// Bind the listener.
+		let listener = std::net::TcpListener::bind(addr)
+			.wrap_err_with(|| format!(r#"Failed to bind the inspector to "{addr}"."#))?;
+		listener
+			.set_nonblocking(true)
+			.wrap_err("Failed to set the listener to nonblocking.")?;
+		let addr = listener
+			.local_addr()
+			.wrap_err("Failed to get the listener's address.")?;
+		let url = format!("ws://{addr}/{}", build.id());
+
+		// Create the channels for messages to and from the debugger.
+		let (incoming_sender, incoming_receiver) = mpsc::unbounded_channel();
+		let (outgoing_sender, outgoing_receiver) = mpsc::unbounded_channel();
+
+		// Spawn a task on the main runtime to serve the debugger.
+		let task = main_runtime_handle.spawn({
+			let id = build.id().clone();
+			async move {
+				let listener = match TcpListener::from_std(listener) {
+					Ok(listener) => listener,
+					Err(error) => {
+						tracing::error!(?error, "Failed to create the inspector's listener.");
+						return;
+					},
+				};
+				serve(listener, addr, id, incoming_sender, outgoing_receiver).await;
+			}
+		});
+
+		// Connect the session.
+		let mut channel = Box::new(Channel {
+			base: ChannelBase::new::<Channel>(),
+			sender: outgoing_sender,
+		});
+		let mut session = self.inspector.connect(
+			CONTEXT_GROUP_ID,
+			&mut *channel,
+			StringView::empty(),
+			V8InspectorClientTrustLevel::FullyTrusted,
+		);
+		self.shared.session.set(Some(NonNull::from(&mut *session)));
+		self.shared.receiver.replace(Some(incoming_receiver));
+
+		self.debugger = Some(Debugger {
+			_session: session,
+			_channel: channel,
+			url,
+			task,
+		});
+
+		Ok(())
+	}
+
+	/// Connect a session.
+	pub(super) fn connect(&mut self) -> Session {
+		let (sender, receiver) = mpsc::unbounded_channel();
+		let mut channel = Box::new(Channel {
+			base: ChannelBase::new::<Channel>(),
+			sender,
+		});
+		let session = self.inspector.connect(
+			CONTEXT_GROUP_ID,
+			&mut *channel,
+			StringView::empty(),
+			V8InspectorClientTrustLevel::FullyTrusted,
+		);
+		Session {
+			session,
+			_channel: channel,
+			receiver,
+			next_id: 1,
+		}
+	}
+
+	/// Start the CPU profiler.
+	pub(super) fn start_profiler(&mut self) -> Result<()> {
+		// Connect the session.
+		let mut profiler = self.connect();
+
+		// Enable and start the profiler.
+		profiler.call("Profiler.enable", &serde_json::Value::Null)?;
+		profiler.call("Profiler.start", &serde_json::Value::Null)?;
+
+		self.profiler = Some(profiler);
+
+		Ok(())
+	}
+
+	/// Stop the CPU profiler and get the profile it recorded.
+	pub(super) fn stop_profiler(&mut self) -> Result<serde_json::Value> {
+		let mut profiler = self
+			.profiler
+			.take()
+			.wrap_err("The profiler was not started.")?;
+		let mut result = profiler.call("Profiler.stop", &serde_json::Value::Null)?;
+		let profile = result
+			.get_mut("profile")
+			.map(serde_json::Value::take)
+			.wrap_err("Expected the result to have a profile.")?;
+		Ok(profile)
+	}
+
+	/// Get the URL the debugger should connect to.
+	pub(super) fn url(&self) -> Option<&str> {
+		self.debugger.as_ref().map(|debugger| debugger.url.as_str())
+	}
+
+	/// Notify the inspector that the context was created.
+	pub(super) fn context_created(&mut self, context: v8::Local<v8::Context>) {
+		let name = StringView::from("tangram".as_bytes());
+		self.inspector
+			.context_created(context, CONTEXT_GROUP_ID, name, StringView::empty());
+	}
+
+	/// Dispatch messages from the debugger until it is ready to run, yielding to the runtime while waiting for them.
+	pub(super) async fn wait_for_debugger(&self) -> Result<()> {
+		self.shared.waiting.set(true);
+		while self.shared.waiting.get() {
+			let Some(message) = self.shared.next().await else {
+				return_error!("The inspector stopped before the debugger was ready.");
+			};
+			self.shared.dispatch(&message);
+		}
+		Ok(())
+	}
+
+	/// Dispatch any messages from the debugger that are ready, registering the waker to be notified of the next one.
+	pub(super) fn poll(&self, cx: &mut Context<'_>) {
+		loop {
+			let message = match self.shared.receiver.borrow_mut().as_mut() {
+				Some(receiver) => match receiver.poll_recv(cx) {
+					Poll::Ready(Some(message)) => message,
+					Poll::Ready(None) | Poll::Pending => break,
+				},
+				None => break,
+			};
+			self.shared.dispatch(&message);
+		}
+	}
+}
+
+impl Drop for Inspector {
+	fn drop(&mut self) {
+		self.shared.session.set(None);
+	}
+}
+
+impl Drop for Debugger {
+	fn drop(&mut self) {
+		self.task.abort();
+	}
+}
+
+impl Session {
+	/// Call a method. V8 responds to most messages synchronously, so the response is available once the message is dispatched.
+	pub(super) fn call(
+		&mut self,
+		method: &str,
+		params: &serde_json::Value,
+	) -> Result<serde_json::Value> {
+		let id = self.send(method, params);
+		let Some(result) = self.try_recv(id, method) else {
+			return_error!(r#"Expected a response to the method "{method}"."#);
+		};
+		result
+	}
+
+	/// Call a method and get the notifications that were sent before its response.
+	pub(super) fn call_with_notifications(
+		&mut self,
+		method: &str,
+		params: &serde_json::Value,
+	) -> Result<(serde_json::Value, Vec<serde_json::Value>)> {
+		let id = self.send(method, params);
+		let mut notifications = Vec::new();
+		let Some(result) = self.try_recv_inner(id, method, Some(&mut notifications)) else {
+			return_error!(r#"Expected a response to the method "{method}"."#);
+		};
+		Ok((result?, notifications))
+	}
+
+	/// Send a message to call a method and return its ID. Use this instead of [`Session::call`] for methods that respond asynchronously.
+	pub(super) fn send(&mut self, method: &str, params: &serde_json::Value) -> u64 {
+		let id = self.next_id;
+		self.next_id += 1;
+		let message = if params.is_null() {
+			serde_json::json!({ "id": id, "method": method })
+		} else {
+			serde_json::json!({ "id": id, "method": method, "params": params })
+		};
+		let message = message.to_string();
+		self.session
+			.dispatch_protocol_message(StringView::from(message.as_bytes()));
+		id
+	}
+
+	/// Get the result of the message with an ID if its response has been received. Notifications and responses to other messages are discarded.
+	pub(super) fn try_recv(&mut self, id: u64, method: &str) -> Option<Result<serde_json::Value>> {
+		self.try_recv_inner(id, method, None)
+	}
+
+	fn try_recv_inner(
+		&mut self,
+		id: u64,
+		method: &str,
+		mut notifications: Option<&mut Vec<serde_json::Value>>,
+	) -> Option<Result<serde_json::Value>> {
+		while let Ok(message) = self.receiver.try_recv() {
+			let mut message: serde_json::Value = match serde_json::from_str(&message)
+				.wrap_err("Failed to deserialize the message.")
+			{
+				Ok(message) => message,
+				Err(error) => return Some(Err(error)),
+			};
+			if message.get("id").is_none() {
+				if let Some(notifications) = notifications.as_mut() {
+					notifications.push(message);
+				}
+				continue;
+			}
+			if message.get("id").and_then(serde_json::Value::as_u64) != Some(id) {
+				continue;
+			}
+			if let Some(error) = message.get("error") {
+				return Some(Err(error!(r#"The method "{method}" failed: {error}."#)));
+			}
+			let result = message
+				.get_mut("result")
+				.map(serde_json::Value::take)
+				.unwrap_or_default();
+			return Some(Ok(result));
+		}
+		None
+	}
+}
+
+impl Shared {
+	/// Block until the next message from the debugger is received. This is only used while V8 is paused, because V8 must not be reentered until it resumes.
+	fn recv(&self) -> Option<String> {
+		let mut receiver = self.receiver.borrow_mut();
+		futures::executor::block_on(receiver.as_mut()?.recv())
+	}
+
+	/// Wait for the next message from the debugger. The receiver is only borrowed while it is polled, so that dispatching the message can borrow it again.
+	async fn next(&self) -> Option<String> {
+		std::future::poll_fn(|cx| match self.receiver.borrow_mut().as_mut() {
+			Some(receiver) => receiver.poll_recv(cx),
+			None => Poll::Ready(None),
+		})
+		.await
+	}
+
+	/// Dispatch a message to the session.
+	fn dispatch(&self, message: &str) {
+		let Some(mut session) = self.session.get() else {
+			return;
+		};
+		let message = StringView::from(message.as_bytes());
+		unsafe { session.as_mut() }.dispatch_protocol_message(message);
+	}
+}
+
+impl V8InspectorClientImpl for Client {
+	fn base(&self) -> &V8InspectorClientBase {
+		&self.base
+	}
+
+	fn base_mut(&mut self) -> &mut V8InspectorClientBase {
+		&mut self.base
+	}
+
+	unsafe fn base_ptr(this: *const Self) -> *const V8InspectorClientBase {
+		addr_of!((*this).base)
+	}
+
+	fn run_message_loop_on_pause(&mut self, _context_group_id: i32) {
+		let shared = self.shared.clone();
+		shared.paused.set(true);
+		while shared.paused.get() {
+			let Some(message) = shared.recv() else {
+				break;
+			};
+			shared.dispatch(&message);
+		}
+	}
+
+	fn quit_message_loop_on_pause(&mut self) {
+		self.shared.paused.set(false);
+	}
+
+	fn run_if_waiting_for_debugger(&mut self, _context_group_id: i32) {
+		self.shared.waiting.set(false);
+	}
+}
+
+impl Channel {
+	fn send(&self, message: v8::UniquePtr<StringBuffer>) {
+		let message = message.unwrap().string().to_string();
+		self.sender.send(message).ok();
+	}
+}
+
+impl ChannelImpl for Channel {
+	fn base(&self) -> &ChannelBase {
+		&self.base
+	}
+
+	fn base_mut(&mut self) -> &mut ChannelBase {
+		&mut self.base
+	}
+
+	unsafe fn base_ptr(this: *const Self) -> *const ChannelBase {
+		addr_of!((*this).base)
+	}
+
+	fn send_response(&mut self, _call_id: i32, message: v8::UniquePtr<StringBuffer>) {
+		self.send(message);
+	}
+
+	fn send_notification(&mut self, message: v8::UniquePtr<StringBuffer>) {
+		self.send(message);
+	}
+
+	fn flush_protocol_notifications(&mut self) {}
+}
+
+/// Accept connections from debuggers. One debugger may be connected at a time.
+async fn serve(
+	listener: TcpListener,
+	addr: SocketAddr,
+	id: tg::build::Id,
+	incoming_sender: mpsc::UnboundedSender<String>,
+	outgoing_receiver: mpsc::UnboundedReceiver<String>,
+) {
+	let outgoing_receiver = Arc::new(tokio::sync::Mutex::new(outgoing_receiver));
+	loop {
+		let stream = match listener.accept().await {
+			Ok((stream, _)) => stream,
+			Err(error) => {
+				tracing::error!(?error, "Failed to accept a connection to the inspector.");
+				continue;
+			},
+		};
+		tokio::spawn({
+			let id = id.clone();
+			let incoming_sender = incoming_sender.clone();
+			let outgoing_receiver = outgoing_receiver.clone();
+			async move {
+				let result =
+					handle_connection(stream, addr, &id, incoming_sender, outgoing_receiver).await;
+				if let Err(error) = result {
+					tracing::error!(?error, "Failed to handle a connection to the inspector.");
+				}
+			}
+		});
+	}
+}
+
+/// Handle a connection, which is either a request for information about the target or a WebSocket connection from a debugger.
+async fn handle_connection(
+	stream: TcpStream,
+	addr: SocketAddr,
+	id: &tg::build::Id,
+	incoming_sender: mpsc::UnboundedSender<String>,
+	outgoing_receiver: Arc<tokio::sync::Mutex<mpsc::UnboundedReceiver<String>>>,
+) -> Result<()> {
+	let mut stream = BufReader::new(stream);
+
+	// Read the request.
+	let Some(request) = read_request(&mut stream).await? else {
+		return Ok(());
+	};
+
+	// Reject requests for other hosts, so that a web page cannot reach the inspector by rebinding its domain to a loopback address.
+	if !is_allowed_host(request.header("host")) {
+		let response = "HTTP/1.1 403 Forbidden\r\nContent-Length: 0\r\n\r\n";
+		stream
+			.write_all(response.as_bytes())
+			.await
+			.wrap_err("Failed to write the response.")?;
+		return Ok(());
+	}
+
+	// If the request is not a WebSocket upgrade, then respond with information about the target.
+	let upgrade = request
+		.header("upgrade")
+		.is_some_and(|value| value.eq_ignore_ascii_case("websocket"));
+	let Some(key) = request.header("sec-websocket-key").filter(|_| upgrade) else {
+		let response = match request.path.trim_end_matches('/') {
+			"/json" | "/json/list" => {
+				let body = serde_json::json!([{
+					"description": "tangram",
+					"devtoolsFrontendUrl": format!("devtools://devtools/bundled/js_app.html?experiments=true&v8only=true&ws={addr}/{id}"),
+					"id": id.to_string(),
+					"title": format!("tangram build {id}"),
+					"type": "node",
+					"url": format!("tangram://{id}"),
+					"webSocketDebuggerUrl": format!("ws://{addr}/{id}"),
+				}]);
+				json_response(&body)
+			},
+			"/json/version" => {
+				let body = serde_json::json!({
+					"Browser": "tangram",
+					"Protocol-Version": "1.3",
+				});
+				json_response(&body)
+			},
+			_ => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n".to_owned(),
+		};
+		stream
+			.write_all(response.as_bytes())
+			.await
+			.wrap_err("Failed to write the response.")?;
+		return Ok(());
+	};
+
+	// Only accept WebSocket connections to the build's path.
+	if request.path != format!("/{id}") {
+		let response = "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n";
+		stream
+			.write_all(response.as_bytes())
+			.await
+			.wrap_err("Failed to write the response.")?;
+		return Ok(());
+	}
+
+	// Only accept WebSocket connections from DevTools or from clients that are not web pages, because a web page could otherwise run code in the build.
+	if !is_allowed_origin(request.header("origin")) {
+		let response = "HTTP/1.1 403 Forbidden\r\nContent-Length: 0\r\n\r\n";
+		stream
+			.write_all(response.as_bytes())
+			.await
+			.wrap_err("Failed to write the response.")?;
+		return Ok(());
+	}
+
+	// Only allow one debugger to be connected at a time.
+	let Ok(mut outgoing_receiver) = outgoing_receiver.try_lock() else {
+		let response = "HTTP/1.1 409 Conflict\r\nContent-Length: 0\r\n\r\n";
+		stream
+			.write_all(response.as_bytes())
+			.await
+			.wrap_err("Failed to write the response.")?;
+		return Ok(());
+	};
+
+	// Discard messages that were sent to a previous debugger.
+	while outgoing_receiver.try_recv().is_ok() {}
+
+	// Complete the WebSocket handshake.
+	let accept = tungstenite::handshake::derive_accept_key(key.as_bytes());
+	let response = format!(
+		"HTTP/1.1 101 Switching Protocols\r\nConnection: Upgrade\r\nUpgrade: websocket\r\nSec-WebSocket-Accept: {accept}\r\n\r\n"
+	);
+	stream
+		.write_all(response.as_bytes())
+		.await
+		.wrap_err("Failed to write the response.")?;
+	let mut websocket =
+		WebSocketStream::from_raw_socket(stream, tungstenite::protocol::Role::Server, None).await;
+
+	// Forward messages between the debugger and the inspector until the debugger disconnects.
+	loop {
+		tokio::select! {
+			message = websocket.next() => {
+				match message {
+					Some(Ok(tungstenite::Message::Text(message))) => {
+						if incoming_sender.send(message).is_err() {
+							break;
+						}
+					},
+					Some(Ok(tungstenite::Message::Close(_)) | Err(_)) | None => break,
+					Some(Ok(_)) => (),
+				}
+			},
+			message = outgoing_receiver.recv() => {
+				let Some(message) = message else {
+					break;
+				};
+				websocket
+					.send(tungstenite::Message::Text(message))
+					.await
+					.wrap_err("Failed to send the message.")?;
+			},
+		}
+	}
+
+	// Disable the debugger so that the build is resumed and its breakpoints are removed.
+	let message = r#"{"id":0,"method":"Debugger.disable"}"#.to_owned();
+	incoming_sender.send(message).ok();
+
+	Ok(())
+}
+
+/// The path and headers of an HTTP request.
+struct Request {
+	path: String,
+	headers: Vec<(String, String)>,
+}
+
+impl Request {
+	fn header(&self, name: &str) -> Option<&str> {
+		self.headers
+			.iter()
+			.find(|(key, _)| key.eq_ignore_ascii_case(name))
+			.map(|(_, value)| value.as_str())
+	}
+}
+
+/// Read the head of an HTTP request. If the connection is closed before a request is received, then `None` is returned.
+async fn read_request(stream: &mut BufReader<TcpStream>) -> Result<Option<Request>> {
+	let mut lines = Vec::new();
+	loop {
+		let mut line = String::new();
+		(&mut *stream)
+			.take(MAX_REQUEST_LINE_LENGTH)
+			.read_line(&mut line)
+			.await
+			.wrap_err("Failed to read the request.")?;
+		if !line.ends_with('\n') && line.len() >= MAX_REQUEST_LINE_LENGTH.to_usize().unwrap() {
+			return_error!("The request's head is too large.");
+		}
+		let line = line.trim_end();
+		if line.is_empty() {
+			break;
+		}
+		if lines.len() >= MAX_REQUEST_HEAD_LINES {
+			return_error!("The request's head is too large.");
+		}
+		lines.push(line.to_owned());
+	}
+	let Some((request_line, headers)) = lines.split_first() else {
+		return Ok(None);
+	};
+	let path = request_line
+		.split(' ')
+		.nth(1)
+		.wrap_err("Invalid request line.")?
+		.to_owned();
+	let headers = headers
+		.iter()
+		.filter_map(|header| {
+			let (key, value) = header.split_once(':')?;
+			Some((key.trim().to_owned(), value.trim().to_owned()))
+		})
+		.collect();
+	Ok(Some(Request { path, headers }))
+}
+
+/// Check that a request's host is a loopback name or an IP address. A request without a host is allowed, because only browsers need to be restricted and they always send one.
+fn is_allowed_host(host: Option<&str>) -> bool {
+	let Some(host) = host else {
+		return true;
+	};
+	let name = if let Some(rest) = host.strip_prefix('[') {
+		let Some((name, _)) = rest.split_once(']') else {
+			return false;
+		};
+		name
+	} else {
+		host.split_once(':').map_or(host, |(name, _)| name)
+	};
+	name.eq_ignore_ascii_case("localhost") || name.parse::<std::net::IpAddr>().is_ok()
+}
+
+/// Check that a WebSocket connection comes from DevTools or from a client that is not a web page, which does not send an origin.
+fn is_allowed_origin(origin: Option<&str>) -> bool {
+	origin.map_or(true, |origin| origin.starts_with("devtools://"))
+}
+
+/// Create an HTTP response with a JSON body.
+fn json_response(body: &serde_json::Value) -> String {
+	let body = body.to_string();
+	format!(
+		"HTTP/1.1 200 OK\r\nContent-Type: application/json; charset=UTF-8\r\nContent-Length: {}\r\n\r\n{body}",
+		body.len()
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	async fn read_request_from(request: String) -> Result<Option<Request>> {
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let addr = listener.local_addr().unwrap();
+		let client = tokio::spawn(async move {
+			let mut stream = TcpStream::connect(addr).await.unwrap();
+			stream.write_all(request.as_bytes()).await.ok();
+		});
+		let (stream, _) = listener.accept().await.unwrap();
+		let mut stream = BufReader::new(stream);
+		let request = read_request(&mut stream).await;
+		client.await.unwrap();
+		request
+	}
+
+	#[tokio::test]
+	async fn read_request_limits_the_line_length() {
+		let request = "GET /json HTTP/1.1\r\nUpgrade: websocket\r\n\r\n".to_owned();
+		let request = read_request_from(request).await.unwrap().unwrap();
+		assert_eq!(request.path, "/json");
+		assert_eq!(request.header("upgrade"), Some("websocket"));
+
+		let path = "a".repeat(2 * MAX_REQUEST_LINE_LENGTH.to_usize().unwrap());
+		let request = format!("GET /{path} HTTP/1.1\r\n\r\n");
+		assert!(read_request_from(request).await.is_err());
+	}
+
+	#[test]
+	fn allowed_hosts() {
+		assert!(is_allowed_host(None));
+		assert!(is_allowed_host(Some("127.0.0.1:9229")));
+		assert!(is_allowed_host(Some("localhost:9229")));
+		assert!(is_allowed_host(Some("LOCALHOST")));
+		assert!(is_allowed_host(Some("[::1]:9229")));
+		assert!(!is_allowed_host(Some("attacker.example.com:9229")));
+		assert!(!is_allowed_host(Some("localhost.example.com")));
+		assert!(!is_allowed_host(Some("[::1")));
+	}
+
+	#[test]
+	fn allowed_origins() {
+		assert!(is_allowed_origin(None));
+		assert!(is_allowed_origin(Some("devtools://devtools")));
+		assert!(!is_allowed_origin(Some("https://attacker.example.com")));
+		assert!(!is_allowed_origin(Some("http://localhost:9229")));
+		assert!(!is_allowed_origin(Some("null")));
+	}
+}
@@ -144,8 +144,11 @@ export class Leaf {
 		return await syscall.decompress(this, format);
 	}
 
-	async extract(format: Blob.ArchiveFormat): Promise<Artifact> {
-		return await syscall.extract(this, format);
+	async extract(
+		format?: Blob.ArchiveFormat,
+		options?: Blob.ExtractOptions,
+	): Promise<Artifact> {
+		return await syscall.extract(this, format, options);
 	}
 }
 
@@ -0,0 +1,202 @@
+use std::{
+	ffi::c_void,
+	sync::{Arc, Mutex},
+	time::{Duration, Instant},
+};
+use tangram_error::{error, Error};
+
+/// The interval at which the monitor checks the time limits.
+const INTERVAL: Duration = Duration::from_millis(10);
+
+/// Limits for a JS build.
+#[derive(Clone, Debug, Default)]
+pub struct Limits {
+	/// The maximum size of the isolate's heap in bytes.
+	pub heap: Option<usize>,
+
+	/// The maximum time the build may spend executing JavaScript, excluding time spent waiting for syscalls and child builds.
+	pub execution_time: Option<Duration>,
+
+	/// The maximum wall-clock time of the build.
+	pub time: Option<Duration>,
+}
+
+/// Enforces a build's limits by terminating execution in its isolate.
+pub(super) struct Monitor {
+	limits: Limits,
+	isolate_handle: v8::IsolateHandle,
+	state: Arc<Mutex<State>>,
+	task: Option<tokio::task::JoinHandle<()>>,
+}
+
+struct State {
+	start: Instant,
+	entered: Option<Instant>,
+	execution_time: Duration,
+	exceeded: Option<Exceeded>,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Exceeded {
+	Heap(usize),
+	ExecutionTime(Duration),
+	Time(Duration),
+}
+
+/// Marks a section in which JavaScript is executing.
+pub(super) struct Guard<'a> {
+	monitor: &'a Monitor,
+}
+
+impl Limits {
+	/// Create the isolate params with the heap limit.
+	pub(super) fn create_params(&self, params: v8::CreateParams) -> v8::CreateParams {
+		match self.heap {
+			Some(heap) => params.heap_limits(0, heap),
+			None => params,
+		}
+	}
+}
+
+impl Monitor {
+	/// Start monitoring an isolate. The monitor is boxed so that its address can be given to the isolate's near heap limit callback, and it must outlive the isolate.
+	#[allow(clippy::unnecessary_box_returns)]
+	pub(super) fn start(
+		isolate: &mut v8::OwnedIsolate,
+		limits: Limits,
+		main_runtime_handle: &tokio::runtime::Handle,
+	) -> Box<Self> {
+		let isolate_handle = isolate.thread_safe_handle();
+		let state = Arc::new(Mutex::new(State {
+			start: Instant::now(),
+			entered: None,
+			execution_time: Duration::ZERO,
+			exceeded: None,
+		}));
+
+		// Spawn a task on the main runtime to check the time limits, because this thread is blocked while JavaScript is executing.
+		let task = (limits.execution_time.is_some() || limits.time.is_some()).then(|| {
+			main_runtime_handle.spawn({
+				let limits = limits.clone();
+				let isolate_handle = isolate_handle.clone();
+				let state = state.clone();
+				async move {
+					loop {
+						tokio::time::sleep(INTERVAL).await;
+						let mut state = state.lock().unwrap();
+						let Some(entered) = state.entered else {
+							continue;
+						};
+						let exceeded = if let Some(time) =
+							limits.time.filter(|time| state.start.elapsed() > *time)
+						{
+							Exceeded::Time(time)
+						} else if let Some(execution_time) = limits
+							.execution_time
+							.filter(|limit| state.execution_time + entered.elapsed() > *limit)
+						{
+							Exceeded::ExecutionTime(execution_time)
+						} else {
+							continue;
+						};
+						state.exceeded.get_or_insert(exceeded);
+						isolate_handle.terminate_execution();
+						break;
+					}
+				}
+			})
+		});
+
+		let monitor = Box::new(Self {
+			limits,
+			isolate_handle,
+			state,
+			task,
+		});
+
+		// Add the near heap limit callback.
+		if monitor.limits.heap.is_some() {
+			let data = std::ptr::addr_of!(*monitor).cast_mut().cast::<c_void>();
+			isolate.add_near_heap_limit_callback(near_heap_limit_callback, data);
+		}
+
+		monitor
+	}
+
+	/// Mark that JavaScript is executing until the guard is dropped.
+	pub(super) fn enter(&self) -> Guard<'_> {
+		self.state.lock().unwrap().entered = Some(Instant::now());
+		Guard { monitor: self }
+	}
+
+	/// Get the time remaining before the wall-clock time limit is exceeded.
+	pub(super) fn remaining_time(&self) -> Option<Duration> {
+		let start = self.state.lock().unwrap().start;
+		self.limits
+			.time
+			.map(|time| time.saturating_sub(start.elapsed()))
+	}
+
+	/// Record that the wall-clock time limit was exceeded while waiting.
+	pub(super) fn time_exceeded(&self) -> Error {
+		let time = self.limits.time.unwrap_or_default();
+		let mut state = self.state.lock().unwrap();
+		state.exceeded.get_or_insert(Exceeded::Time(time));
+		drop(state);
+		self.error().unwrap()
+	}
+
+	/// Get an error if a limit was exceeded.
+	pub(super) fn error(&self) -> Option<Error> {
+		let exceeded = self.state.lock().unwrap().exceeded?;
+		let error = match exceeded {
+			Exceeded::Heap(heap) => {
+				error!("The build exceeded the heap limit of {heap} bytes.")
+			},
+			Exceeded::ExecutionTime(execution_time) => {
+				let seconds = execution_time.as_secs_f64();
+				error!("The build exceeded the execution time limit of {seconds}s.")
+			},
+			Exceeded::Time(time) => {
+				let seconds = time.as_secs_f64();
+				error!("The build exceeded the time limit of {seconds}s.")
+			},
+		};
+		Some(error)
+	}
+}
+
+impl Drop for Monitor {
+	fn drop(&mut self) {
+		if let Some(task) = self.task.take() {
+			task.abort();
+		}
+	}
+}
+
+impl Drop for Guard<'_> {
+	fn drop(&mut self) {
+		let mut state = self.monitor.state.lock().unwrap();
+		if let Some(entered) = state.entered.take() {
+			state.execution_time += entered.elapsed();
+		}
+	}
+}
+
+/// Terminate execution when the heap is near its limit, raising the limit so that the termination can complete.
+extern "C" fn near_heap_limit_callback(
+	data: *mut c_void,
+	current_heap_limit: usize,
+	_initial_heap_limit: usize,
+) -> usize {
+	let monitor = unsafe { &*data.cast::<Monitor>() };
+	let heap = monitor.limits.heap.unwrap_or(current_heap_limit);
+	monitor
+		.state
+		.lock()
+		.unwrap()
+		.exceeded
+		.get_or_insert(Exceeded::Heap(heap));
+	monitor.isolate_handle.terminate_execution();
+	current_heap_limit * 2
+}
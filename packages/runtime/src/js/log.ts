@@ -17,11 +17,20 @@ export let log = (...args: Array<unknown>) => {
 	syscall.log(string);
 };
 
+/** Pretty print a value over multiple lines, as the REPL displays it. */
+export let inspect = (value: unknown): string => {
+	return stringifyInner(value, new WeakSet(), 0);
+};
+
 let stringify = (value: unknown): string => {
 	return stringifyInner(value, new WeakSet());
 };
 
-let stringifyInner = (value: unknown, visited: WeakSet<object>): string => {
+let stringifyInner = (
+	value: unknown,
+	visited: WeakSet<object>,
+	indent?: number,
+): string => {
 	switch (typeof value) {
 		case "string": {
 			return `"${value}"`;
@@ -39,12 +48,12 @@ let stringifyInner = (value: unknown, visited: WeakSet<object>): string => {
 			if (value === null) {
 				return "null";
 			} else {
-				return stringifyObject(value, visited);
+				return stringifyObject(value, visited, indent);
 			}
 		}
 		case "function": {
 			if (Target.is(value)) {
-				return stringifyObject(value, visited);
+				return stringifyObject(value, visited, indent);
 			} else {
 				return `(function "${value.name ?? "(anonymous)"}")`;
 			}
@@ -58,83 +67,116 @@ let stringifyInner = (value: unknown, visited: WeakSet<object>): string => {
 	}
 };
 
-let stringifyObject = (value: object, visited: WeakSet<object>): string => {
+let stringifyObject = (
+	value: object,
+	visited: WeakSet<object>,
+	indent?: number,
+): string => {
 	if (visited.has(value)) {
 		return "(circular)";
 	}
 	visited.add(value);
+	let string;
 	if (value instanceof Array) {
-		return `[${value
-			.map((value) => stringifyInner(value, visited))
-			.join(", ")}]`;
+		let items = value.map((value) =>
+			stringifyInner(value, visited, nextIndent(indent)),
+		);
+		string =
+			indent === undefined
+				? `[${items.join(", ")}]`
+				: join("[", items, "]", indent);
 	} else if (value instanceof Uint8Array) {
 		let bytes = encoding.hex.encode(value);
-		return `(tg.bytes ${bytes})`;
+		string = `(tg.bytes ${bytes})`;
 	} else if (value instanceof Error) {
-		return value.message;
+		string = value.message;
 	} else if (value instanceof Promise) {
-		return "(promise)";
+		string = "(promise)";
 	} else if (Leaf.is(value)) {
-		return stringifyState("leaf", value.state, visited);
+		string = stringifyState("leaf", value.state, visited, indent);
 	} else if (Branch.is(value)) {
-		return stringifyState("branch", value.state, visited);
+		string = stringifyState("branch", value.state, visited, indent);
 	} else if (Directory.is(value)) {
-		return stringifyState("directory", value.state, visited);
+		string = stringifyState("directory", value.state, visited, indent);
 	} else if (File.is(value)) {
-		return stringifyState("file", value.state, visited);
+		string = stringifyState("file", value.state, visited, indent);
 	} else if (Symlink.is(value)) {
-		return stringifyState("symlink", value.state, visited);
+		string = stringifyState("symlink", value.state, visited, indent);
 	} else if (Lock.is(value)) {
-		return stringifyState("lock", value.state, visited);
+		string = stringifyState("lock", value.state, visited, indent);
 	} else if (Target.is(value)) {
-		return stringifyState("target", value.state, visited);
+		string = stringifyState("target", value.state, visited, indent);
 	} else if (Mutation.is(value)) {
-		return `(tg.mutation ${stringifyObject(value.inner, visited)})`;
+		string = `(tg.mutation ${stringifyObject(value.inner, visited, indent)})`;
 	} else if (Template.is(value)) {
-		return `\`${value.components
+		string = `\`${value.components
 			.map((component) => {
 				if (typeof component === "string") {
 					return component;
 				} else {
-					return `\${${stringifyInner(component, visited)}}`;
+					return `\${${stringifyInner(component, visited, indent)}}`;
 				}
 			})
 			.join("")}\``;
 	} else {
-		let string = "";
+		string = "";
 		if (
 			value.constructor !== undefined &&
 			value.constructor.name !== "Object"
 		) {
 			string += `${value.constructor.name} `;
 		}
-		string += "{";
-		let entries = Object.entries(value);
-		if (entries.length > 0) {
-			string += " ";
-		}
-		string += entries
-			.map(([key, value]) => `"${key}": ${stringifyInner(value, visited)}`)
-			.join(", ");
-		if (entries.length > 0) {
-			string += " ";
-		}
-		string += "}";
-		return string;
+		let entries = Object.entries(value).map(
+			([key, value]) =>
+				`"${key}": ${stringifyInner(value, visited, nextIndent(indent))}`,
+		);
+		string += join("{", entries, "}", indent);
 	}
+	return string;
 };
 
 let stringifyState = (
 	kind: string,
 	state: Object_.State<string, object>,
 	visited: WeakSet<object>,
+	indent?: number,
 ): string => {
 	let { id, object } = state;
-	if (id !== undefined) {
+	if (id !== undefined && (indent === undefined || object === undefined)) {
 		return id;
 	} else if (object !== undefined) {
-		return `(tg.${kind} ${stringifyObject(object, visited)})`;
+		let string = stringifyObject(object, visited, indent);
+		if (id !== undefined) {
+			return `(tg.${kind} ${id} ${string})`;
+		} else {
+			return `(tg.${kind} ${string})`;
+		}
 	} else {
 		return unreachable();
 	}
 };
+
+/** Get the indentation of a value's children, which is `undefined` if the value is stringified on a single line. */
+let nextIndent = (indent?: number): number | undefined => {
+	return indent !== undefined ? indent + 1 : undefined;
+};
+
+/** Join the stringified entries of an object on a single line, or on one line per entry if an indentation is given. */
+let join = (
+	open: string,
+	items: Array<string>,
+	close: string,
+	indent?: number,
+): string => {
+	if (items.length === 0) {
+		return `${open}${close}`;
+	} else if (indent === undefined) {
+		return `${open} ${items.join(", ")} ${close}`;
+	} else {
+		let inner = "\t".repeat(indent + 1);
+		let outer = "\t".repeat(indent);
+		return `${open}\n${items
+			.map((item) => `${inner}${item},\n`)
+			.join("")}${outer}${close}`;
+	}
+};
@@ -11,8 +11,9 @@ import { File, file } from "./file.ts";
 import { include } from "./include.ts";
 import { Leaf, leaf } from "./leaf.ts";
 import { Lock } from "./lock.ts";
-import { log } from "./log.ts";
+import { inspect, log } from "./log.ts";
 import { Mutation, mutation } from "./mutation.ts";
+import { patch } from "./patch.ts";
 import { resolve } from "./resolve.ts";
 import { sleep } from "./sleep.ts";
 import { start } from "./start.ts";
@@ -72,9 +73,11 @@ Object.assign(tg, {
 	encoding,
 	file,
 	include,
+	inspect,
 	leaf,
 	log,
 	mutation,
+	patch,
 	resolve,
 	sleep,
 	start,
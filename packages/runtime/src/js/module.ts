@@ -5,6 +5,7 @@ import { encoding } from "./syscall.ts";
 
 export type Module =
 	| { kind: "document"; value: Document }
+	| { kind: "file"; value: File }
 	| { kind: "library"; value: Library }
 	| { kind: "normal"; value: Normal };
 
@@ -23,12 +24,25 @@ export type Normal = {
 	path: string;
 };
 
+export type File = {
+	kind: "json" | "text" | "bytes";
+	module: Module;
+};
+
 export namespace Module {
 	export let toUrl = (module: Module): string => {
 		let data = encoding.hex.encode(
 			encoding.utf8.encode(encoding.json.encode(module)),
 		);
-		return `tg://${data}/${module.value.path}`;
+		return `tg://${data}/${path(module)}`;
+	};
+
+	export let path = (module: Module): string => {
+		if (module.kind === "file") {
+			return path(module.value.module);
+		} else {
+			return module.value.path;
+		}
 	};
 
 	export let fromUrl = (url: string): Module => {
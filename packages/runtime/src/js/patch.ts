@@ -0,0 +1,28 @@
+import { Blob } from "./blob.ts";
+import { Directory } from "./directory.ts";
+import { File } from "./file.ts";
+import * as syscall from "./syscall.ts";
+
+export let patch = async (
+	directory: Directory,
+	...args: Array<string | File | Blob | patch.Options>
+): Promise<Directory> => {
+	let options: patch.Options = {};
+	let texts: Array<string> = [];
+	for (let arg of args) {
+		if (typeof arg === "string") {
+			texts.push(arg);
+		} else if (File.is(arg) || Blob.is(arg)) {
+			texts.push(await arg.text());
+		} else {
+			options = { ...options, ...arg };
+		}
+	}
+	return await syscall.patch(directory, texts, options);
+};
+
+export namespace patch {
+	export type Options = {
+		strip?: number;
+	};
+}
@@ -0,0 +1,97 @@
+use super::{error::get_location, inspector::Inspector, State};
+use bytes::Bytes;
+use num::ToPrimitive;
+use serde_json::Value;
+use tangram_client as tg;
+use tangram_error::{Result, WrapErr};
+
+/// A profile of a JS build.
+#[derive(Clone, Debug)]
+pub struct Profile {
+	/// The CPU profile, in the `.cpuprofile` format.
+	pub cpu: Bytes,
+
+	/// The heap snapshot, in the `.heapsnapshot` format.
+	pub heap_snapshot: Option<Bytes>,
+}
+
+/// Stop the profiler and record the build's profile.
+pub(super) fn record(
+	state: &State,
+	isolate: &mut v8::Isolate,
+	inspector: &mut Inspector,
+	options: tg::build::ProfileOptions,
+) -> Result<Profile> {
+	// Stop the profiler and map its positions to the original sources.
+	let mut cpu = inspector.stop_profiler()?;
+	map_profile(state, &mut cpu);
+	let cpu = serde_json::to_vec(&cpu)
+		.wrap_err("Failed to serialize the profile.")?
+		.into();
+
+	// Take a heap snapshot if requested.
+	let heap_snapshot = options.heap_snapshot.then(|| {
+		let mut bytes = Vec::new();
+		isolate.take_heap_snapshot(|chunk| {
+			bytes.extend_from_slice(chunk);
+			true
+		});
+		bytes.into()
+	});
+
+	Ok(Profile { cpu, heap_snapshot })
+}
+
+/// Map the positions of the call frames in a CPU profile from the transpiled code to the original sources.
+fn map_profile(state: &State, profile: &mut Value) {
+	let Some(nodes) = profile.get_mut("nodes").and_then(Value::as_array_mut) else {
+		return;
+	};
+	for node in nodes {
+		// Get the call frame's position. Native functions and V8's synthetic nodes do not have one.
+		let Some(call_frame) = node.get_mut("callFrame") else {
+			continue;
+		};
+		let url = call_frame
+			.get("url")
+			.and_then(Value::as_str)
+			.map(ToOwned::to_owned);
+		let line = call_frame
+			.get("lineNumber")
+			.and_then(Value::as_i64)
+			.and_then(|line| line.to_u32());
+		let column = call_frame
+			.get("columnNumber")
+			.and_then(Value::as_i64)
+			.and_then(|column| column.to_u32());
+		let (Some(url), Some(line), Some(column)) = (url, line, column) else {
+			continue;
+		};
+
+		// Map the call frame's position.
+		let Some(location) = get_location(state, Some(&url), line, column) else {
+			continue;
+		};
+		call_frame["url"] = location.source.into();
+		call_frame["lineNumber"] = location.line.into();
+		call_frame["columnNumber"] = location.column.into();
+
+		// Map the lines of the position ticks, which are one-based.
+		let Some(ticks) = node.get_mut("positionTicks").and_then(Value::as_array_mut) else {
+			continue;
+		};
+		for tick in ticks {
+			let Some(line) = tick
+				.get("line")
+				.and_then(Value::as_u64)
+				.and_then(|line| line.checked_sub(1))
+				.and_then(|line| line.to_u32())
+			else {
+				continue;
+			};
+			if let Some(location) = get_location(state, Some(&url), line, 0) {
+				tick["line"] = (location.line + 1).into();
+			}
+		}
+	}
+}
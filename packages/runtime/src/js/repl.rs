@@ -0,0 +1,360 @@
+use super::{
+	convert::from_v8,
+	create_context, create_isolate, error,
+	inspector::{Inspector, Session},
+	Limits, State, SOURCE_MAP,
+};
+use futures::{stream::FuturesUnordered, StreamExt};
+use serde_json::{json, Value};
+use sourcemap::SourceMap;
+use std::{cell::RefCell, future::poll_fn, rc::Rc, task::Poll};
+use tangram_client as tg;
+use tangram_error::{error, return_error, Error, Result, WrapErr};
+
+/// The object group of the values that the REPL's evaluations produce, which is released after each evaluation.
+const OBJECT_GROUP: &str = "repl";
+
+/// The messages of the syntax errors that V8 reports when code ends before it is complete.
+const INCOMPLETE_MESSAGES: [&str; 3] = [
+	"Unexpected end of input",
+	"Unterminated template literal",
+	"missing ) after argument list",
+];
+
+/// A REPL that evaluates code with the `tg` global outside of a build. Code is evaluated in the inspector's REPL mode, so it may use top-level `await` and its declarations are available to later evaluations.
+///
+/// The fields are declared in the order they must be dropped. The session must be dropped before the inspector, and everything must be dropped before the isolate.
+pub struct Repl {
+	session: Session,
+	_inspector: Inspector,
+	context: v8::Global<v8::Context>,
+	context_id: u64,
+	state: Rc<State>,
+	isolate: v8::OwnedIsolate,
+}
+
+/// The result of evaluating code in a REPL.
+#[derive(Clone, Debug)]
+pub enum Evaluation {
+	/// The code evaluated to a value, which is pretty printed.
+	Value(String),
+
+	/// The code is incomplete, so more lines should be read before it is evaluated.
+	Incomplete,
+}
+
+impl Repl {
+	/// Create a REPL. Futures that must run on the main runtime, such as loading modules, are spawned on `main_runtime_handle`.
+	pub fn new(tg: &dyn tg::Handle, main_runtime_handle: tokio::runtime::Handle) -> Result<Self> {
+		// Create the isolate.
+		let mut isolate = create_isolate(&Limits::default());
+
+		// Create the inspector.
+		let mut inspector = Inspector::new(&mut isolate);
+
+		// Create the state.
+		let state = Rc::new(State {
+			build: None,
+			depth: 0,
+			futures: RefCell::new(FuturesUnordered::new()),
+			global_source_map: Some(SourceMap::from_slice(SOURCE_MAP).unwrap()),
+			inspect: false,
+			module_cache: None,
+			modules: RefCell::new(Vec::new()),
+			main_runtime_handle,
+			retry: tg::build::Retry::default(),
+			tg: tg.clone_box(),
+			vendor_path: None,
+		});
+
+		// Create the context.
+		let context = create_context(&mut isolate, &state, Some(&mut inspector));
+
+		// Connect a session and get the ID of the context from the notification that the runtime domain sends when it is enabled.
+		let mut session = inspector.connect();
+		let (_, notifications) = session.call_with_notifications("Runtime.enable", &Value::Null)?;
+		let context_id = notifications
+			.iter()
+			.filter(|notification| {
+				notification.get("method").and_then(Value::as_str)
+					== Some("Runtime.executionContextCreated")
+			})
+			.find_map(|notification| notification.pointer("/params/context/id")?.as_u64())
+			.wrap_err("Failed to get the ID of the context.")?;
+
+		Ok(Self {
+			session,
+			_inspector: inspector,
+			context,
+			context_id,
+			state,
+			isolate,
+		})
+	}
+
+	/// Evaluate code. The value it evaluates to is available to later evaluations as `_`, and the error it throws as `_error`.
+	pub async fn evaluate(&mut self, code: &str) -> Result<Evaluation> {
+		// Evaluate the code and wait for its result.
+		let id = self.session.send(
+			"Runtime.evaluate",
+			&json!({
+				"expression": code,
+				"contextId": self.context_id,
+				"objectGroup": OBJECT_GROUP,
+				"replMode": true,
+			}),
+		);
+		let result = self.wait(id).await;
+
+		// Release the values that the evaluation produced once they are no longer needed.
+		let output = match result {
+			Ok(result) => self.output(&result),
+			Err(error) => Err(error),
+		};
+		self.session
+			.call(
+				"Runtime.releaseObjectGroup",
+				&json!({ "objectGroup": OBJECT_GROUP }),
+			)
+			.ok();
+
+		output
+	}
+
+	/// Run the futures that the evaluation is waiting on until the response to the message with an ID is received.
+	async fn wait(&mut self, id: u64) -> Result<Value> {
+		let Self {
+			session,
+			context,
+			state,
+			isolate,
+			..
+		} = self;
+		poll_fn(|cx| loop {
+			// Return the result once it is received. The response is sent while the futures are resolved, so it is checked after each one.
+			if let Some(result) = session.try_recv(id, "Runtime.evaluate") {
+				return Poll::Ready(result);
+			}
+
+			// Poll the futures.
+			let (result, promise_resolver) = match state.futures.borrow_mut().poll_next_unpin(cx) {
+				Poll::Ready(Some(output)) => output,
+				Poll::Ready(None) => {
+					// Run the pending microtasks, which may settle the evaluation.
+					isolate.perform_microtask_checkpoint();
+					if let Some(result) = session.try_recv(id, "Runtime.evaluate") {
+						return Poll::Ready(result);
+					}
+					return Poll::Ready(Err(error!(
+						"The evaluation is waiting on a promise that will never settle."
+					)));
+				},
+				Poll::Pending => return Poll::Pending,
+			};
+
+			// Enter the context.
+			let scope = &mut v8::HandleScope::new(isolate.as_mut());
+			let context = v8::Local::new(scope, context.clone());
+			let scope = &mut v8::ContextScope::new(scope, context);
+
+			// Resolve or reject the promise.
+			let promise_resolver = v8::Local::new(scope, promise_resolver);
+			match result.and_then(|value| value.to_v8(scope)) {
+				Ok(value) => {
+					promise_resolver.resolve(scope, value);
+				},
+				Err(error) => {
+					let exception = error::to_exception(scope, &error);
+					promise_resolver.reject(scope, exception);
+				},
+			}
+		})
+		.await
+	}
+
+	/// Get the output of an evaluation from the result of the `Runtime.evaluate` method.
+	fn output(&mut self, result: &Value) -> Result<Evaluation> {
+		// If the evaluation threw, then get the exception.
+		if let Some(exception) = result.pointer("/exceptionDetails/exception") {
+			// If the code is incomplete, then wait for more lines.
+			let description = exception
+				.get("description")
+				.and_then(Value::as_str)
+				.unwrap_or_default();
+			let incomplete = exception.get("className").and_then(Value::as_str)
+				== Some("SyntaxError")
+				&& INCOMPLETE_MESSAGES
+					.iter()
+					.any(|message| description.contains(message));
+			if incomplete {
+				return Ok(Evaluation::Incomplete);
+			}
+
+			// Store the exception and convert it to an error.
+			self.set_global("_error", exception)?;
+			return Err(self.error(exception));
+		}
+
+		// Store the value and pretty print it.
+		let value = result
+			.get("result")
+			.wrap_err("Expected the result to have a value.")?;
+		self.set_global("_", value)?;
+		let string = self.inspect("_")?;
+
+		Ok(Evaluation::Value(string))
+	}
+
+	/// Set a property of the global object to a value that the inspector refers to.
+	fn set_global(&mut self, name: &str, value: &Value) -> Result<()> {
+		// Refer to the value by its object ID, or by its value if it is a primitive.
+		let argument = if let Some(object_id) = value.get("objectId") {
+			json!({ "objectId": object_id })
+		} else if let Some(value) = value.get("unserializableValue") {
+			json!({ "unserializableValue": value })
+		} else if let Some(value) = value.get("value") {
+			json!({ "value": value })
+		} else {
+			json!({})
+		};
+		self.session.call(
+			"Runtime.callFunctionOn",
+			&json!({
+				"functionDeclaration": format!("function (value) {{ globalThis.{name} = value; }}"),
+				"executionContextId": self.context_id,
+				"arguments": [argument],
+				"silent": true,
+			}),
+		)?;
+		Ok(())
+	}
+
+	/// Pretty print a property of the global object with `tg.inspect`.
+	fn inspect(&mut self, name: &str) -> Result<String> {
+		// Enter the context.
+		let scope = &mut v8::HandleScope::new(self.isolate.as_mut());
+		let context = v8::Local::new(scope, self.context.clone());
+		let scope = &mut v8::ContextScope::new(scope, context);
+
+		// Get the value.
+		let global = context.global(scope);
+		let key = v8::String::new(scope, name).unwrap();
+		let value = global.get(scope, key.into()).unwrap();
+
+		// Get the inspect function.
+		let tg = v8::String::new_external_onebyte_static(scope, "tg".as_bytes()).unwrap();
+		let tg = global.get(scope, tg.into()).unwrap();
+		let tg = v8::Local::<v8::Object>::try_from(tg).unwrap();
+		let inspect = v8::String::new_external_onebyte_static(scope, "inspect".as_bytes()).unwrap();
+		let inspect = tg.get(scope, inspect.into()).unwrap();
+		let inspect = v8::Local::<v8::Function>::try_from(inspect).unwrap();
+
+		// Call the inspect function.
+		let undefined = v8::undefined(scope);
+		let Some(string) = inspect.call(scope, undefined.into(), &[value]) else {
+			return_error!("Failed to inspect the value.");
+		};
+		from_v8(scope, string)
+	}
+
+	/// Convert an exception to an error. If the exception is a `tg.Error`, then it is converted with its location and stack. Otherwise, the error's message is the inspector's description of the exception.
+	fn error(&mut self, exception: &Value) -> Error {
+		// Enter the context.
+		let scope = &mut v8::HandleScope::new(self.isolate.as_mut());
+		let context = v8::Local::new(scope, self.context.clone());
+		let scope = &mut v8::ContextScope::new(scope, context);
+
+		// Get the exception and the tg.Error class.
+		let global = context.global(scope);
+		let key = v8::String::new_external_onebyte_static(scope, "_error".as_bytes()).unwrap();
+		let value = global.get(scope, key.into()).unwrap();
+		let tg = v8::String::new_external_onebyte_static(scope, "tg".as_bytes()).unwrap();
+		let tg = global.get(scope, tg.into()).unwrap();
+		let tg = v8::Local::<v8::Object>::try_from(tg).unwrap();
+		let class = v8::String::new_external_onebyte_static(scope, "Error".as_bytes()).unwrap();
+		let class = tg.get(scope, class.into()).unwrap();
+		let class = v8::Local::<v8::Function>::try_from(class).unwrap();
+
+		// Convert a tg.Error.
+		if value.instance_of(scope, class.into()).unwrap_or(false) {
+			if let Ok(error) = from_v8::<Error>(scope, value) {
+				return error;
+			}
+		}
+
+		// Otherwise, use the description of the exception.
+		let message = exception
+			.get("description")
+			.and_then(Value::as_str)
+			.map(ToOwned::to_owned)
+			.or_else(|| exception.get("value").map(ToString::to_string))
+			.unwrap_or_else(|| "An exception was thrown.".to_owned());
+		Error {
+			message,
+			location: None,
+			stack: None,
+			source: None,
+		}
+	}
+}
+
+/// Resolve an import from the REPL, whose specifier is a package, such as `./path/to/package` or `std@0.0.0`. Relative paths are resolved against the current directory.
+pub(super) fn resolve_module(
+	scope: &mut v8::HandleScope,
+	specifier: &str,
+) -> Option<tangram_language::Module> {
+	let context = scope.get_current_context();
+	let state = context.get_slot::<Rc<State>>(scope).unwrap().clone();
+
+	let (sender, receiver) = std::sync::mpsc::channel();
+	state.main_runtime_handle.spawn({
+		let tg = state.tg.clone_box();
+		let specifier = specifier.to_owned();
+		async move {
+			let result = resolve_module_inner(tg.as_ref(), &specifier).await;
+			sender.send(result).unwrap();
+		}
+	});
+
+	match receiver
+		.recv()
+		.unwrap()
+		.wrap_err_with(|| format!(r#"Failed to resolve "{specifier}"."#))
+	{
+		Ok(module) => Some(module),
+		Err(error) => {
+			let exception = error::to_exception(scope, &error);
+			scope.throw_exception(exception);
+			None
+		},
+	}
+}
+
+async fn resolve_module_inner(
+	tg: &dyn tg::Handle,
+	specifier: &str,
+) -> Result<tangram_language::Module> {
+	// Parse the dependency. The `tg:` prefix of dependency imports is optional.
+	let specifier = specifier.strip_prefix("tg:").unwrap_or(specifier);
+	let mut dependency: tg::Dependency = specifier.parse()?;
+
+	// Canonicalize the path.
+	if let Some(path) = dependency.path.as_mut() {
+		*path = tokio::fs::canonicalize(&path)
+			.await
+			.wrap_err("Failed to canonicalize the path.")?
+			.try_into()?;
+	}
+
+	// Get the package and its lock.
+	let (package, lock) = tg::package::get_with_lock(tg, &dependency).await?;
+
+	// Create the module for the package's root module.
+	let module = tangram_language::Module::Normal(tangram_language::module::Normal {
+		package: package.id(tg).await?.clone(),
+		lock: lock.id(tg).await?.clone(),
+		path: tg::package::ROOT_MODULE_FILE_NAME.parse().unwrap(),
+	});
+
+	Ok(module)
+}
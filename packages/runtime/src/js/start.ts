@@ -46,6 +46,9 @@ export let start = async (target: Target): Promise<Value> => {
 	// Get the args.
 	let args = await target.args();
 
+	// If a debugger is attached, then pause before calling the function.
+	debugger;
+
 	// Call the function.
 	let output = await resolve(function_(...args));
 
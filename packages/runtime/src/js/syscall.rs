@@ -4,16 +4,25 @@ use super::{
 	convert::{from_v8, FromV8, ToV8},
 	error, State,
 };
+use crate::{patch, walk};
 use base64::Engine as _;
 use bytes::Bytes;
 use futures::{Future, TryStreamExt};
 use itertools::Itertools;
-use std::rc::Rc;
+use std::{
+	io::Write,
+	path::{Path, PathBuf},
+	rc::Rc,
+};
 use tangram_client as tg;
 use tangram_error::{return_error, Result, WrapErr};
-use tokio_util::io::StreamReader;
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+use tokio_util::io::{ReaderStream, StreamReader};
 use url::Url;
 
+/// The maximum number of attempts to download a URL when the connection is interrupted.
+const MAX_DOWNLOAD_ATTEMPTS: usize = 5;
+
 pub fn syscall<'s>(
 	scope: &mut v8::HandleScope<'s>,
 	args: v8::FunctionCallbackArguments<'s>,
@@ -44,11 +53,14 @@ pub fn syscall<'s>(
 		"encoding_yaml_decode" => syscall_sync(scope, &args, syscall_encoding_yaml_decode),
 		"encoding_yaml_encode" => syscall_sync(scope, &args, syscall_encoding_yaml_encode),
 		"extract" => syscall_async(scope, &args, syscall_extract),
+		"glob" => syscall_async(scope, &args, syscall_glob),
 		"load" => syscall_async(scope, &args, syscall_load),
 		"log" => syscall_sync(scope, &args, syscall_log),
+		"patch" => syscall_async(scope, &args, syscall_patch),
 		"read" => syscall_async(scope, &args, syscall_read),
 		"sleep" => syscall_async(scope, &args, syscall_sleep),
 		"store" => syscall_async(scope, &args, syscall_store),
+		"walk" => syscall_async(scope, &args, syscall_walk),
 		_ => unreachable!(r#"Unknown syscall "{name}"."#),
 	};
 
@@ -69,10 +81,15 @@ pub fn syscall<'s>(
 
 async fn syscall_archive(
 	state: Rc<State>,
-	args: (tg::Artifact, tg::blob::ArchiveFormat),
+	args: (
+		tg::Artifact,
+		tg::blob::ArchiveFormat,
+		Option<tg::blob::ArchiveOptions>,
+	),
 ) -> Result<tg::Blob> {
-	let (artifact, format) = args;
-	let blob = tg::Blob::archive(state.tg.as_ref(), &artifact, format).await?;
+	let (artifact, format, options) = args;
+	let options = options.unwrap_or_default();
+	let blob = tg::Blob::archive(state.tg.as_ref(), &artifact, format, options).await?;
 	Ok(blob)
 }
 
@@ -81,7 +98,9 @@ async fn syscall_build(state: Rc<State>, args: (tg::Target,)) -> Result<tg::Valu
 	let build = target
 		.build(state.tg.as_ref(), None, state.depth + 1, state.retry)
 		.await?;
-	state.build.add_child(state.tg.as_ref(), &build).await?;
+	if let Some(parent) = state.build.as_ref() {
+		parent.add_child(state.tg.as_ref(), &build).await?;
+	}
 	let output = build
 		.outcome(state.tg.as_ref())
 		.await
@@ -126,26 +145,199 @@ async fn syscall_decompress(
 	Ok(blob)
 }
 
-async fn syscall_download(state: Rc<State>, args: (Url, tg::Checksum)) -> Result<tg::Blob> {
-	let (url, checksum) = args;
-	let response = reqwest::get(url)
+async fn syscall_download(state: Rc<State>, args: (Vec<Url>, tg::Checksum)) -> Result<tg::Blob> {
+	let (urls, checksum) = args;
+	if urls.is_empty() {
+		return_error!("Expected at least one URL.");
+	}
+
+	// Attempt to get the blob for the checksum from the download cache.
+	if !matches!(checksum, tg::Checksum::Unsafe) {
+		if let Some(id) = state.tg.try_get_blob_for_checksum(&checksum).await? {
+			return Ok(tg::Blob::with_id(id));
+		}
+	}
+
+	// Attempt to download from each URL in order.
+	let mut errors = Vec::new();
+	for url in &urls {
+		let blob = match download(
+			state.tg.as_ref(),
+			url,
+			&checksum,
+			state.vendor_path.as_deref(),
+		)
 		.await
-		.wrap_err("Failed to perform the request.")?
-		.error_for_status()
-		.wrap_err("Expected a sucess status.")?;
+		{
+			Ok(blob) => blob,
+			Err(error) => {
+				errors.push(format!("{url}: {error}"));
+				continue;
+			},
+		};
+
+		// Add the blob to the download cache.
+		if !matches!(checksum, tg::Checksum::Unsafe) {
+			let id = blob.id(state.tg.as_ref()).await?;
+			state.tg.set_blob_for_checksum(&checksum, &id).await?;
+		}
+
+		return Ok(blob);
+	}
+
+	let errors = errors.join("\n");
+	return_error!("Failed to download from any of the URLs.\n{errors}");
+}
+
+/// Download the contents of a URL to a blob and verify its checksum. A `file://` URL must refer to a file in the vendor directory.
+async fn download(
+	tg: &dyn tg::Handle,
+	url: &Url,
+	checksum: &tg::Checksum,
+	vendor_path: Option<&Path>,
+) -> Result<tg::Blob> {
 	let mut checksum_writer = tg::checksum::Writer::new(checksum.algorithm());
-	let stream = response
-		.bytes_stream()
-		.map_err(|error| std::io::Error::new(std::io::ErrorKind::Other, error))
-		.inspect_ok(|chunk| checksum_writer.update(chunk));
-	let blob = tg::Blob::with_reader(state.tg.as_ref(), StreamReader::new(stream)).await?;
+	let blob = match url.scheme() {
+		"file" => {
+			let path = vendored_file_path(url, checksum, vendor_path).await?;
+			let file = tokio::fs::File::open(&path)
+				.await
+				.wrap_err("Failed to open the file.")?;
+			let stream = ReaderStream::new(file).inspect_ok(|chunk| checksum_writer.update(chunk));
+			tg::Blob::with_reader(tg, StreamReader::new(stream)).await?
+		},
+		"http" | "https" => {
+			let file = tempfile::tempfile().wrap_err("Failed to create the temporary file.")?;
+			let mut file = tokio::fs::File::from_std(file);
+			download_http(url, &mut file, checksum.algorithm(), &mut checksum_writer).await?;
+			file.rewind()
+				.await
+				.wrap_err("Failed to seek the temporary file.")?;
+			tg::Blob::with_reader(tg, file).await?
+		},
+		scheme => return_error!(r#"Unsupported URL scheme "{scheme}"."#),
+	};
 	let actual = checksum_writer.finalize();
-	if actual != checksum {
+	if actual != *checksum {
 		return_error!(r#"The checksum did not match. Expected "{checksum}" but got "{actual}"."#);
 	}
 	Ok(blob)
 }
 
+/// Get the path of a `file://` URL, which must be in the vendor directory after resolving symlinks and `..` components. The checksum must not be unsafe, so that the file is still verified.
+async fn vendored_file_path(
+	url: &Url,
+	checksum: &tg::Checksum,
+	vendor_path: Option<&Path>,
+) -> Result<PathBuf> {
+	let Some(vendor_path) = vendor_path else {
+		return_error!("File URLs are not allowed.");
+	};
+	if matches!(checksum, tg::Checksum::Unsafe) {
+		return_error!("File URLs require a checksum.");
+	}
+	let path = url.to_file_path().ok().wrap_err("Invalid file URL.")?;
+	let path = tokio::fs::canonicalize(&path)
+		.await
+		.wrap_err_with(|| format!(r#"Failed to resolve the path "{}"."#, path.display()))?;
+	let vendor_path = tokio::fs::canonicalize(vendor_path)
+		.await
+		.wrap_err("Failed to resolve the vendor directory.")?;
+	if !path.starts_with(&vendor_path) {
+		return_error!(
+			r#"The path "{}" is not in the vendor directory "{}"."#,
+			path.display(),
+			vendor_path.display()
+		);
+	}
+	Ok(path)
+}
+
+/// Download the contents of an HTTP URL to a file, resuming with range requests if the connection is interrupted.
+async fn download_http(
+	url: &Url,
+	file: &mut tokio::fs::File,
+	algorithm: tg::checksum::Algorithm,
+	checksum_writer: &mut tg::checksum::Writer,
+) -> Result<()> {
+	let client = reqwest::Client::new();
+	let mut position = 0;
+	let mut attempts = 0;
+	loop {
+		attempts += 1;
+		let mut received = 0;
+		let result = download_http_inner(
+			&client,
+			url,
+			file,
+			algorithm,
+			checksum_writer,
+			&mut position,
+			&mut received,
+		)
+		.await;
+
+		// Retry only if the attempt received bytes, so failures such as missing files move on to the next URL.
+		if result.is_ok() || received == 0 || attempts >= MAX_DOWNLOAD_ATTEMPTS {
+			return result;
+		}
+	}
+}
+
+async fn download_http_inner(
+	client: &reqwest::Client,
+	url: &Url,
+	file: &mut tokio::fs::File,
+	algorithm: tg::checksum::Algorithm,
+	checksum_writer: &mut tg::checksum::Writer,
+	position: &mut u64,
+	received: &mut u64,
+) -> Result<()> {
+	// Request the remaining bytes.
+	let mut request = client.get(url.clone());
+	if *position > 0 {
+		request = request.header(reqwest::header::RANGE, format!("bytes={position}-"));
+	}
+	let response = request
+		.send()
+		.await
+		.wrap_err("Failed to perform the request.")?
+		.error_for_status()
+		.wrap_err("Expected a success status.")?;
+
+	// If the server did not honor the range request, then restart the download.
+	if *position > 0 && response.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+		file.rewind()
+			.await
+			.wrap_err("Failed to seek the temporary file.")?;
+		file.set_len(0)
+			.await
+			.wrap_err("Failed to truncate the temporary file.")?;
+		*checksum_writer = tg::checksum::Writer::new(algorithm);
+		*position = 0;
+	}
+
+	// Write the response body to the file.
+	let mut stream = response.bytes_stream();
+	while let Some(chunk) = stream
+		.try_next()
+		.await
+		.wrap_err("Failed to read the response body.")?
+	{
+		file.write_all(&chunk)
+			.await
+			.wrap_err("Failed to write to the temporary file.")?;
+		checksum_writer.update(&chunk);
+		*position += chunk.len() as u64;
+		*received += chunk.len() as u64;
+	}
+	file.flush()
+		.await
+		.wrap_err("Failed to flush the temporary file.")?;
+
+	Ok(())
+}
+
 fn syscall_encoding_base64_decode(
 	_scope: &mut v8::HandleScope,
 	_state: Rc<State>,
@@ -271,13 +463,32 @@ fn syscall_encoding_yaml_encode(
 
 async fn syscall_extract(
 	state: Rc<State>,
-	args: (tg::Blob, tg::blob::ArchiveFormat),
+	args: (
+		tg::Blob,
+		Option<tg::blob::ArchiveFormat>,
+		Option<tg::blob::ExtractOptions>,
+	),
 ) -> Result<tg::Artifact> {
-	let (blob, format) = args;
-	let artifact = blob.extract(state.tg.as_ref(), format).await?;
+	let (blob, format, options) = args;
+	let options = options.unwrap_or_default();
+	let artifact = blob.extract(state.tg.as_ref(), format, options).await?;
 	Ok(artifact)
 }
 
+async fn syscall_glob(
+	state: Rc<State>,
+	args: (tg::Directory, Vec<String>, Option<walk::Options>),
+) -> Result<Vec<(String, tg::Artifact)>> {
+	let (directory, patterns, options) = args;
+	let options = options.unwrap_or_default();
+	let entries = walk::glob(state.tg.as_ref(), &directory, &patterns, &options).await?;
+	let entries = entries
+		.into_iter()
+		.map(|(path, artifact)| (path.to_string(), artifact))
+		.collect();
+	Ok(entries)
+}
+
 async fn syscall_load(state: Rc<State>, args: (tg::object::Id,)) -> Result<tg::object::Object> {
 	let (id,) = args;
 	tg::object::Handle::with_id(id)
@@ -287,9 +498,19 @@ async fn syscall_load(state: Rc<State>, args: (tg::object::Id,)) -> Result<tg::o
 
 fn syscall_log(_scope: &mut v8::HandleScope, state: Rc<State>, args: (String,)) -> Result<()> {
 	let (string,) = args;
+
+	// If there is no build, then write to stdout.
+	let Some(build) = state.build.clone() else {
+		let mut stdout = std::io::stdout().lock();
+		stdout
+			.write_all(string.as_bytes())
+			.and_then(|()| stdout.flush())
+			.wrap_err("Failed to write to stdout.")?;
+		return Ok(());
+	};
+
 	let (sender, receiver) = std::sync::mpsc::channel();
 	state.main_runtime_handle.spawn({
-		let build = state.build.clone();
 		let tg = state.tg.clone_box();
 		async move {
 			let result = build.add_log(tg.as_ref(), string.into()).await;
@@ -303,6 +524,16 @@ fn syscall_log(_scope: &mut v8::HandleScope, state: Rc<State>, args: (String,))
 	Ok(())
 }
 
+async fn syscall_patch(
+	state: Rc<State>,
+	args: (tg::Directory, Vec<String>, Option<patch::Options>),
+) -> Result<tg::Directory> {
+	let (directory, patches, options) = args;
+	let options = options.unwrap_or_default();
+	let directory = patch::apply(state.tg.as_ref(), &directory, &patches, &options).await?;
+	Ok(directory)
+}
+
 async fn syscall_read(state: Rc<State>, args: (tg::Blob,)) -> Result<Bytes> {
 	let (blob,) = args;
 	let bytes = blob.bytes(state.tg.as_ref()).await?;
@@ -323,6 +554,20 @@ async fn syscall_store(state: Rc<State>, args: (tg::object::Object,)) -> Result<
 	Ok(id.clone())
 }
 
+async fn syscall_walk(
+	state: Rc<State>,
+	args: (tg::Directory, Option<walk::Options>),
+) -> Result<Vec<(String, tg::Artifact)>> {
+	let (directory, options) = args;
+	let options = options.unwrap_or_default();
+	let entries = walk::walk(state.tg.as_ref(), &directory, &options).await?;
+	let entries = entries
+		.into_iter()
+		.map(|(path, artifact)| (path.to_string(), artifact))
+		.collect();
+	Ok(entries)
+}
+
 fn syscall_sync<'s, A, T, F>(
 	scope: &mut v8::HandleScope<'s>,
 	args: &v8::FunctionCallbackArguments,
@@ -405,3 +650,57 @@ where
 
 	Ok(promise.into())
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn vendored_file_paths() {
+		let tempdir = tempfile::TempDir::new().unwrap();
+		let root = tempdir.path().canonicalize().unwrap();
+		let vendor_path = root.join("vendor");
+		std::fs::create_dir(&vendor_path).unwrap();
+		std::fs::write(vendor_path.join("foo.tar.gz"), "foo").unwrap();
+		std::fs::write(root.join("secret"), "secret").unwrap();
+		std::os::unix::fs::symlink(root.join("secret"), vendor_path.join("link")).unwrap();
+		let url = |path: &Path| Url::from_file_path(path).unwrap();
+		let mut writer = tg::checksum::Writer::new(tg::checksum::Algorithm::Sha256);
+		writer.update("foo");
+		let checksum = writer.finalize();
+
+		// A file in the vendor directory is allowed.
+		let path = vendor_path.join("foo.tar.gz");
+		assert_eq!(
+			vendored_file_path(&url(&path), &checksum, Some(&vendor_path))
+				.await
+				.unwrap(),
+			path
+		);
+
+		// Files outside the vendor directory are not allowed, even through `..` or a symlink.
+		let path = vendor_path.join("../secret");
+		assert!(
+			vendored_file_path(&url(&path), &checksum, Some(&vendor_path))
+				.await
+				.is_err()
+		);
+		let path = vendor_path.join("link");
+		assert!(
+			vendored_file_path(&url(&path), &checksum, Some(&vendor_path))
+				.await
+				.is_err()
+		);
+
+		// File URLs require a vendor directory and a checksum.
+		let path = vendor_path.join("foo.tar.gz");
+		assert!(vendored_file_path(&url(&path), &checksum, None)
+			.await
+			.is_err());
+		assert!(
+			vendored_file_path(&url(&path), &tg::Checksum::Unsafe, Some(&vendor_path))
+				.await
+				.is_err()
+		);
+	}
+}
@@ -11,6 +11,7 @@ declare global {
 		syscall: "archive",
 		artifact: Artifact,
 		format: Blob.ArchiveFormat,
+		options: Blob.ArchiveOptions | undefined,
 	): Promise<Blob>;
 
 	function syscall(syscall: "build", target: Target): Promise<Value>;
@@ -37,7 +38,7 @@ declare global {
 
 	function syscall(
 		syscall: "download",
-		url: string,
+		urls: Array<string>,
 		checksum: Checksum,
 	): Promise<Blob>;
 
@@ -74,26 +75,48 @@ declare global {
 	function syscall(
 		syscall: "extract",
 		blob: Blob,
-		format: Blob.ArchiveFormat,
+		format: Blob.ArchiveFormat | undefined,
+		options: Blob.ExtractOptions | undefined,
 	): Promise<Artifact>;
 
+	function syscall(
+		syscall: "glob",
+		directory: Directory,
+		patterns: Array<string>,
+		options: Directory.WalkOptions | undefined,
+	): Promise<Array<[string, Artifact]>>;
+
 	function syscall(syscall: "load", id: Object_.Id): Promise<Object_>;
 
 	function syscall(syscall: "log", value: string): void;
 
+	function syscall(
+		syscall: "patch",
+		directory: Directory,
+		patches: Array<string>,
+		options: { strip?: number } | undefined,
+	): Promise<Directory>;
+
 	function syscall(syscall: "read", blob: Blob): Promise<Uint8Array>;
 
 	function syscall(syscall: "store", object: Object_): Promise<Object_.Id>;
 
 	function syscall(syscall: "sleep", duration: number): Promise<void>;
+
+	function syscall(
+		syscall: "walk",
+		directory: Directory,
+		options: Directory.WalkOptions | undefined,
+	): Promise<Array<[string, Artifact]>>;
 }
 
 export let archive = async (
 	artifact: Artifact,
 	format: Blob.ArchiveFormat,
+	options?: Blob.ArchiveOptions,
 ): Promise<Blob> => {
 	try {
-		return await syscall("archive", artifact, format);
+		return await syscall("archive", artifact, format, options);
 	} catch (cause) {
 		throw new Error("The syscall failed.", { cause });
 	}
@@ -149,11 +172,11 @@ export let decompress = async (
 };
 
 export let download = async (
-	url: string,
+	urls: Array<string>,
 	checksum: Checksum,
 ): Promise<Blob> => {
 	try {
-		return await syscall("download", url, checksum);
+		return await syscall("download", urls, checksum);
 	} catch (cause) {
 		throw new Error("The syscall failed.", { cause });
 	}
@@ -271,10 +294,11 @@ export let encoding = {
 
 export let extract = async (
 	blob: Blob,
-	format: Blob.ArchiveFormat,
+	format?: Blob.ArchiveFormat,
+	options?: Blob.ExtractOptions,
 ): Promise<Artifact> => {
 	try {
-		return await syscall("extract", blob, format);
+		return await syscall("extract", blob, format, options);
 	} catch (cause) {
 		throw new Error("The syscall failed.", { cause });
 	}
@@ -288,6 +312,18 @@ export let log = (value: string) => {
 	}
 };
 
+export let glob = async (
+	directory: Directory,
+	patterns: Array<string>,
+	options?: Directory.WalkOptions,
+): Promise<Array<[string, Artifact]>> => {
+	try {
+		return await syscall("glob", directory, patterns, options);
+	} catch (cause) {
+		throw new Error("The syscall failed.", { cause });
+	}
+};
+
 export let load = async (id: Object_.Id): Promise<Object_> => {
 	try {
 		return await syscall("load", id);
@@ -296,6 +332,18 @@ export let load = async (id: Object_.Id): Promise<Object_> => {
 	}
 };
 
+export let patch = async (
+	directory: Directory,
+	patches: Array<string>,
+	options?: { strip?: number },
+): Promise<Directory> => {
+	try {
+		return await syscall("patch", directory, patches, options);
+	} catch (cause) {
+		throw new Error("The syscall failed.", { cause });
+	}
+};
+
 export let read = async (blob: Blob): Promise<Uint8Array> => {
 	try {
 		return await syscall("read", blob);
@@ -319,3 +367,14 @@ export let sleep = async (duration: number): Promise<void> => {
 		throw new Error("The syscall failed.", { cause });
 	}
 };
+
+export let walk = async (
+	directory: Directory,
+	options?: Directory.WalkOptions,
+): Promise<Array<[string, Artifact]>> => {
+	try {
+		return await syscall("walk", directory, options);
+	} catch (cause) {
+		throw new Error("The syscall failed.", { cause });
+	}
+};
@@ -99,6 +99,9 @@ export function target<
 				args: [],
 				env: getCurrentEnv(),
 				checksum: undefined,
+				purity: undefined,
+				mounts: [],
+				secrets: [],
 			},
 		});
 	} else {
@@ -166,6 +169,9 @@ export class Target<
 			env?: MaybeNestedArray<MutationMap>;
 			args?: Array<Value>;
 			checksum?: Checksum | undefined;
+			purity?: Target.Purity | undefined;
+			mounts?: Array<Target.Mount>;
+			secrets?: Array<string>;
 		};
 		let {
 			host,
@@ -175,6 +181,9 @@ export class Target<
 			env: env_,
 			args: args_,
 			checksum,
+			purity,
+			mounts: mounts_,
+			secrets: secrets_,
 		} = await Args.apply<Target.Arg, Apply>(
 			[{ env: await getCurrent().env() }, ...args],
 			async (arg) => {
@@ -208,6 +217,22 @@ export class Target<
 									values: [...arg.args],
 								}));
 					}
+					if (arg.mounts !== undefined) {
+						object.mounts = Mutation.is(arg.mounts)
+							? arg.mounts
+							: await mutation({
+									kind: "array_append",
+									values: [...arg.mounts],
+								});
+					}
+					if (arg.secrets !== undefined) {
+						object.secrets = Mutation.is(arg.secrets)
+							? arg.secrets
+							: await mutation({
+									kind: "array_append",
+									values: [...arg.secrets],
+								});
+					}
 					return {
 						...arg,
 						...object,
@@ -225,6 +250,8 @@ export class Target<
 		}
 		let env = await Args.apply(flatten(env_ ?? []), async (arg) => arg);
 		args_ ??= [];
+		mounts_ ??= [];
+		secrets_ ??= [];
 		return new Target({
 			object: {
 				host,
@@ -234,6 +261,9 @@ export class Target<
 				env,
 				args: args_,
 				checksum,
+				purity,
+				mounts: mounts_,
+				secrets: secrets_,
 			},
 		});
 	}
@@ -306,6 +336,18 @@ export class Target<
 		return (await this.object()).checksum;
 	}
 
+	async purity(): Promise<Target.Purity | undefined> {
+		return (await this.object()).purity;
+	}
+
+	async mounts(): Promise<Array<Target.Mount>> {
+		return (await this.object()).mounts;
+	}
+
+	async secrets(): Promise<Array<string>> {
+		return (await this.object()).secrets;
+	}
+
 	async build(...args: A): Promise<Value> {
 		return await syscall.build(
 			await Target.new<[], R>(this as Target, { args }),
@@ -331,6 +373,9 @@ export namespace Target {
 		env?: MaybeNestedArray<MutationMap>;
 		args?: Array<Value>;
 		checksum?: Checksum | undefined;
+		purity?: Purity | undefined;
+		mounts?: Array<Mount>;
+		secrets?: Array<string>;
 	};
 
 	export type Id = string;
@@ -343,6 +388,19 @@ export namespace Target {
 		env: Record<string, Value>;
 		args: Array<Value>;
 		checksum: Checksum | undefined;
+		purity: Purity | undefined;
+		mounts: Array<Mount>;
+		secrets: Array<string>;
+	};
+
+	export type Mount = {
+		artifact: Artifact;
+		path: string;
+	};
+
+	export type Purity = {
+		mode?: "warn" | "fail";
+		prefixes?: Array<string>;
 	};
 
 	export type State = Object_.State<Target.Id, Target.Object_>;
@@ -3,4 +3,7 @@ pub mod darwin;
 pub mod js;
 #[cfg(target_os = "linux")]
 pub mod linux;
+pub mod patch;
+pub mod purity;
 pub mod util;
+pub mod walk;
@@ -1,5 +1,4 @@
-use crate::util::render;
-use bytes::Bytes;
+use crate::util::{render, Redactor};
 use futures::{stream::FuturesOrdered, TryStreamExt};
 use indoc::formatdoc;
 use itertools::Itertools;
@@ -57,6 +56,7 @@ pub async fn build(
 	build: &tg::Build,
 	_retry: tg::build::Retry,
 	server_directory_path: &Path,
+	secrets: &BTreeMap<String, String>,
 ) -> Result<tg::Value> {
 	// Get the target.
 	let target = build.target(tg).await?;
@@ -125,7 +125,7 @@ pub async fn build(
 	let output_guest_path = output_parent_directory_guest_path.join("output");
 
 	// Create the host and guest paths for the artifacts directory.
-	let _artifacts_directory_host_path = server_directory_host_path.join("artifacts");
+	let artifacts_directory_host_path = server_directory_host_path.join("artifacts");
 	let artifacts_directory_guest_path = server_directory_guest_path.join("artifacts");
 
 	// Create the host and guest paths for the home directory.
@@ -180,6 +180,11 @@ pub async fn build(
 	// Enable the network if a checksum was provided.
 	let network_enabled = target.checksum(tg).await?.is_some();
 
+	// Add the secrets to the env.
+	for (name, value) in secrets {
+		env.insert(name.clone(), value.clone());
+	}
+
 	// Set `$HOME`.
 	env.insert(
 		"HOME".to_owned(),
@@ -388,6 +393,54 @@ pub async fn build(
 		readonly: false,
 	});
 
+	// Add the target's mounts to the mounts.
+	let target_mounts = target.mounts(tg).await?;
+	validate_mounts(target_mounts)?;
+	for mount in target_mounts {
+		let id = mount.artifact.id(tg).await?;
+		let source_path = artifacts_directory_host_path.join(id.to_string());
+		let target_path = mount_target_path(&root_directory_host_path, &mount.path)?;
+		match &mount.artifact {
+			tg::Artifact::Directory(_) => {
+				tokio::fs::create_dir_all(&target_path)
+					.await
+					.wrap_err_with(|| {
+						format!(r#"Failed to create the mount point for "{}"."#, mount.path)
+					})?;
+			},
+			tg::Artifact::File(_) => {
+				tokio::fs::create_dir_all(target_path.parent().unwrap())
+					.await
+					.wrap_err_with(|| {
+						format!(r#"Failed to create the mount point for "{}"."#, mount.path)
+					})?;
+				tokio::fs::OpenOptions::new()
+					.write(true)
+					.create(true)
+					.truncate(false)
+					.open(&target_path)
+					.await
+					.wrap_err_with(|| {
+						format!(r#"Failed to create the mount point for "{}"."#, mount.path)
+					})?;
+			},
+			tg::Artifact::Symlink(_) => {
+				return_error!(r#"Cannot mount the symlink at "{}"."#, mount.path);
+			},
+		}
+		let source_path = CString::new(source_path.as_os_str().as_bytes()).unwrap();
+		let target_path = CString::new(target_path.as_os_str().as_bytes())
+			.wrap_err("The mount path is not a valid C string.")?;
+		mounts.push(Mount {
+			source: source_path,
+			target: target_path,
+			fstype: None,
+			flags: libc::MS_BIND | libc::MS_REC,
+			data: None,
+			readonly: true,
+		});
+	}
+
 	// Create the executable.
 	let executable =
 		CString::new(executable).wrap_err("The executable is not a valid C string.")?;
@@ -480,15 +533,24 @@ pub async fn build(
 	let log_task = tokio::task::spawn({
 		let build = build.clone();
 		let tg = tg.clone_box();
+		let mut redactor = Redactor::new(secrets.values());
 		async move {
 			let mut buf = vec![0; 512];
 			loop {
 				match log_recv.read(&mut buf).await {
 					Err(error) => return Err(error.wrap("Failed to read from the log.")),
-					Ok(0) => return Ok(()),
+					Ok(0) => {
+						let log = redactor.finalize();
+						if !log.is_empty() {
+							build.add_log(tg.as_ref(), log).await?;
+						}
+						return Ok(());
+					},
 					Ok(size) => {
-						let log = Bytes::copy_from_slice(&buf[0..size]);
-						build.add_log(tg.as_ref(), log).await?;
+						let log = redactor.update(&buf[0..size]);
+						if !log.is_empty() {
+							build.add_log(tg.as_ref(), log).await?;
+						}
 					},
 				}
 			}
@@ -595,6 +657,27 @@ pub async fn build(
 		.await
 		.wrap_err("Failed to determine in the path exists.")?
 	{
+		// Check the output for references to host paths if requested.
+		if let Some(purity) = target.purity(tg).await? {
+			let artifacts_directory_guest_path = artifacts_directory_guest_path.to_str().unwrap();
+			let mut forbidden = vec![
+				root_directory_tempdir.path().to_str().unwrap().to_owned(),
+				output_parent_directory_host_path
+					.to_str()
+					.unwrap()
+					.to_owned(),
+				server_directory_host_path.to_str().unwrap().to_owned(),
+				WORKING_DIRECTORY_GUEST_PATH.to_owned(),
+				format!("{SERVER_DIRECTORY_GUEST_PATH}/"),
+			];
+			if let Some(home) = std::env::var("HOME").ok().filter(|home| home != "/") {
+				forbidden.push(home);
+			}
+			let allowed = vec![format!("{artifacts_directory_guest_path}/")];
+			let rules = crate::purity::Rules { forbidden, allowed };
+			crate::purity::check(tg, build, purity, &output_host_path, rules).await?;
+		}
+
 		// Check in the output.
 		let artifact = tg::Artifact::check_in(tg, &output_host_path.clone().try_into()?)
 			.await
@@ -621,6 +704,62 @@ pub async fn build(
 	Ok(value)
 }
 
+/// Validate a target's mounts. Mount paths must be absolute and normalized, must not overlap the paths the runtime mounts, and must not be nested within one another.
+fn validate_mounts(mounts: &[tg::target::Mount]) -> Result<()> {
+	let reserved = [
+		"/dev",
+		"/proc",
+		"/tmp",
+		SERVER_DIRECTORY_GUEST_PATH,
+		HOME_DIRECTORY_GUEST_PATH,
+		OUTPUT_PARENT_DIRECTORY_GUEST_PATH,
+	];
+	let paths = mounts
+		.iter()
+		.map(|mount| mount_target_path(Path::new("/"), &mount.path))
+		.collect::<Result<Vec<_>>>()?;
+	for (index, path) in paths.iter().enumerate() {
+		if path == Path::new("/") {
+			return_error!("Cannot mount an artifact at the root.");
+		}
+		for reserved in reserved.iter().map(Path::new) {
+			if path.starts_with(reserved) || reserved.starts_with(path) {
+				return_error!(
+					r#"The mount path "{}" overlaps the reserved path "{}"."#,
+					path.display(),
+					reserved.display()
+				);
+			}
+		}
+		for other in &paths[..index] {
+			if path.starts_with(other) || other.starts_with(path) {
+				return_error!(
+					r#"The mount path "{}" overlaps the mount path "{}"."#,
+					path.display(),
+					other.display()
+				);
+			}
+		}
+	}
+	Ok(())
+}
+
+/// Get the path of a mount's target under a root directory. The mount path must be absolute and contain only normal components after the root, so the target can never escape the root directory.
+fn mount_target_path(root: &Path, path: &tg::Path) -> Result<PathBuf> {
+	let mut components = path.components().iter();
+	if components.next() != Some(&tg::path::Component::Root) {
+		return_error!(r#"The mount path "{path}" is not absolute."#);
+	}
+	let mut target = root.to_owned();
+	for component in components {
+		let tg::path::Component::Normal(name) = component else {
+			return_error!(r#"The mount path "{path}" is not normalized."#);
+		};
+		target.push(name);
+	}
+	Ok(target)
+}
+
 #[allow(clippy::too_many_lines)]
 fn root(context: &Context) {
 	unsafe {
@@ -952,3 +1091,25 @@ macro_rules! abort_errno {
 }
 
 use abort_errno;
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn mount_target_path_is_under_the_root() {
+		let root = Path::new("/var/tangram/root");
+		let path: tg::Path = "/usr/lib".parse().unwrap();
+		let target = mount_target_path(root, &path).unwrap();
+		assert_eq!(target, Path::new("/var/tangram/root/usr/lib"));
+	}
+
+	#[test]
+	fn mount_target_path_rejects_parent_and_current() {
+		let root = Path::new("/var/tangram/root");
+		for path in ["/usr/../../etc", "./usr", "usr/lib"] {
+			let path: tg::Path = path.parse().unwrap();
+			assert!(mount_target_path(root, &path).is_err());
+		}
+	}
+}
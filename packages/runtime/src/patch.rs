@@ -0,0 +1,676 @@
+use tangram_client as tg;
+use tangram_error::{return_error, Result, WrapErr};
+
+/// A patch to a single file, parsed from a unified or git-style diff.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FilePatch {
+	/// The path of the file before the patch, or `None` if the file is created.
+	pub old_path: Option<String>,
+
+	/// The path of the file after the patch, or `None` if the file is deleted.
+	pub new_path: Option<String>,
+
+	/// The mode of the file before the patch, if the diff specifies one.
+	pub old_mode: Option<u32>,
+
+	/// The mode of the file after the patch, if the diff specifies one.
+	pub new_mode: Option<u32>,
+
+	/// Whether the new file is a copy of the old file rather than a rename.
+	pub copy: bool,
+
+	/// The hunks.
+	pub hunks: Vec<Hunk>,
+}
+
+/// A hunk of a file patch.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Hunk {
+	/// The line the hunk starts at in the old file.
+	pub old_start: usize,
+
+	/// The lines of the hunk. Each line includes its line ending unless it is the last line of a file without a trailing newline.
+	pub lines: Vec<Line>,
+}
+
+/// A line of a hunk.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Line {
+	Context(String),
+	Remove(String),
+	Add(String),
+}
+
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Options {
+	/// The number of leading components to remove from each path in the patches, like `patch -p`. The default is 1, which removes the `a/` and `b/` prefixes of git diffs.
+	#[serde(default = "default_strip")]
+	pub strip: usize,
+}
+
+impl Default for Options {
+	fn default() -> Self {
+		Self {
+			strip: default_strip(),
+		}
+	}
+}
+
+fn default_strip() -> usize {
+	1
+}
+
+/// The mode git uses for symlinks.
+const SYMLINK_MODE: u32 = 0o120_000;
+
+/// Apply patches to a directory. Each path in the patches has `options.strip` leading components removed.
+pub async fn apply(
+	tg: &dyn tg::Handle,
+	directory: &tg::Directory,
+	patches: &[String],
+	options: &Options,
+) -> Result<tg::Directory> {
+	let mut directory = directory.clone();
+	for (index, patch) in patches.iter().enumerate() {
+		let file_patches =
+			parse(patch).wrap_err_with(|| format!("Failed to parse patch #{}.", index + 1))?;
+		for file_patch in file_patches {
+			directory = apply_file_patch(tg, &directory, &file_patch, options.strip)
+				.await
+				.wrap_err_with(|| format!("Failed to apply patch #{}.", index + 1))?;
+		}
+	}
+	Ok(directory)
+}
+
+async fn apply_file_patch(
+	tg: &dyn tg::Handle,
+	directory: &tg::Directory,
+	file_patch: &FilePatch,
+	strip: usize,
+) -> Result<tg::Directory> {
+	let old_path = file_patch
+		.old_path
+		.as_deref()
+		.map(|path| strip_path(path, strip))
+		.transpose()?;
+	let new_path = file_patch
+		.new_path
+		.as_deref()
+		.map(|path| strip_path(path, strip))
+		.transpose()?;
+	let mode = file_patch.new_mode.or(file_patch.old_mode);
+
+	// Get the old artifact without resolving it if it is a symlink.
+	let old_artifact = if let Some(old_path) = &old_path {
+		let artifact = try_get_entry(tg, directory, old_path).await?;
+		let Some(artifact) = artifact else {
+			return_error!(r#"The file "{old_path}" does not exist."#);
+		};
+		Some(artifact)
+	} else {
+		None
+	};
+
+	// A created, renamed, or copied file must not replace an existing file.
+	if let Some(new_path) = &new_path {
+		if old_path.as_ref() != Some(new_path)
+			&& try_get_entry(tg, directory, new_path).await?.is_some()
+		{
+			return_error!(r#"The file "{new_path}" already exists."#);
+		}
+	}
+
+	// Create the new artifact.
+	let artifact: tg::Artifact = match old_artifact {
+		// If there are no hunks, then move the old artifact unchanged, except for its executable bit.
+		Some(tg::Artifact::File(file)) if file_patch.hunks.is_empty() => {
+			let executable = file.executable(tg).await?;
+			match mode.map(|mode| mode & 0o111 != 0) {
+				Some(new_executable) if new_executable != executable => {
+					let contents = file.contents(tg).await?.clone();
+					let references = file.references(tg).await?.to_owned();
+					tg::File::new(contents, new_executable, references).into()
+				},
+				_ => file.into(),
+			}
+		},
+		Some(artifact) if file_patch.hunks.is_empty() => artifact,
+
+		// Otherwise, apply the hunks to the old contents. Git diffs a symlink's target as its contents.
+		old_artifact => {
+			let (contents, executable, references, is_symlink) = match &old_artifact {
+				Some(tg::Artifact::File(file)) => {
+					let contents = file.text(tg).await?;
+					let executable = file.executable(tg).await?;
+					let references = file.references(tg).await?.to_owned();
+					(contents, executable, references, false)
+				},
+				Some(tg::Artifact::Symlink(symlink)) => {
+					if symlink.artifact(tg).await?.is_some() {
+						return_error!("Cannot patch a symlink that refers to an artifact.");
+					}
+					let target = symlink.path(tg).await?.clone().unwrap_or_default();
+					(target, false, Vec::new(), true)
+				},
+				Some(tg::Artifact::Directory(_)) => {
+					return_error!("Cannot patch a directory.");
+				},
+				None => (String::new(), false, Vec::new(), false),
+			};
+			let display_path = new_path.as_ref().or(old_path.as_ref()).unwrap().to_string();
+			let contents = apply_hunks(&display_path, &contents, &file_patch.hunks)?;
+			let is_symlink = mode.map_or(is_symlink, |mode| mode & 0o170_000 == SYMLINK_MODE);
+			if is_symlink {
+				tg::Symlink::new(None, Some(contents)).into()
+			} else {
+				let executable = mode.map_or(executable, |mode| mode & 0o111 != 0);
+				let contents = tg::Blob::with_reader(tg, contents.as_bytes()).await?;
+				tg::File::new(contents, executable, references).into()
+			}
+		},
+	};
+
+	// Remove the old file if it was deleted or renamed.
+	let mut builder = directory.builder(tg).await?;
+	if let Some(old_path) = &old_path {
+		if new_path.as_ref() != Some(old_path) && !file_patch.copy {
+			builder = builder.remove(tg, old_path).await?;
+		}
+	}
+
+	// Add the new file.
+	if let Some(new_path) = &new_path {
+		builder = builder.add(tg, new_path, artifact).await?;
+	}
+
+	Ok(builder.build())
+}
+
+/// Get the entry at a path in a directory without resolving the entry if it is a symlink.
+async fn try_get_entry(
+	tg: &dyn tg::Handle,
+	directory: &tg::Directory,
+	path: &tg::Path,
+) -> Result<Option<tg::Artifact>> {
+	let Some((name, parent)) = path.components().split_last() else {
+		return Ok(None);
+	};
+	let name = name
+		.try_unwrap_normal_ref()
+		.ok()
+		.wrap_err("The path must contain only normal components.")?;
+	let parent: tg::Path = parent.iter().cloned().collect();
+	let parent = if parent.is_empty() {
+		directory.clone()
+	} else {
+		match directory.try_get(tg, &parent).await? {
+			Some(tg::Artifact::Directory(parent)) => parent,
+			_ => return Ok(None),
+		}
+	};
+	let entry = parent.entries(tg).await?.get(name).cloned();
+	Ok(entry)
+}
+
+/// Remove `strip` leading components from a path in a patch.
+fn strip_path(path: &str, strip: usize) -> Result<tg::Path> {
+	let path: tg::Path = path.parse()?;
+	let path: tg::Path = path.components().iter().skip(strip).cloned().collect();
+	if path.is_empty() {
+		return_error!("The patch contains an empty path.");
+	}
+	let is_normal = path
+		.components()
+		.iter()
+		.all(|component| component.try_unwrap_normal_ref().is_ok());
+	if !is_normal {
+		return_error!(r#"The path "{path}" must contain only normal components."#);
+	}
+	Ok(path)
+}
+
+/// Parse a unified or git-style diff.
+pub fn parse(text: &str) -> Result<Vec<FilePatch>> {
+	let lines: Vec<&str> = text.split_inclusive('\n').collect();
+	let mut file_patches: Vec<FilePatch> = Vec::new();
+	let mut in_git_header = false;
+	let mut git_prefixes = ("", "");
+	let mut i = 0;
+	while i < lines.len() {
+		let line = lines[i].trim_end_matches(['\r', '\n']);
+		if let Some(rest) = line.strip_prefix("diff --git ") {
+			// Start a git file patch. The paths are overridden by later headers if they are present.
+			let (old_path, new_path) = parse_git_paths(rest)?;
+			git_prefixes = if old_path.starts_with("a/") && new_path.starts_with("b/") {
+				("a/", "b/")
+			} else {
+				("", "")
+			};
+			file_patches.push(FilePatch {
+				old_path: Some(old_path),
+				new_path: Some(new_path),
+				..Default::default()
+			});
+			in_git_header = true;
+			i += 1;
+		} else if in_git_header && !line.starts_with("--- ") && !line.starts_with("@@ ") {
+			// Parse a git extended header.
+			let file_patch = file_patches.last_mut().unwrap();
+			if let Some(mode) = line.strip_prefix("old mode ") {
+				file_patch.old_mode = Some(parse_mode(mode)?);
+			} else if let Some(mode) = line.strip_prefix("new mode ") {
+				file_patch.new_mode = Some(parse_mode(mode)?);
+			} else if let Some(mode) = line.strip_prefix("new file mode ") {
+				file_patch.old_path = None;
+				file_patch.new_mode = Some(parse_mode(mode)?);
+			} else if let Some(mode) = line.strip_prefix("deleted file mode ") {
+				file_patch.new_path = None;
+				file_patch.old_mode = Some(parse_mode(mode)?);
+			} else if let Some(path) = line.strip_prefix("rename from ") {
+				file_patch.old_path = Some(format!("{}{}", git_prefixes.0, unquote(path)?));
+			} else if let Some(path) = line.strip_prefix("rename to ") {
+				file_patch.new_path = Some(format!("{}{}", git_prefixes.1, unquote(path)?));
+			} else if let Some(path) = line.strip_prefix("copy from ") {
+				file_patch.old_path = Some(format!("{}{}", git_prefixes.0, unquote(path)?));
+				file_patch.copy = true;
+			} else if let Some(path) = line.strip_prefix("copy to ") {
+				file_patch.new_path = Some(format!("{}{}", git_prefixes.1, unquote(path)?));
+			} else if line.starts_with("GIT binary patch") || line.starts_with("Binary files ") {
+				return_error!("Binary patches are not supported.");
+			} else if line.starts_with("diff ") {
+				in_git_header = false;
+				continue;
+			}
+			i += 1;
+		} else if let (Some(old), Some(new)) = (
+			line.strip_prefix("--- "),
+			lines
+				.get(i + 1)
+				.and_then(|line| line.trim_end_matches(['\r', '\n']).strip_prefix("+++ ")),
+		) {
+			// Parse the file headers, starting a new file patch unless they follow a git header.
+			let old_path = parse_header_path(old)?;
+			let new_path = parse_header_path(new)?;
+			if !in_git_header {
+				file_patches.push(FilePatch::default());
+			}
+			let file_patch = file_patches.last_mut().unwrap();
+			file_patch.old_path = old_path;
+			file_patch.new_path = new_path;
+			in_git_header = false;
+			i += 2;
+		} else if line.starts_with("@@ ") {
+			// Parse a hunk.
+			let file_patch = file_patches
+				.last_mut()
+				.wrap_err("Expected a file header before the hunk.")?;
+			let (hunk, consumed) = parse_hunk(&lines[i..])?;
+			file_patch.hunks.push(hunk);
+			in_git_header = false;
+			i += consumed;
+		} else {
+			// Ignore any other lines, such as commit messages and `Only in` lines.
+			i += 1;
+		}
+	}
+	if file_patches.is_empty() {
+		return_error!("The patch does not contain any changes.");
+	}
+	Ok(file_patches)
+}
+
+/// Parse the paths in a `diff --git` line.
+fn parse_git_paths(rest: &str) -> Result<(String, String)> {
+	if let Some(quoted) = rest.strip_prefix('"') {
+		let end = quoted.find("\" ").wrap_err("Invalid diff header.")?;
+		let old = unquote(&rest[..end + 2])?;
+		let new = unquote(quoted[end + 2..].trim())?;
+		return Ok((old, new));
+	}
+
+	// The paths are ambiguous if they contain spaces, so assume that the old and new paths are the same length.
+	let (old, new) = if rest.len() % 2 == 1 && rest.as_bytes()[rest.len() / 2] == b' ' {
+		(&rest[..rest.len() / 2], &rest[rest.len() / 2 + 1..])
+	} else if let Some(index) = rest.find(" b/").filter(|_| rest.starts_with("a/")) {
+		(&rest[..index], &rest[index + 1..])
+	} else {
+		return_error!("Invalid diff header.");
+	};
+	Ok((old.to_owned(), new.to_owned()))
+}
+
+/// Parse a path from a `---` or `+++` line, removing any timestamp.
+fn parse_header_path(value: &str) -> Result<Option<String>> {
+	let path = if value.starts_with('"') {
+		unquote(value.split('\t').next().unwrap().trim_end())?
+	} else {
+		value.split('\t').next().unwrap().trim_end().to_owned()
+	};
+	if path == "/dev/null" {
+		return Ok(None);
+	}
+	Ok(Some(path))
+}
+
+/// Remove the quotes and escapes from a quoted path in a git diff.
+fn unquote(value: &str) -> Result<String> {
+	let Some(value) = value
+		.strip_prefix('"')
+		.and_then(|value| value.strip_suffix('"'))
+	else {
+		return Ok(value.to_owned());
+	};
+	let mut bytes = Vec::new();
+	let mut chars = value.chars();
+	while let Some(c) = chars.next() {
+		if c != '\\' {
+			let mut buffer = [0; 4];
+			bytes.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+			continue;
+		}
+		match chars.next().wrap_err("Invalid quoted path.")? {
+			'n' => bytes.push(b'\n'),
+			't' => bytes.push(b'\t'),
+			'"' => bytes.push(b'"'),
+			'\\' => bytes.push(b'\\'),
+			c @ '0'..='7' => {
+				// Parse an octal escape for a byte of a UTF-8 sequence.
+				let digits: String = std::iter::once(c).chain(chars.by_ref().take(2)).collect();
+				let byte = u8::from_str_radix(&digits, 8).wrap_err("Invalid quoted path.")?;
+				bytes.push(byte);
+			},
+			_ => return_error!("Invalid quoted path."),
+		}
+	}
+	let value = String::from_utf8(bytes).wrap_err("Invalid quoted path.")?;
+	Ok(value)
+}
+
+/// Parse a file mode in a git extended header.
+fn parse_mode(value: &str) -> Result<u32> {
+	let mode = u32::from_str_radix(value.trim(), 8).wrap_err("Invalid file mode.")?;
+	Ok(mode)
+}
+
+/// Parse a hunk, returning the hunk and the number of lines consumed.
+fn parse_hunk(lines: &[&str]) -> Result<(Hunk, usize)> {
+	// Parse the header, which looks like `@@ -1,5 +1,6 @@`.
+	let header = lines[0].trim_end_matches(['\r', '\n']);
+	let ranges = header
+		.strip_prefix("@@ ")
+		.and_then(|header| header.split(" @@").next())
+		.wrap_err("Invalid hunk header.")?;
+	let (old, new) = ranges.split_once(' ').wrap_err("Invalid hunk header.")?;
+	let (old_start, mut old_count) = parse_range(old.strip_prefix('-'))?;
+	let (_, mut new_count) = parse_range(new.strip_prefix('+'))?;
+
+	// Parse the lines.
+	let mut hunk = Hunk {
+		old_start,
+		lines: Vec::new(),
+	};
+	let mut i = 1;
+	while old_count > 0 || new_count > 0 {
+		let line = lines.get(i).wrap_err("The hunk is truncated.")?;
+		let (kind, text) = line.split_at(line.len().min(1));
+		match kind {
+			" " => {
+				old_count = old_count.checked_sub(1).wrap_err("Invalid hunk.")?;
+				new_count = new_count.checked_sub(1).wrap_err("Invalid hunk.")?;
+				hunk.lines.push(Line::Context(text.to_owned()));
+			},
+			// Some tools remove the trailing whitespace from empty context lines.
+			"\n" | "\r" | "" => {
+				old_count = old_count.checked_sub(1).wrap_err("Invalid hunk.")?;
+				new_count = new_count.checked_sub(1).wrap_err("Invalid hunk.")?;
+				hunk.lines.push(Line::Context(line.to_string()));
+			},
+			"-" => {
+				old_count = old_count.checked_sub(1).wrap_err("Invalid hunk.")?;
+				hunk.lines.push(Line::Remove(text.to_owned()));
+			},
+			"+" => {
+				new_count = new_count.checked_sub(1).wrap_err("Invalid hunk.")?;
+				hunk.lines.push(Line::Add(text.to_owned()));
+			},
+			"\\" => (),
+			_ => return_error!("Invalid hunk line {line:?}."),
+		}
+		i += 1;
+
+		// Handle a marker that the previous line has no trailing newline.
+		if lines.get(i).is_some_and(|line| line.starts_with('\\')) {
+			if let Some(Line::Context(text) | Line::Remove(text) | Line::Add(text)) =
+				hunk.lines.last_mut()
+			{
+				let trimmed = text.strip_suffix('\n').unwrap_or(text);
+				let trimmed = trimmed.strip_suffix('\r').unwrap_or(trimmed);
+				*text = text[..trimmed.len()].to_owned();
+			}
+			i += 1;
+		}
+	}
+
+	Ok((hunk, i))
+}
+
+/// Parse a hunk range such as `1,5` or `3`.
+fn parse_range(range: Option<&str>) -> Result<(usize, usize)> {
+	let range = range.wrap_err("Invalid hunk header.")?;
+	let (start, count) = match range.split_once(',') {
+		Some((start, count)) => (start, count.parse().wrap_err("Invalid hunk header.")?),
+		None => (range, 1),
+	};
+	let start = start.parse().wrap_err("Invalid hunk header.")?;
+	Ok((start, count))
+}
+
+/// Apply hunks to a file's contents. Each hunk's context must match exactly, but may be found at an offset from the line in its header.
+pub fn apply_hunks(path: &str, contents: &str, hunks: &[Hunk]) -> Result<String> {
+	let lines: Vec<&str> = contents.split_inclusive('\n').collect();
+	let mut output: Vec<&str> = Vec::new();
+	let mut position = 0;
+	let mut offset: isize = 0;
+	for (index, hunk) in hunks.iter().enumerate() {
+		let old: Vec<&str> = hunk
+			.lines
+			.iter()
+			.filter_map(|line| match line {
+				Line::Context(text) | Line::Remove(text) => Some(text.as_str()),
+				Line::Add(_) => None,
+			})
+			.collect();
+		let new: Vec<&str> = hunk
+			.lines
+			.iter()
+			.filter_map(|line| match line {
+				Line::Context(text) | Line::Add(text) => Some(text.as_str()),
+				Line::Remove(_) => None,
+			})
+			.collect();
+
+		// Find the hunk, starting at the line in its header adjusted by the offset of the previous hunk.
+		let start = if old.is_empty() {
+			hunk.old_start
+		} else {
+			hunk.old_start.saturating_sub(1)
+		};
+		let expected = start.saturating_add_signed(offset).max(position);
+		let Some(found) = find_lines(&lines, &old, expected, position) else {
+			let number = index + 1;
+			let line = hunk.old_start;
+			return_error!(r#"Hunk #{number} failed to apply at line {line} of "{path}"."#);
+		};
+
+		// Replace the lines.
+		output.extend_from_slice(&lines[position..found]);
+		output.extend_from_slice(&new);
+		position = found + old.len();
+		#[allow(clippy::cast_possible_wrap)]
+		{
+			offset = found as isize - start as isize;
+		}
+	}
+	output.extend_from_slice(&lines[position..]);
+	Ok(output.concat())
+}
+
+/// Find the position of a sequence of lines at or after `minimum`, searching outward from `expected`.
+fn find_lines(lines: &[&str], needle: &[&str], expected: usize, minimum: usize) -> Option<usize> {
+	let last = lines.len().checked_sub(needle.len())?;
+	let matches = |position: usize| lines[position..position + needle.len()] == *needle;
+	let max_distance = last
+		.saturating_sub(expected)
+		.max(expected.saturating_sub(minimum));
+	(0..=max_distance).find_map(|distance| {
+		let after = Some(expected + distance).filter(|after| *after <= last);
+		let before = expected
+			.checked_sub(distance)
+			.filter(|before| distance > 0 && *before >= minimum && *before <= last);
+		after
+			.filter(|after| matches(*after))
+			.or(before.filter(|before| matches(*before)))
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tg::test::{directory, file, Store};
+
+	#[test]
+	fn test_apply() {
+		let patch = "diff --git a/src/main.c b/src/main.c\nindex 1234567..89abcde 100644\n--- a/src/main.c\n+++ b/src/main.c\n@@ -2,3 +2,3 @@\n b\n-c\n+C\n d\n";
+		let file_patches = parse(patch).unwrap();
+		assert_eq!(file_patches.len(), 1);
+		assert_eq!(file_patches[0].old_path.as_deref(), Some("a/src/main.c"));
+
+		// The hunk applies at an offset.
+		let contents = "x\na\nb\nc\nd\ne\n";
+		let output = apply_hunks("src/main.c", contents, &file_patches[0].hunks).unwrap();
+		assert_eq!(output, "x\na\nb\nC\nd\ne\n");
+
+		// The hunk fails if its context does not match.
+		let error = apply_hunks("src/main.c", "a\nb\nX\nd\n", &file_patches[0].hunks).unwrap_err();
+		assert!(error.to_string().contains("Hunk #1 failed"));
+	}
+
+	#[test]
+	fn test_parse_git_headers() {
+		let patch = "diff --git a/old.sh b/new.sh\nold mode 100644\nnew mode 100755\nsimilarity index 100%\nrename from old.sh\nrename to new.sh\ndiff --git a/new.txt b/new.txt\nnew file mode 100644\n--- /dev/null\n+++ b/new.txt\n@@ -0,0 +1 @@\n+hello\n\\ No newline at end of file\n";
+		let file_patches = parse(patch).unwrap();
+		assert_eq!(file_patches.len(), 2);
+		assert_eq!(file_patches[0].old_path.as_deref(), Some("a/old.sh"));
+		assert_eq!(file_patches[0].new_path.as_deref(), Some("b/new.sh"));
+		assert_eq!(file_patches[0].new_mode, Some(0o100_755));
+		assert_eq!(file_patches[1].old_path, None);
+		let output = apply_hunks("new.txt", "", &file_patches[1].hunks).unwrap();
+		assert_eq!(output, "hello");
+	}
+
+	#[test]
+	fn test_parse_git_headers_without_prefixes() {
+		let patch = "diff --git old.sh new.sh\nsimilarity index 100%\nrename from old.sh\nrename to new.sh\n";
+		let file_patches = parse(patch).unwrap();
+		assert_eq!(file_patches.len(), 1);
+		assert_eq!(file_patches[0].old_path.as_deref(), Some("old.sh"));
+		assert_eq!(file_patches[0].new_path.as_deref(), Some("new.sh"));
+		assert_eq!(file_patches[0].hunks, []);
+	}
+
+	#[test]
+	fn test_strip_path() {
+		assert_eq!(
+			strip_path("a/src/main.c", 1).unwrap().to_string(),
+			"src/main.c"
+		);
+		assert_eq!(
+			strip_path("src/main.c", 0).unwrap().to_string(),
+			"src/main.c"
+		);
+		assert_eq!(
+			strip_path("x/y/src/main.c", 2).unwrap().to_string(),
+			"src/main.c"
+		);
+		let error = strip_path("main.c", 1).unwrap_err();
+		assert!(error.to_string().contains("empty path"));
+		assert!(strip_path("a/../main.c", 1).is_err());
+	}
+
+	async fn apply_one(
+		tg: &Store,
+		directory: &tg::Artifact,
+		patch: &str,
+		strip: usize,
+	) -> Result<tg::Directory> {
+		let directory = directory.try_unwrap_directory_ref().unwrap();
+		apply(tg, directory, &[patch.to_owned()], &Options { strip }).await
+	}
+
+	#[tokio::test]
+	async fn test_apply_without_hunks() {
+		let tg = Store::default();
+		let contents = tg::Leaf::with_object(tg::leaf::Object {
+			bytes: vec![0xff, 0xfe].into(),
+		});
+		let binary: tg::Artifact = tg::File::new(contents.into(), false, vec![]).into();
+		let root = directory(vec![("old.bin", binary.clone())]);
+
+		// A rename moves a file that is not UTF-8 unchanged.
+		let patch = "diff --git a/old.bin b/new.bin\nsimilarity index 100%\nrename from old.bin\nrename to new.bin\n";
+		let output = apply_one(&tg, &root, patch, 1).await.unwrap();
+		let entries = output.entries(&tg).await.unwrap();
+		assert!(!entries.contains_key("old.bin"));
+		assert_eq!(
+			entries["new.bin"].id(&tg).await.unwrap(),
+			binary.id(&tg).await.unwrap()
+		);
+
+		// A mode change only sets the executable bit.
+		let patch = "diff --git a/old.bin b/old.bin\nold mode 100644\nnew mode 100755\n";
+		let output = apply_one(&tg, &root, patch, 1).await.unwrap();
+		let file = output.entries(&tg).await.unwrap()["old.bin"]
+			.clone()
+			.try_unwrap_file()
+			.unwrap();
+		assert!(file.executable(&tg).await.unwrap());
+		assert_eq!(file.bytes(&tg).await.unwrap(), [0xff, 0xfe]);
+	}
+
+	#[tokio::test]
+	async fn test_apply_symlink() {
+		let tg = Store::default();
+		let symlink = tg::Symlink::new(None, Some("old".to_owned()));
+		let root = directory(vec![("link", symlink.into())]);
+		let patch = "diff --git a/link b/link\nindex 1234567..89abcde 120000\n--- a/link\n+++ b/link\n@@ -1 +1 @@\n-old\n\\ No newline at end of file\n+new\n\\ No newline at end of file\n";
+		let output = apply_one(&tg, &root, patch, 1).await.unwrap();
+		let symlink = output.entries(&tg).await.unwrap()["link"]
+			.clone()
+			.try_unwrap_symlink()
+			.unwrap();
+		assert_eq!(symlink.path(&tg).await.unwrap().as_deref(), Some("new"));
+	}
+
+	#[tokio::test]
+	async fn test_apply_strip_and_existing_files() {
+		let tg = Store::default();
+		let root = directory(vec![("hello.txt", file("hello\n", false))]);
+
+		// Plain diffs without prefixes apply with a strip of zero.
+		let patch = "--- hello.txt\n+++ hello.txt\n@@ -1 +1 @@\n-hello\n+goodbye\n";
+		let output = apply_one(&tg, &root, patch, 0).await.unwrap();
+		let file = output.entries(&tg).await.unwrap()["hello.txt"]
+			.clone()
+			.try_unwrap_file()
+			.unwrap();
+		assert_eq!(file.text(&tg).await.unwrap(), "goodbye\n");
+		assert!(apply_one(&tg, &root, patch, 1).await.is_err());
+
+		// Creating a file that already exists is an error.
+		let patch = "--- /dev/null\n+++ b/hello.txt\n@@ -0,0 +1 @@\n+hello\n";
+		let error = apply_one(&tg, &root, patch, 1).await.unwrap_err();
+		assert!(error.trace().to_string().contains("already exists"));
+	}
+}
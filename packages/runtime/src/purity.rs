@@ -0,0 +1,227 @@
+use bytes::Bytes;
+use std::{
+	io::Read,
+	os::unix::ffi::OsStrExt,
+	path::{Path, PathBuf},
+};
+use tangram_client as tg;
+use tangram_error::{return_error, Result, WrapErr};
+
+/// The size of the chunks that files are read in.
+const CHUNK_SIZE: usize = 65536;
+
+/// The rules to check a build's output against.
+#[derive(Clone, Debug, Default)]
+pub struct Rules {
+	/// Prefixes that must not appear in the output.
+	pub forbidden: Vec<String>,
+
+	/// Paths that may appear in the output even though they start with a forbidden prefix.
+	pub allowed: Vec<String>,
+}
+
+/// A reference to a forbidden path in a build's output.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Violation {
+	/// The path of the file or symlink, relative to the output.
+	pub path: PathBuf,
+
+	/// The byte offset of the reference.
+	pub offset: u64,
+
+	/// The forbidden prefix.
+	pub prefix: String,
+}
+
+/// Check a build's output for references to forbidden paths and either warn or fail according to the target's purity configuration.
+pub async fn check(
+	tg: &dyn tg::Handle,
+	build: &tg::Build,
+	purity: &tg::target::Purity,
+	output_path: &Path,
+	mut rules: Rules,
+) -> Result<()> {
+	// Add the prefixes from the target.
+	rules.forbidden.extend(purity.prefixes.iter().cloned());
+	rules.forbidden.retain(|prefix| !prefix.is_empty());
+
+	// Scan the output.
+	let violations = tokio::task::spawn_blocking({
+		let output_path = output_path.to_owned();
+		move || scan_path(&output_path, &rules)
+	})
+	.await
+	.wrap_err("Failed to join the scan task.")??;
+	if violations.is_empty() {
+		return Ok(());
+	}
+
+	// Format the violations.
+	let message = violations
+		.iter()
+		.map(|violation| {
+			format!(
+				r#"{}:{}: found a reference to "{}"."#,
+				violation.path.display(),
+				violation.offset,
+				violation.prefix,
+			)
+		})
+		.collect::<Vec<_>>()
+		.join("\n");
+
+	// Warn or fail.
+	match purity.mode {
+		tg::target::PurityMode::Warn => {
+			let log = format!("warning: the output references host paths.\n{message}\n");
+			build.add_log(tg, Bytes::from(log)).await?;
+			Ok(())
+		},
+		tg::target::PurityMode::Fail => {
+			return_error!("The output references host paths.\n{message}");
+		},
+	}
+}
+
+/// Scan the file, directory, or symlink at a path.
+fn scan_path(output_path: &Path, rules: &Rules) -> Result<Vec<Violation>> {
+	let mut violations = Vec::new();
+	let mut stack = vec![PathBuf::new()];
+	while let Some(relative_path) = stack.pop() {
+		let path = output_path.join(&relative_path);
+		let metadata = std::fs::symlink_metadata(&path).wrap_err("Failed to get the metadata.")?;
+		if metadata.is_dir() {
+			let mut entries = std::fs::read_dir(&path)
+				.wrap_err("Failed to read the directory.")?
+				.map(|entry| Ok(entry?.file_name()))
+				.collect::<std::io::Result<Vec<_>>>()
+				.wrap_err("Failed to read the directory entry.")?;
+			entries.sort();
+			for name in entries.into_iter().rev() {
+				stack.push(relative_path.join(name));
+			}
+		} else if metadata.is_symlink() {
+			let target = std::fs::read_link(&path).wrap_err("Failed to read the symlink.")?;
+			let target = target.as_os_str().as_bytes();
+			violations.extend(
+				scan(target, rules)
+					.wrap_err("Failed to scan the symlink.")?
+					.into_iter()
+					.map(|(offset, prefix)| Violation {
+						path: relative_path.clone(),
+						offset,
+						prefix,
+					}),
+			);
+		} else if metadata.is_file() {
+			let file = std::fs::File::open(&path).wrap_err("Failed to open the file.")?;
+			violations.extend(
+				scan(file, rules)
+					.wrap_err("Failed to scan the file.")?
+					.into_iter()
+					.map(|(offset, prefix)| Violation {
+						path: relative_path.clone(),
+						offset,
+						prefix,
+					}),
+			);
+		}
+	}
+	Ok(violations)
+}
+
+/// Scan a reader for forbidden prefixes, returning the offset and prefix of each reference.
+fn scan(mut reader: impl Read, rules: &Rules) -> std::io::Result<Vec<(u64, String)>> {
+	let mut violations = Vec::new();
+	let overlap = rules
+		.forbidden
+		.iter()
+		.chain(rules.allowed.iter())
+		.map(String::len)
+		.max()
+		.unwrap_or(0);
+	if overlap == 0 {
+		return Ok(violations);
+	}
+
+	// Read the input in chunks, keeping enough bytes from the previous chunk to match across chunk boundaries.
+	let mut buffer = Vec::with_capacity(CHUNK_SIZE + overlap);
+	let mut buffer_offset: u64 = 0;
+	let mut chunk = vec![0; CHUNK_SIZE];
+	loop {
+		let size = reader.read(&mut chunk)?;
+		let eof = size == 0;
+		buffer.extend_from_slice(&chunk[..size]);
+
+		// Only check positions that have enough lookahead, unless this is the end of the input.
+		let end = if eof {
+			buffer.len()
+		} else {
+			buffer.len().saturating_sub(overlap)
+		};
+		for position in 0..end {
+			let rest = &buffer[position..];
+			let Some(prefix) = rules
+				.forbidden
+				.iter()
+				.find(|prefix| rest.starts_with(prefix.as_bytes()))
+			else {
+				continue;
+			};
+			if rules
+				.allowed
+				.iter()
+				.any(|allowed| rest.starts_with(allowed.as_bytes()))
+			{
+				continue;
+			}
+			violations.push((buffer_offset + position as u64, prefix.clone()));
+		}
+
+		if eof {
+			break;
+		}
+
+		// Keep the unchecked bytes for the next chunk.
+		buffer.drain(..end);
+		buffer_offset += end as u64;
+	}
+	Ok(violations)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn rules() -> Rules {
+		Rules {
+			forbidden: vec!["/home/tangram/work".to_owned(), "/.tangram/".to_owned()],
+			allowed: vec!["/.tangram/artifacts/".to_owned()],
+		}
+	}
+
+	#[test]
+	fn test_scan() {
+		let input = b"#!/bin/sh\ncd /home/tangram/work/src\nexec /.tangram/artifacts/abc/bin/sh\n/.tangram/tmp/x";
+		let violations = scan(&input[..], &rules()).unwrap();
+		assert_eq!(
+			violations,
+			vec![
+				(13, "/home/tangram/work".to_owned()),
+				(72, "/.tangram/".to_owned()),
+			]
+		);
+	}
+
+	#[test]
+	fn test_scan_across_chunks() {
+		let mut input = vec![b'a'; CHUNK_SIZE - 4];
+		input.extend_from_slice(b"/home/tangram/work");
+		input.extend_from_slice(&vec![b'a'; CHUNK_SIZE]);
+		let violations = scan(input.as_slice(), &rules()).unwrap();
+		assert_eq!(
+			violations,
+			vec![((CHUNK_SIZE - 4) as u64, "/home/tangram/work".to_owned())]
+		);
+	}
+}
@@ -1,3 +1,4 @@
+use bytes::Bytes;
 use std::path::Path;
 use tangram_client as tg;
 use tangram_error::Result;
@@ -33,3 +34,120 @@ pub async fn render(
 		Ok("<tangram value>".to_owned())
 	}
 }
+
+/// Replaces secrets in a stream of log bytes.
+pub struct Redactor {
+	buffer: Vec<u8>,
+	secrets: Vec<Vec<u8>>,
+}
+
+impl Redactor {
+	/// The text secrets are replaced with.
+	const REPLACEMENT: &'static [u8] = b"[REDACTED]";
+
+	#[must_use]
+	pub fn new<'a>(secrets: impl IntoIterator<Item = &'a String>) -> Self {
+		let secrets = secrets
+			.into_iter()
+			.filter(|secret| !secret.is_empty())
+			.map(|secret| secret.as_bytes().to_vec())
+			.collect();
+		Self {
+			buffer: Vec::new(),
+			secrets,
+		}
+	}
+
+	/// Redact a chunk of the stream. Bytes that may be the beginning of a secret are held back until the next chunk.
+	pub fn update(&mut self, bytes: &[u8]) -> Bytes {
+		self.buffer.extend_from_slice(bytes);
+		self.redact(false)
+	}
+
+	/// Redact the remainder of the stream.
+	pub fn finalize(&mut self) -> Bytes {
+		self.redact(true)
+	}
+
+	fn redact(&mut self, eof: bool) -> Bytes {
+		let mut output = Vec::with_capacity(self.buffer.len());
+		let mut position = 0;
+		while position < self.buffer.len() {
+			// Find the end of the secrets that start at this position and of the secrets that overlap them, so that no part of an overlapping secret is written. Hold back the rest of the buffer if a secret may start at one of these positions.
+			let mut end = position;
+			let mut index = position;
+			let mut partial = false;
+			loop {
+				let rest = &self.buffer[index..];
+				for secret in &self.secrets {
+					if rest.starts_with(secret) {
+						end = end.max(index + secret.len());
+					} else if !eof && secret.starts_with(rest) {
+						partial = true;
+					}
+				}
+				index += 1;
+				if index >= end {
+					break;
+				}
+			}
+			if partial {
+				break;
+			}
+
+			// Replace the secrets or write the byte.
+			if end > position {
+				output.extend_from_slice(Self::REPLACEMENT);
+				position = end;
+			} else {
+				output.push(self.buffer[position]);
+				position += 1;
+			}
+		}
+		self.buffer.drain(..position);
+		output.into()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Redactor;
+
+	fn redact(secrets: &[&str], chunks: &[&str]) -> String {
+		let secrets = secrets
+			.iter()
+			.map(|secret| (*secret).to_owned())
+			.collect::<Vec<_>>();
+		let mut redactor = Redactor::new(&secrets);
+		let mut output = Vec::new();
+		for chunk in chunks {
+			output.extend_from_slice(&redactor.update(chunk.as_bytes()));
+		}
+		output.extend_from_slice(&redactor.finalize());
+		String::from_utf8(output).unwrap()
+	}
+
+	#[test]
+	fn redactor_overlapping_secrets() {
+		// Overlapping secrets are replaced together.
+		let secrets = ["abc", "bcd"];
+		assert_eq!(redact(&secrets, &["xabcdy"]), "x[REDACTED]y");
+		assert_eq!(
+			redact(&secrets, &["xbcdy abcy"]),
+			"x[REDACTED]y [REDACTED]y"
+		);
+
+		// A secret that contains another secret is replaced entirely.
+		let secrets = ["key", "secret-key"];
+		assert_eq!(redact(&secrets, &["a secret-key!"]), "a [REDACTED]!");
+		assert_eq!(redact(&secrets, &["a secret-ke"]), "a secret-ke");
+
+		// Secrets are replaced when they are split across chunks.
+		let chunks = "xabcdy".split_inclusive(|_| true).collect::<Vec<_>>();
+		assert_eq!(redact(&["abc", "bcd"], &chunks), "x[REDACTED]y");
+		assert_eq!(
+			redact(&["key", "secret-key"], &["a secr", "et-k", "ey!"]),
+			"a [REDACTED]!"
+		);
+	}
+}
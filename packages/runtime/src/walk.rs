@@ -0,0 +1,383 @@
+use globset::{GlobBuilder, GlobSetBuilder};
+use std::collections::VecDeque;
+use tangram_client as tg;
+use tangram_error::{return_error, Result, WrapErr};
+
+/// The maximum number of symlinks to follow when resolving a single symlink.
+const MAX_SYMLINK_DEPTH: usize = 40;
+
+#[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Options {
+	/// Whether to resolve symlinks and descend into the directories they point to.
+	#[serde(default)]
+	pub follow_symlinks: bool,
+
+	/// The maximum depth of the entries to return, where the entries of the directory itself are at depth 1.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub max_depth: Option<usize>,
+}
+
+/// An entry to visit, along with the directories used to resolve its path if it is a symlink.
+struct Item {
+	path: tg::Path,
+	artifact: tg::Artifact,
+	parents: Vec<tg::Directory>,
+	ancestors: Vec<tg::directory::Id>,
+}
+
+/// Walk a directory, returning the path and artifact of every entry in depth-first order, with the entries of each directory sorted by name.
+pub async fn walk(
+	tg: &dyn tg::Handle,
+	directory: &tg::Directory,
+	options: &Options,
+) -> Result<Vec<(tg::Path, tg::Artifact)>> {
+	walk_inner(tg, directory, options, |_| true).await
+}
+
+/// Walk a directory, returning the entries whose paths match any of the glob patterns. In a pattern, `*` and `?` do not match `/`, `**` matches any number of path components, and `{a,b}` and `[ab]` match alternatives.
+pub async fn glob(
+	tg: &dyn tg::Handle,
+	directory: &tg::Directory,
+	patterns: &[String],
+	options: &Options,
+) -> Result<Vec<(tg::Path, tg::Artifact)>> {
+	let mut builder = GlobSetBuilder::new();
+	for pattern in patterns {
+		let glob = GlobBuilder::new(pattern)
+			.literal_separator(true)
+			.build()
+			.wrap_err_with(|| format!(r#"Invalid glob pattern "{pattern}"."#))?;
+		builder.add(glob);
+	}
+	let set = builder
+		.build()
+		.wrap_err("Failed to build the glob patterns.")?;
+	walk_inner(tg, directory, options, |path| {
+		set.is_match(path.to_string())
+	})
+	.await
+}
+
+async fn walk_inner(
+	tg: &dyn tg::Handle,
+	directory: &tg::Directory,
+	options: &Options,
+	filter: impl Fn(&tg::Path) -> bool,
+) -> Result<Vec<(tg::Path, tg::Artifact)>> {
+	let mut output = Vec::new();
+	let ancestors = if options.follow_symlinks {
+		vec![directory.id(tg).await?.clone()]
+	} else {
+		Vec::new()
+	};
+	let parents = [directory.clone()];
+	let mut stack = children(tg, &tg::Path::default(), directory, &parents, &ancestors).await?;
+	while let Some(item) = stack.pop() {
+		let Item {
+			path,
+			mut artifact,
+			mut parents,
+			ancestors,
+		} = item;
+
+		// Skip the entry if it is deeper than the maximum depth.
+		let depth = path.components().len();
+		if options.max_depth.is_some_and(|max_depth| depth > max_depth) {
+			continue;
+		}
+
+		// Resolve the artifact if it is a symlink and symlinks should be followed.
+		if options.follow_symlinks {
+			if let tg::Artifact::Symlink(symlink) = &artifact {
+				if let Some((resolved, resolved_parents)) = resolve(tg, &parents, symlink)
+					.await
+					.wrap_err_with(|| format!(r#"Failed to resolve the symlink at "{path}"."#))?
+				{
+					artifact = resolved;
+					parents = resolved_parents;
+				}
+			}
+		}
+
+		// Add the entry if it matches.
+		if filter(&path) {
+			output.push((path.clone(), artifact.clone()));
+		}
+
+		// Visit the entries of directories, unless the maximum depth has been reached or the directory is one of its own ancestors.
+		let tg::Artifact::Directory(directory) = &artifact else {
+			continue;
+		};
+		if options
+			.max_depth
+			.is_some_and(|max_depth| depth >= max_depth)
+		{
+			continue;
+		}
+		let mut ancestors = ancestors;
+		if options.follow_symlinks {
+			let id = directory.id(tg).await?;
+			if ancestors.contains(id) {
+				continue;
+			}
+			ancestors.push(id.clone());
+		}
+		parents.push(directory.clone());
+		stack.extend(children(tg, &path, directory, &parents, &ancestors).await?);
+	}
+	Ok(output)
+}
+
+/// Get the items for a directory's entries in reverse order, so that they are popped from the stack in order. The directory must be the last of the parents.
+async fn children(
+	tg: &dyn tg::Handle,
+	path: &tg::Path,
+	directory: &tg::Directory,
+	parents: &[tg::Directory],
+	ancestors: &[tg::directory::Id],
+) -> Result<Vec<Item>> {
+	let items = directory
+		.entries(tg)
+		.await?
+		.iter()
+		.rev()
+		.map(|(name, artifact)| Item {
+			path: path
+				.clone()
+				.join(tg::path::Component::Normal(name.clone()).into()),
+			artifact: artifact.clone(),
+			parents: parents.to_owned(),
+			ancestors: ancestors.to_owned(),
+		})
+		.collect();
+	Ok(items)
+}
+
+/// Resolve a symlink whose parent directories are `parents`, where the last directory contains the symlink. If the symlink resolves to a directory, the directories that contain it are returned along with it. If the symlink does not resolve to an artifact, then `None` is returned.
+async fn resolve(
+	tg: &dyn tg::Handle,
+	parents: &[tg::Directory],
+	symlink: &tg::Symlink,
+) -> Result<Option<(tg::Artifact, Vec<tg::Directory>)>> {
+	let mut parents = parents.to_owned();
+	let mut components = VecDeque::new();
+	let mut count = 0;
+	let mut symlink = Some(symlink.clone());
+	loop {
+		// Follow the current symlink.
+		if let Some(current) = symlink.take() {
+			count += 1;
+			if count > MAX_SYMLINK_DEPTH {
+				return_error!("Too many levels of symlinks.");
+			}
+			let artifact = current.artifact(tg).await?.clone();
+			let path = current.path(tg).await?.clone();
+			let path: Option<tg::Path> = path.as_deref().map(str::parse).transpose()?;
+			let path = match (artifact, path) {
+				(None, Some(path)) => Some(path),
+				(Some(tg::Artifact::Directory(directory)), path) => {
+					parents = vec![directory];
+					path
+				},
+				(Some(tg::Artifact::Symlink(artifact)), None) => {
+					parents.clear();
+					symlink = Some(artifact);
+					continue;
+				},
+				(Some(artifact), None) => {
+					if components.is_empty() {
+						return Ok(Some((artifact, parents)));
+					}
+					return Ok(None);
+				},
+				(None, None) | (Some(_), Some(_)) => return Ok(None),
+			};
+			if let Some(path) = path {
+				for component in path.into_components().into_iter().rev() {
+					components.push_front(component);
+				}
+			}
+		}
+
+		// Get the next component.
+		let Some(component) = components.pop_front() else {
+			let Some(directory) = parents.pop() else {
+				return Ok(None);
+			};
+			return Ok(Some((directory.into(), parents)));
+		};
+		let name = match component {
+			tg::path::Component::Current => continue,
+			tg::path::Component::Parent => {
+				if parents.len() <= 1 {
+					return Ok(None);
+				}
+				parents.pop();
+				continue;
+			},
+			tg::path::Component::Root => return Ok(None),
+			tg::path::Component::Normal(name) => name,
+		};
+
+		// Get the entry.
+		let Some(directory) = parents.last() else {
+			return Ok(None);
+		};
+		let Some(entry) = directory.entries(tg).await?.get(&name).cloned() else {
+			return Ok(None);
+		};
+		match entry {
+			tg::Artifact::Directory(directory) => parents.push(directory),
+			tg::Artifact::Symlink(entry) => symlink = Some(entry),
+			artifact @ tg::Artifact::File(_) => {
+				if components.is_empty() {
+					return Ok(Some((artifact, parents)));
+				}
+				return Ok(None);
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tg::test::{directory, file, Store};
+
+	fn symlink(path: &str) -> tg::Artifact {
+		tg::Symlink::new(None, Some(path.to_owned())).into()
+	}
+
+	fn root(artifact: tg::Artifact) -> tg::Directory {
+		artifact.try_unwrap_directory().unwrap()
+	}
+
+	fn paths(entries: &[(tg::Path, tg::Artifact)]) -> Vec<String> {
+		entries.iter().map(|(path, _)| path.to_string()).collect()
+	}
+
+	#[tokio::test]
+	async fn max_depth() {
+		let tg = Store::default();
+		let root = root(directory(vec![
+			("a", directory(vec![("b", file("b", false))])),
+			("c", file("c", false)),
+		]));
+		let walk_to = |max_depth| {
+			let options = Options {
+				follow_symlinks: false,
+				max_depth,
+			};
+			let (tg, root) = (tg.clone(), root.clone());
+			async move { paths(&walk(&tg, &root, &options).await.unwrap()) }
+		};
+		assert_eq!(walk_to(None).await, ["a", "a/b", "c"]);
+		assert_eq!(walk_to(Some(1)).await, ["a", "c"]);
+		assert_eq!(walk_to(Some(0)).await, Vec::<String>::new());
+	}
+
+	#[tokio::test]
+	async fn follow_symlinks() {
+		let tg = Store::default();
+		let root = root(directory(vec![
+			("dir", directory(vec![("file", file("hello", false))])),
+			("link", symlink("dir")),
+			("nested", symlink("link/file")),
+			("dangling", symlink("missing")),
+		]));
+
+		// Symlinks are not resolved by default.
+		let options = Options::default();
+		let entries = walk(&tg, &root, &options).await.unwrap();
+		assert_eq!(
+			paths(&entries),
+			["dangling", "dir", "dir/file", "link", "nested"]
+		);
+
+		// Symlinks to directories are walked, symlinks through symlinks resolve to their targets, and dangling symlinks are returned unresolved.
+		let options = Options {
+			follow_symlinks: true,
+			max_depth: None,
+		};
+		let entries = walk(&tg, &root, &options).await.unwrap();
+		assert_eq!(
+			paths(&entries),
+			["dangling", "dir", "dir/file", "link", "link/file", "nested"]
+		);
+		assert!(matches!(entries[0].1, tg::Artifact::Symlink(_)));
+		assert!(matches!(entries[3].1, tg::Artifact::Directory(_)));
+		assert!(matches!(entries[5].1, tg::Artifact::File(_)));
+	}
+
+	#[tokio::test]
+	async fn symlink_cycles() {
+		let tg = Store::default();
+		let options = Options {
+			follow_symlinks: true,
+			max_depth: None,
+		};
+
+		// A symlink to an ancestor is returned but not walked.
+		let root = self::root(directory(vec![(
+			"dir",
+			directory(vec![("up", symlink(".."))]),
+		)]));
+		let entries = walk(&tg, &root, &options).await.unwrap();
+		assert_eq!(paths(&entries), ["dir", "dir/up"]);
+		assert!(matches!(entries[1].1, tg::Artifact::Directory(_)));
+
+		// A symlink to itself fails to resolve.
+		let root = self::root(directory(vec![("loop", symlink("loop"))]));
+		let error = walk(&tg, &root, &options).await.unwrap_err();
+		assert!(error
+			.trace()
+			.to_string()
+			.contains("Too many levels of symlinks."));
+	}
+
+	#[tokio::test]
+	async fn glob_patterns() {
+		let tg = Store::default();
+		let root = root(directory(vec![
+			("README.md", file("", false)),
+			(
+				"src",
+				directory(vec![
+					("lib.rs", file("", false)),
+					("main.rs", file("", false)),
+					("nested", directory(vec![("mod.rs", file("", false))])),
+				]),
+			),
+		]));
+		let glob_paths = |patterns: &[&str]| {
+			let patterns: Vec<String> = patterns.iter().map(ToString::to_string).collect();
+			let (tg, root) = (tg.clone(), root.clone());
+			async move {
+				let entries = glob(&tg, &root, &patterns, &Options::default())
+					.await
+					.unwrap();
+				paths(&entries)
+			}
+		};
+		assert_eq!(glob_paths(&["*.md"]).await, ["README.md"]);
+		assert_eq!(
+			glob_paths(&["src/*.rs"]).await,
+			["src/lib.rs", "src/main.rs"]
+		);
+		assert_eq!(
+			glob_paths(&["**/*.rs"]).await,
+			["src/lib.rs", "src/main.rs", "src/nested/mod.rs"]
+		);
+		assert_eq!(
+			glob_paths(&["src/{main,nested/mod}.rs", "README.?d"]).await,
+			["README.md", "src/main.rs", "src/nested/mod.rs"]
+		);
+
+		// An invalid pattern is an error.
+		let patterns = ["[".to_owned()];
+		assert!(glob(&tg, &root, &patterns, &Options::default())
+			.await
+			.is_err());
+	}
+}
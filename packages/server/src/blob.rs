@@ -0,0 +1,86 @@
+use super::Server;
+use tangram_client as tg;
+use tangram_error::{return_error, Result, WrapErr};
+
+impl Server {
+	pub async fn try_get_blob_for_checksum(
+		&self,
+		checksum: &tg::Checksum,
+	) -> Result<Option<tg::blob::Id>> {
+		// An unsafe checksum does not identify a blob.
+		if matches!(checksum, tg::Checksum::Unsafe) {
+			return Ok(None);
+		}
+
+		// Attempt to get the blob for the checksum from the database.
+		'a: {
+			let Some(blob_id) = self.inner.database.try_get_blob_for_checksum(checksum)? else {
+				break 'a;
+			};
+			return Ok(Some(blob_id));
+		}
+
+		// Attempt to get the blob for the checksum from the remote.
+		'a: {
+			// Get the remote.
+			let Some(remote) = self.inner.remote.as_ref() else {
+				break 'a;
+			};
+
+			// Get the blob for the checksum from the remote.
+			let Some(blob_id) = remote.try_get_blob_for_checksum(checksum).await? else {
+				break 'a;
+			};
+
+			// Verify that the blob matches the checksum before trusting the remote's mapping.
+			if let Err(error) = self.verify_blob_checksum(checksum, &blob_id).await {
+				tracing::warn!(
+					?checksum,
+					?blob_id,
+					?error,
+					"The remote's blob for the checksum is invalid."
+				);
+				break 'a;
+			}
+
+			// Add the checksum to the database.
+			self.inner
+				.database
+				.set_blob_for_checksum(checksum, &blob_id)?;
+
+			return Ok(Some(blob_id));
+		}
+
+		Ok(None)
+	}
+
+	pub async fn set_blob_for_checksum(
+		&self,
+		checksum: &tg::Checksum,
+		id: &tg::blob::Id,
+	) -> Result<()> {
+		self.verify_blob_checksum(checksum, id).await?;
+		self.inner.database.set_blob_for_checksum(checksum, id)?;
+		Ok(())
+	}
+
+	/// Verify that a blob's contents hash to a checksum.
+	async fn verify_blob_checksum(&self, checksum: &tg::Checksum, id: &tg::blob::Id) -> Result<()> {
+		if matches!(checksum, tg::Checksum::Unsafe) {
+			return_error!("Cannot set the blob for an unsafe checksum.");
+		}
+		let blob = tg::Blob::with_id(id.clone());
+		let mut reader = blob.reader(self).await?;
+		let mut writer = tg::checksum::Writer::new(checksum.algorithm());
+		tokio::io::copy(&mut reader, &mut writer)
+			.await
+			.wrap_err("Failed to read the blob.")?;
+		let actual = writer.finalize();
+		if actual != *checksum {
+			return_error!(
+				r#"The blob "{id}" does not match the checksum. Expected "{checksum}" but got "{actual}"."#
+			);
+		}
+		Ok(())
+	}
+}
@@ -1,7 +1,7 @@
 use super::Server;
 use crate::{
 	BuildQueueTaskMessage, BuildState, BuildStateInner, BuildStatus, ChildrenState, LogState,
-	OutcomeState, StopState,
+	OutcomeState, ProfileState, StopState,
 };
 use async_recursion::async_recursion;
 use bytes::Bytes;
@@ -184,12 +184,32 @@ impl Server {
 		id: &tg::target::Id,
 		depth: u64,
 		retry: tg::build::Retry,
+		inspect: Option<std::net::SocketAddr>,
+		profile: Option<tg::build::ProfileOptions>,
 	) -> Result<tg::build::Id> {
 		let target = tg::Target::with_id(id.clone());
 		let host = target.host(self).await?.clone();
 
+		// Only JS targets can be inspected or profiled.
+		if inspect.is_some() && host.os() != tg::system::Os::Js {
+			return_error!("Only JS targets can be inspected.");
+		}
+		if profile.is_some() && host.os() != tg::system::Os::Js {
+			return_error!("Only JS targets can be profiled.");
+		}
+
+		// The debugger has full control of the build, so only allow local clients to connect.
+		if let Some(addr) = inspect {
+			if !addr.ip().is_loopback() {
+				return_error!(r#"The inspector's address "{addr}" must be a loopback address."#);
+			}
+		}
+
+		// A build that will be inspected or profiled must be new and must run on this server.
+		let local = inspect.is_some() || profile.is_some();
+
 		// Attempt to get the build for the target.
-		if let Some(build_id) = self.try_get_build_for_target(id).await? {
+		if let (false, Some(build_id)) = (local, self.try_get_build_for_target(id).await?) {
 			let build = tg::build::Build::with_id(build_id.clone());
 			if let Some(object) = build.try_get_object(self).await? {
 				let retry = retry >= object.outcome.retry();
@@ -202,7 +222,7 @@ impl Server {
 		}
 
 		// Decide whether to attempt to escalate the build.
-		let escalate = true;
+		let escalate = !local;
 
 		// Attempt to escalate the build.
 		if escalate {
@@ -211,7 +231,7 @@ impl Server {
 				let result = object.push(self, remote.as_ref()).await;
 				if result.is_ok() {
 					if let Ok(build_id) = remote
-						.get_or_create_build_for_target(user, id, depth, retry)
+						.get_or_create_build_for_target(user, id, depth, retry, None, None)
 						.await
 					{
 						return Ok(build_id);
@@ -248,6 +268,12 @@ impl Server {
 		let (sender, receiver) = tokio::sync::watch::channel(None);
 		let outcome = OutcomeState { sender, receiver };
 
+		// Create the profile state.
+		let profile = ProfileState {
+			options: profile,
+			profile: std::sync::Mutex::new(None),
+		};
+
 		// Create the build state.
 		let state = BuildState {
 			inner: Arc::new(BuildStateInner {
@@ -258,6 +284,8 @@ impl Server {
 				children,
 				log,
 				outcome,
+				inspect,
+				profile,
 			}),
 		};
 
@@ -333,8 +361,35 @@ impl Server {
 		// Build the target with the appropriate runtime.
 		let result = match target.host(self).await?.os() {
 			tg::system::Os::Js => {
+				// Get the build's inspect and profile options.
+				let (inspect, profile) = self
+					.inner
+					.build_state
+					.read()
+					.unwrap()
+					.get(id)
+					.map_or((None, None), |state| {
+						(state.inner.inspect, state.inner.profile.options)
+					});
+				let mut limits = self.inner.js_limits.clone();
+				if inspect.is_some() {
+					// Do not limit the time of a build that may be paused in the debugger.
+					limits.execution_time = None;
+					limits.time = None;
+				}
+				let module_cache =
+					tangram_runtime::js::ModuleCache::new(self.cache_path().join("modules"));
+				let options = tangram_runtime::js::Options {
+					limits,
+					inspect,
+					profile,
+					module_cache: Some(module_cache),
+					vendor_path: Some(self.vendor_path()),
+				};
+
 				// Build the target on the server's local pool because it is a `!Send` future.
-				self.inner
+				let output = self
+					.inner
 					.local_pool
 					.spawn_pinned({
 						let server = self.clone();
@@ -346,18 +401,31 @@ impl Server {
 								&build,
 								depth,
 								retry,
+								options,
 								main_runtime_handle,
 							)
 							.await
 						}
 					})
 					.await
-					.wrap_err("Failed to join the build task.")?
+					.wrap_err("Failed to join the build task.")?;
+
+				// Store the profile so that it is attached to the build when it finishes.
+				if let Some(profile) = output.profile {
+					self.set_build_profile(id, profile).await?;
+				}
+
+				output.result
 			},
 			tg::system::Os::Darwin => {
 				#[cfg(target_os = "macos")]
 				{
-					tangram_runtime::darwin::build(self, &build, retry, self.path()).await
+					async {
+						let secrets = self.get_secrets_for_target(&target).await?;
+						tangram_runtime::darwin::build(self, &build, retry, self.path(), &secrets)
+							.await
+					}
+					.await
 				}
 				#[cfg(not(target_os = "macos"))]
 				{
@@ -367,7 +435,12 @@ impl Server {
 			tg::system::Os::Linux => {
 				#[cfg(target_os = "linux")]
 				{
-					tangram_runtime::linux::build(self, &build, retry, self.path()).await
+					async {
+						let secrets = self.get_secrets_for_target(&target).await?;
+						tangram_runtime::linux::build(self, &build, retry, self.path(), &secrets)
+							.await
+					}
+					.await
 				}
 				#[cfg(not(target_os = "linux"))]
 				{
@@ -404,6 +477,31 @@ impl Server {
 		Ok(())
 	}
 
+	/// Store a build's profile on its state.
+	async fn set_build_profile(
+		&self,
+		id: &tg::build::Id,
+		profile: tangram_runtime::js::Profile,
+	) -> Result<()> {
+		let cpu = tg::Blob::with_reader(self, profile.cpu.as_ref()).await?;
+		let heap_snapshot = if let Some(heap_snapshot) = profile.heap_snapshot {
+			Some(tg::Blob::with_reader(self, heap_snapshot.as_ref()).await?)
+		} else {
+			None
+		};
+		let state = self.inner.build_state.read().unwrap().get(id).cloned();
+		if let Some(state) = state {
+			state
+				.inner
+				.profile
+				.profile
+				.lock()
+				.unwrap()
+				.replace(tg::build::Profile { cpu, heap_snapshot });
+		}
+		Ok(())
+	}
+
 	pub async fn get_build_from_queue(
 		&self,
 		user: Option<&tg::User>,
@@ -822,8 +920,20 @@ impl Server {
 				outcome
 			};
 
+			// Get the profile.
+			let profile = state.inner.profile.profile.lock().unwrap().take();
+
 			// Create the build.
-			tg::Build::new(self, id.clone(), target, children, log, outcome.clone()).await?;
+			tg::Build::new(
+				self,
+				id.clone(),
+				target,
+				children,
+				log,
+				outcome.clone(),
+				profile,
+			)
+			.await?;
 
 			// Add the assignment to the database.
 			self.inner.database.set_build_for_target(&target_id, id)?;
@@ -1,6 +1,6 @@
 use super::Server;
 use lmdb::Transaction;
-use tangram_error::{Result, WrapErr};
+use tangram_error::{Result, Wrap, WrapErr};
 
 impl Server {
 	pub async fn clean(&self) -> Result<()> {
@@ -16,9 +16,18 @@ impl Server {
 				.wrap_err("Failed to clear the objects.")?;
 			txn.clear_db(self.inner.database.assignments)
 				.wrap_err("Failed to clear the assignments.")?;
+			txn.clear_db(self.inner.database.checksums)
+				.wrap_err("Failed to clear the checksums.")?;
 			txn.commit().wrap_err("Failed to commit the transaction.")?;
 		}
 
+		// Remove the cache.
+		match tokio::fs::remove_dir_all(self.cache_path()).await {
+			Ok(()) => (),
+			Err(error) if error.kind() == std::io::ErrorKind::NotFound => (),
+			Err(error) => return Err(error.wrap("Failed to remove the cache directory.")),
+		}
+
 		// Clear the temporary path.
 		tokio::fs::remove_dir_all(self.tmp_path())
 			.await
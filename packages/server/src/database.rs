@@ -1,21 +1,27 @@
 use bytes::Bytes;
-use lmdb::Transaction;
-use std::path::Path;
+use lmdb::{Cursor, Transaction};
+use std::{collections::BTreeMap, path::Path};
 use tangram_client as tg;
-use tangram_error::{Result, Wrap, WrapErr};
+use tangram_error::{return_error, Result, Wrap, WrapErr};
 
 #[derive(Debug)]
 pub struct Database {
 	pub env: lmdb::Environment,
 	pub objects: lmdb::Database,
 	pub assignments: lmdb::Database,
+	pub checksums: lmdb::Database,
+	pub packages: lmdb::Database,
+	pub package_statuses: lmdb::Database,
+	pub package_signatures: lmdb::Database,
+	pub package_owners: lmdb::Database,
+	pub package_metadata: lmdb::Database,
 }
 
 impl Database {
 	pub fn open(path: &Path) -> Result<Self> {
 		let mut env_builder = lmdb::Environment::new();
 		env_builder.set_map_size(1_099_511_627_776);
-		env_builder.set_max_dbs(3);
+		env_builder.set_max_dbs(9);
 		env_builder.set_max_readers(1024);
 		env_builder.set_flags(lmdb::EnvironmentFlags::NO_SUB_DIR);
 		let env = env_builder
@@ -27,10 +33,34 @@ impl Database {
 		let assignments = env
 			.open_db(Some("assignments"))
 			.wrap_err("Failed to open the assignments database.")?;
+		let checksums = env
+			.open_db(Some("checksums"))
+			.wrap_err("Failed to open the checksums database.")?;
+		let packages = env
+			.open_db(Some("packages"))
+			.wrap_err("Failed to open the packages database.")?;
+		let package_statuses = env
+			.open_db(Some("package_statuses"))
+			.wrap_err("Failed to open the package statuses database.")?;
+		let package_signatures = env
+			.open_db(Some("package_signatures"))
+			.wrap_err("Failed to open the package signatures database.")?;
+		let package_owners = env
+			.open_db(Some("package_owners"))
+			.wrap_err("Failed to open the package owners database.")?;
+		let package_metadata = env
+			.open_db(Some("package_metadata"))
+			.wrap_err("Failed to open the package metadata database.")?;
 		let database = Database {
 			env,
 			objects,
 			assignments,
+			checksums,
+			packages,
+			package_statuses,
+			package_signatures,
+			package_owners,
+			package_metadata,
 		};
 		Ok(database)
 	}
@@ -126,4 +156,333 @@ impl Database {
 
 		Ok(())
 	}
+
+	pub fn try_get_blob_for_checksum(
+		&self,
+		checksum: &tg::Checksum,
+	) -> Result<Option<tg::blob::Id>> {
+		let txn = self
+			.env
+			.begin_ro_txn()
+			.wrap_err("Failed to create the transaction.")?;
+		let bytes = match txn.get(self.checksums, &checksum.to_string()) {
+			Ok(bytes) => bytes,
+			Err(lmdb::Error::NotFound) => return Ok(None),
+			Err(error) => return Err(error.wrap("Failed to get the blob.")),
+		};
+		let blob_id = std::str::from_utf8(bytes).wrap_err("Invalid ID.")?;
+		let blob_id = blob_id.parse().wrap_err("Invalid ID.")?;
+		Ok(Some(blob_id))
+	}
+
+	pub fn set_blob_for_checksum(
+		&self,
+		checksum: &tg::Checksum,
+		blob_id: &tg::blob::Id,
+	) -> Result<()> {
+		// Create a write transaction.
+		let mut txn = self
+			.env
+			.begin_rw_txn()
+			.wrap_err("Failed to create the transaction.")?;
+
+		// Add the checksum to the database.
+		txn.put(
+			self.checksums,
+			&checksum.to_string(),
+			&blob_id.to_string(),
+			lmdb::WriteFlags::empty(),
+		)
+		.wrap_err("Failed to put the checksum.")?;
+
+		// Commit the transaction.
+		txn.commit().wrap_err("Failed to commit the transaction.")?;
+
+		Ok(())
+	}
+
+	pub fn get_package_names(&self) -> Result<Vec<String>> {
+		let txn = self
+			.env
+			.begin_ro_txn()
+			.wrap_err("Failed to create the transaction.")?;
+		let mut cursor = txn
+			.open_ro_cursor(self.packages)
+			.wrap_err("Failed to open the cursor.")?;
+		let mut names = Vec::new();
+		for entry in cursor.iter_start() {
+			let (name, _) = entry.wrap_err("Failed to get the package.")?;
+			let name = std::str::from_utf8(name).wrap_err("Invalid package name.")?;
+			names.push(name.to_owned());
+		}
+		Ok(names)
+	}
+
+	pub fn try_get_package_versions(
+		&self,
+		name: &str,
+	) -> Result<Option<BTreeMap<String, tg::directory::Id>>> {
+		let txn = self
+			.env
+			.begin_ro_txn()
+			.wrap_err("Failed to create the transaction.")?;
+		let bytes = match txn.get(self.packages, &name) {
+			Ok(bytes) => bytes,
+			Err(lmdb::Error::NotFound) => return Ok(None),
+			Err(error) => return Err(error.wrap("Failed to get the package.")),
+		};
+		let versions = serde_json::from_slice(bytes)
+			.wrap_err("Failed to deserialize the package versions.")?;
+		Ok(Some(versions))
+	}
+
+	pub fn try_get_package_owner(&self, name: &str) -> Result<Option<tg::Id>> {
+		let txn = self
+			.env
+			.begin_ro_txn()
+			.wrap_err("Failed to create the transaction.")?;
+		let bytes = match txn.get(self.package_owners, &name) {
+			Ok(bytes) => bytes,
+			Err(lmdb::Error::NotFound) => return Ok(None),
+			Err(error) => return Err(error.wrap("Failed to get the package owner.")),
+		};
+		let owner = std::str::from_utf8(bytes)
+			.wrap_err("Invalid package owner.")?
+			.parse()?;
+		Ok(Some(owner))
+	}
+
+	pub fn try_get_package_metadata(
+		&self,
+		name: &str,
+		version: &str,
+	) -> Result<Option<tg::package::Metadata>> {
+		let txn = self
+			.env
+			.begin_ro_txn()
+			.wrap_err("Failed to create the transaction.")?;
+		let bytes = match txn.get(self.package_metadata, &format!("{name}@{version}")) {
+			Ok(bytes) => bytes,
+			Err(lmdb::Error::NotFound) => return Ok(None),
+			Err(error) => return Err(error.wrap("Failed to get the package metadata.")),
+		};
+		let metadata = serde_json::from_slice(bytes)
+			.wrap_err("Failed to deserialize the package metadata.")?;
+		Ok(Some(metadata))
+	}
+
+	/// Add a version of a package along with its metadata. The first user to publish a package becomes its owner. Returns an error if the version already exists or if the user is not the package's owner.
+	pub fn add_package_version(
+		&self,
+		name: &str,
+		version: &str,
+		package_id: &tg::directory::Id,
+		metadata: &tg::package::Metadata,
+		user: &tg::Id,
+	) -> Result<()> {
+		// Create a write transaction.
+		let mut txn = self
+			.env
+			.begin_rw_txn()
+			.wrap_err("Failed to create the transaction.")?;
+
+		// Verify that the user owns the package, or make the user the owner if the package is new.
+		match txn.get(self.package_owners, &name) {
+			Ok(bytes) => {
+				let owner = std::str::from_utf8(bytes).wrap_err("Invalid package owner.")?;
+				if owner != user.to_string() {
+					return_error!(r#"You are not the owner of the package "{name}"."#);
+				}
+			},
+			Err(lmdb::Error::NotFound) => {
+				txn.put(
+					self.package_owners,
+					&name,
+					&user.to_string(),
+					lmdb::WriteFlags::empty(),
+				)
+				.wrap_err("Failed to put the package owner.")?;
+			},
+			Err(error) => return Err(error.wrap("Failed to get the package owner.")),
+		}
+
+		// Get the package's versions.
+		let mut versions: BTreeMap<String, tg::directory::Id> = match txn.get(self.packages, &name)
+		{
+			Ok(bytes) => serde_json::from_slice(bytes)
+				.wrap_err("Failed to deserialize the package versions.")?,
+			Err(lmdb::Error::NotFound) => BTreeMap::new(),
+			Err(error) => return Err(error.wrap("Failed to get the package.")),
+		};
+
+		// Add the version.
+		if versions.contains_key(version) {
+			return_error!(r#"Version "{version}" of the package "{name}" already exists."#);
+		}
+		versions.insert(version.to_owned(), package_id.clone());
+
+		// Put the package's versions.
+		let bytes =
+			serde_json::to_vec(&versions).wrap_err("Failed to serialize the package versions.")?;
+		txn.put(self.packages, &name, &bytes, lmdb::WriteFlags::empty())
+			.wrap_err("Failed to put the package.")?;
+
+		// Put the version's metadata.
+		let bytes =
+			serde_json::to_vec(metadata).wrap_err("Failed to serialize the package metadata.")?;
+		txn.put(
+			self.package_metadata,
+			&format!("{name}@{version}"),
+			&bytes,
+			lmdb::WriteFlags::empty(),
+		)
+		.wrap_err("Failed to put the package metadata.")?;
+
+		// Commit the transaction.
+		txn.commit().wrap_err("Failed to commit the transaction.")?;
+
+		Ok(())
+	}
+
+	pub fn try_get_package_status(&self, name: &str) -> Result<Option<tg::package::Status>> {
+		let txn = self
+			.env
+			.begin_ro_txn()
+			.wrap_err("Failed to create the transaction.")?;
+		let bytes = match txn.get(self.package_statuses, &name) {
+			Ok(bytes) => bytes,
+			Err(lmdb::Error::NotFound) => return Ok(None),
+			Err(error) => return Err(error.wrap("Failed to get the package status.")),
+		};
+		let status =
+			serde_json::from_slice(bytes).wrap_err("Failed to deserialize the package status.")?;
+		Ok(Some(status))
+	}
+
+	pub fn set_package_status(&self, name: &str, status: &tg::package::Status) -> Result<()> {
+		// Create a write transaction.
+		let mut txn = self
+			.env
+			.begin_rw_txn()
+			.wrap_err("Failed to create the transaction.")?;
+
+		// Put the package's status.
+		let bytes =
+			serde_json::to_vec(status).wrap_err("Failed to serialize the package status.")?;
+		txn.put(
+			self.package_statuses,
+			&name,
+			&bytes,
+			lmdb::WriteFlags::empty(),
+		)
+		.wrap_err("Failed to put the package status.")?;
+
+		// Commit the transaction.
+		txn.commit().wrap_err("Failed to commit the transaction.")?;
+
+		Ok(())
+	}
+
+	pub fn get_package_signatures(
+		&self,
+		name: &str,
+		version: &str,
+	) -> Result<Vec<tg::package::Signature>> {
+		let txn = self
+			.env
+			.begin_ro_txn()
+			.wrap_err("Failed to create the transaction.")?;
+		let bytes = match txn.get(self.package_signatures, &format!("{name}@{version}")) {
+			Ok(bytes) => bytes,
+			Err(lmdb::Error::NotFound) => return Ok(Vec::new()),
+			Err(error) => return Err(error.wrap("Failed to get the package signatures.")),
+		};
+		let signatures = serde_json::from_slice(bytes)
+			.wrap_err("Failed to deserialize the package signatures.")?;
+		Ok(signatures)
+	}
+
+	/// Add a signature of a version of a package, replacing any existing signature with the same public key.
+	pub fn add_package_signature(
+		&self,
+		name: &str,
+		version: &str,
+		signature: &tg::package::Signature,
+	) -> Result<()> {
+		// Create a write transaction.
+		let mut txn = self
+			.env
+			.begin_rw_txn()
+			.wrap_err("Failed to create the transaction.")?;
+
+		// Get the signatures.
+		let key = format!("{name}@{version}");
+		let mut signatures: Vec<tg::package::Signature> =
+			match txn.get(self.package_signatures, &key) {
+				Ok(bytes) => serde_json::from_slice(bytes)
+					.wrap_err("Failed to deserialize the package signatures.")?,
+				Err(lmdb::Error::NotFound) => Vec::new(),
+				Err(error) => return Err(error.wrap("Failed to get the package signatures.")),
+			};
+
+		// Add the signature.
+		signatures.retain(|existing| existing.public_key != signature.public_key);
+		signatures.push(signature.clone());
+
+		// Put the signatures.
+		let bytes = serde_json::to_vec(&signatures)
+			.wrap_err("Failed to serialize the package signatures.")?;
+		txn.put(
+			self.package_signatures,
+			&key,
+			&bytes,
+			lmdb::WriteFlags::empty(),
+		)
+		.wrap_err("Failed to put the package signatures.")?;
+
+		// Commit the transaction.
+		txn.commit().wrap_err("Failed to commit the transaction.")?;
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Server;
+
+	#[tokio::test]
+	async fn only_the_owner_can_publish_new_versions() {
+		let temp = tempfile::TempDir::new().unwrap();
+		Server::migrate(temp.path()).await.unwrap();
+		let database = Database::open(&temp.path().join("database")).unwrap();
+		let owner = tg::Id::new_random(tg::id::Kind::User);
+		let other = tg::Id::new_random(tg::id::Kind::User);
+		let package: tg::directory::Id = tg::Id::new_hashed(tg::id::Kind::Directory, b"package")
+			.try_into()
+			.unwrap();
+		let metadata = tg::package::Metadata::default();
+
+		// The first publisher becomes the owner.
+		database
+			.add_package_version("foo", "1.0.0", &package, &metadata, &owner)
+			.unwrap();
+		assert_eq!(
+			database.try_get_package_owner("foo").unwrap(),
+			Some(owner.clone())
+		);
+
+		// Another user cannot publish a new version.
+		assert!(database
+			.add_package_version("foo", "1.1.0", &package, &metadata, &other)
+			.is_err());
+		let versions = database.try_get_package_versions("foo").unwrap().unwrap();
+		assert!(!versions.contains_key("1.1.0"));
+
+		// The owner can.
+		database
+			.add_package_version("foo", "1.1.0", &package, &metadata, &owner)
+			.unwrap();
+	}
 }
@@ -13,13 +13,16 @@ use tangram_error::{Result, Wrap, WrapErr};
 use tg::util::rmrf;
 
 mod artifact;
+mod blob;
 mod build;
 mod clean;
 mod database;
 mod migrations;
 mod object;
 mod package;
+mod secret;
 mod serve;
+mod user;
 
 /// A server.
 #[derive(Clone)]
@@ -56,6 +59,9 @@ struct Inner {
 	/// The database.
 	database: Database,
 
+	/// The limits for JS builds.
+	js_limits: tangram_runtime::js::Limits,
+
 	/// A semaphore that prevents opening too many file descriptors.
 	file_descriptor_semaphore: tokio::sync::Semaphore,
 
@@ -69,12 +75,21 @@ struct Inner {
 	#[allow(dead_code)]
 	lock_file: tokio::fs::File,
 
+	/// The package options.
+	package: PackageOptions,
+
 	/// The path to the directory where the server stores its data.
 	path: PathBuf,
 
+	/// The registry options. If this is `None`, then the server proxies package operations to the remote.
+	registry: Option<RegistryOptions>,
+
 	/// A client for communicating with the remote server.
 	remote: Option<Box<dyn tg::Handle>>,
 
+	/// A lock that serializes changes to the secret store.
+	secrets_lock: tokio::sync::Mutex<()>,
+
 	/// The server's version.
 	version: String,
 
@@ -96,6 +111,8 @@ struct BuildStateInner {
 	children: std::sync::Mutex<ChildrenState>,
 	log: Arc<tokio::sync::Mutex<LogState>>,
 	outcome: OutcomeState,
+	inspect: Option<std::net::SocketAddr>,
+	profile: ProfileState,
 }
 
 #[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
@@ -122,6 +139,12 @@ struct LogState {
 	sender: Option<tokio::sync::broadcast::Sender<Bytes>>,
 }
 
+#[derive(Debug)]
+struct ProfileState {
+	options: Option<tg::build::ProfileOptions>,
+	profile: std::sync::Mutex<Option<tg::build::Profile>>,
+}
+
 #[derive(Debug)]
 struct OutcomeState {
 	sender: tokio::sync::watch::Sender<Option<tg::build::Outcome>>,
@@ -142,20 +165,43 @@ type Task = (
 pub struct Options {
 	pub addr: tg::client::Addr,
 	pub build: Option<BuildOptions>,
+	pub package: Option<PackageOptions>,
 	pub path: PathBuf,
+	pub registry: Option<RegistryOptions>,
 	pub remote: Option<RemoteOptions>,
 	pub version: String,
 }
 
 pub struct BuildOptions {
+	pub js: Option<JsBuildOptions>,
 	pub remote: Option<RemoteBuildOptions>,
 }
 
+pub struct JsBuildOptions {
+	pub heap_limit: Option<usize>,
+	pub execution_time_limit: Option<std::time::Duration>,
+	pub time_limit: Option<std::time::Duration>,
+}
+
 pub struct RemoteBuildOptions {
 	pub enable: bool,
 	pub hosts: Option<Vec<tg::System>>,
 }
 
+#[derive(Default)]
+pub struct PackageOptions {
+	/// Whether to allow packages from the remote that are not signed by a trusted key.
+	pub allow_unsigned: bool,
+}
+
+pub struct RegistryOptions {
+	/// Whether to fall through to the remote for packages that are not in the registry.
+	pub upstream: bool,
+
+	/// The users that the registry authenticates by their tokens, so that a registry without a remote can accept publishes. The remote authenticates all other users.
+	pub users: Vec<tg::User>,
+}
+
 pub struct RemoteOptions {
 	pub tg: Box<dyn tg::Handle>,
 }
@@ -231,6 +277,18 @@ impl Server {
 		// Open the database.
 		let database = Database::open(&path.join("database"))?;
 
+		// Get the limits for JS builds.
+		let js_limits = options
+			.build
+			.as_ref()
+			.and_then(|build| build.js.as_ref())
+			.map(|js| tangram_runtime::js::Limits {
+				heap: js.heap_limit,
+				execution_time: js.execution_time_limit,
+				time: js.time_limit,
+			})
+			.unwrap_or_default();
+
 		// Create the file system semaphore.
 		let file_descriptor_semaphore = tokio::sync::Semaphore::new(16);
 
@@ -242,6 +300,12 @@ impl Server {
 			std::thread::available_parallelism().unwrap().get(),
 		);
 
+		// Get the package options.
+		let package = options.package.unwrap_or_default();
+
+		// Get the registry options.
+		let registry = options.registry;
+
 		// Get the remote.
 		let remote = if let Some(remote) = options.remote {
 			Some(remote.tg)
@@ -249,6 +313,9 @@ impl Server {
 			None
 		};
 
+		// Create the secrets lock.
+		let secrets_lock = tokio::sync::Mutex::new(());
+
 		// Get the version.
 		let version = options.version;
 
@@ -267,11 +334,15 @@ impl Server {
 			build_state,
 			database,
 			file_descriptor_semaphore,
+			js_limits,
 			task,
 			local_pool,
 			lock_file,
+			package,
 			path,
+			registry,
 			remote,
+			secrets_lock,
 			version,
 			vfs,
 		});
@@ -395,15 +466,35 @@ impl Server {
 		self.path().join("artifacts")
 	}
 
+	#[must_use]
+	pub fn cache_path(&self) -> PathBuf {
+		self.path().join("cache")
+	}
+
 	#[must_use]
 	pub fn database_path(&self) -> PathBuf {
 		self.path().join("database")
 	}
 
+	#[must_use]
+	pub fn secrets_path(&self) -> PathBuf {
+		self.path().join("secrets.json")
+	}
+
 	#[must_use]
 	pub fn tmp_path(&self) -> PathBuf {
 		self.path().join("tmp")
 	}
+
+	#[must_use]
+	pub fn vendor_path(&self) -> PathBuf {
+		self.path().join("vendor")
+	}
+
+	#[must_use]
+	pub fn trusted_keys_path(&self) -> PathBuf {
+		self.path().join("trusted_keys.json")
+	}
 }
 
 #[async_trait]
@@ -461,6 +552,21 @@ impl tg::Handle for Server {
 		self.check_out_artifact(id, path).await
 	}
 
+	async fn try_get_blob_for_checksum(
+		&self,
+		checksum: &tg::Checksum,
+	) -> Result<Option<tg::blob::Id>> {
+		self.try_get_blob_for_checksum(checksum).await
+	}
+
+	async fn set_blob_for_checksum(
+		&self,
+		checksum: &tg::Checksum,
+		id: &tg::blob::Id,
+	) -> Result<()> {
+		self.set_blob_for_checksum(checksum, id).await
+	}
+
 	async fn try_get_build_for_target(&self, id: &tg::target::Id) -> Result<Option<tg::build::Id>> {
 		self.try_get_build_for_target(id).await
 	}
@@ -471,8 +577,10 @@ impl tg::Handle for Server {
 		id: &tg::target::Id,
 		depth: u64,
 		retry: tg::build::Retry,
+		inspect: Option<std::net::SocketAddr>,
+		profile: Option<tg::build::ProfileOptions>,
 	) -> Result<tg::build::Id> {
-		self.get_or_create_build_for_target(user, id, depth, retry)
+		self.get_or_create_build_for_target(user, id, depth, retry, inspect, profile)
 			.await
 	}
 
@@ -540,7 +648,7 @@ impl tg::Handle for Server {
 		self.finish_build(user, id, outcome).await
 	}
 
-	async fn search_packages(&self, query: &str) -> Result<Vec<String>> {
+	async fn search_packages(&self, query: &str) -> Result<Vec<tg::package::SearchResult>> {
 		self.search_packages(query).await
 	}
 
@@ -583,6 +691,48 @@ impl tg::Handle for Server {
 		self.publish_package(user, id).await
 	}
 
+	async fn try_get_package_status(
+		&self,
+		dependency: &tg::Dependency,
+	) -> Result<Option<tg::package::Status>> {
+		self.try_get_package_status(dependency).await
+	}
+
+	async fn yank_package(
+		&self,
+		user: Option<&tg::User>,
+		dependency: &tg::Dependency,
+		yanked: bool,
+	) -> Result<()> {
+		self.yank_package(user, dependency, yanked).await
+	}
+
+	async fn deprecate_package(
+		&self,
+		user: Option<&tg::User>,
+		dependency: &tg::Dependency,
+		message: Option<&str>,
+	) -> Result<()> {
+		self.deprecate_package(user, dependency, message).await
+	}
+
+	async fn try_get_package_signatures(
+		&self,
+		dependency: &tg::Dependency,
+	) -> Result<Option<Vec<tg::package::Signature>>> {
+		self.try_get_package_signatures(dependency).await
+	}
+
+	async fn add_package_signature(
+		&self,
+		user: Option<&tg::User>,
+		dependency: &tg::Dependency,
+		signature: &tg::package::Signature,
+	) -> Result<()> {
+		self.add_package_signature(user, dependency, signature)
+			.await
+	}
+
 	async fn create_login(&self) -> Result<tg::user::Login> {
 		self.inner
 			.remote
@@ -602,11 +752,18 @@ impl tg::Handle for Server {
 	}
 
 	async fn get_user_for_token(&self, token: &str) -> Result<Option<tg::user::User>> {
-		self.inner
-			.remote
-			.as_ref()
-			.wrap_err("The server does not have a remote.")?
-			.get_user_for_token(token)
-			.await
+		self.get_user_for_token(token).await
+	}
+
+	async fn list_secrets(&self) -> Result<Vec<String>> {
+		self.list_secrets().await
+	}
+
+	async fn set_secret(&self, name: &str, value: &str) -> Result<()> {
+		self.set_secret(name, value).await
+	}
+
+	async fn remove_secret(&self, name: &str) -> Result<bool> {
+		self.remove_secret(name).await
 	}
 }
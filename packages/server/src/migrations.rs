@@ -5,7 +5,15 @@ use tangram_error::{return_error, Result, Wrap, WrapErr};
 
 impl Server {
 	pub async fn migrate(path: &Path) -> Result<()> {
-		let migrations = vec![migration_0000(path).boxed()];
+		let migrations = vec![
+			migration_0000(path).boxed(),
+			migration_0001(path).boxed(),
+			migration_0002(path).boxed(),
+			migration_0003(path).boxed(),
+			migration_0004(path).boxed(),
+			migration_0005(path).boxed(),
+			migration_0006(path).boxed(),
+		];
 
 		// Read the version from the version file.
 		let version = match tokio::fs::read_to_string(path.join("version")).await {
@@ -93,3 +101,117 @@ async fn migration_0000(path: &Path) -> Result<()> {
 
 	Ok(())
 }
+
+async fn migration_0001(path: &Path) -> Result<()> {
+	let path = path.to_owned();
+
+	// Open the database.
+	let database_path = path.join("database");
+	let mut env_builder = lmdb::Environment::new();
+	env_builder.set_max_dbs(4);
+	env_builder.set_flags(lmdb::EnvironmentFlags::NO_SUB_DIR);
+	let env = env_builder
+		.open(&database_path)
+		.wrap_err("Failed to open the database.")?;
+
+	// Create the checksums database.
+	env.create_db("checksums".into(), lmdb::DatabaseFlags::empty())
+		.wrap_err("Failed to create the checksums database.")?;
+
+	Ok(())
+}
+
+async fn migration_0002(path: &Path) -> Result<()> {
+	let path = path.to_owned();
+
+	// Open the database.
+	let database_path = path.join("database");
+	let mut env_builder = lmdb::Environment::new();
+	env_builder.set_max_dbs(5);
+	env_builder.set_flags(lmdb::EnvironmentFlags::NO_SUB_DIR);
+	let env = env_builder
+		.open(&database_path)
+		.wrap_err("Failed to open the database.")?;
+
+	// Create the packages database.
+	env.create_db("packages".into(), lmdb::DatabaseFlags::empty())
+		.wrap_err("Failed to create the packages database.")?;
+
+	Ok(())
+}
+
+async fn migration_0003(path: &Path) -> Result<()> {
+	let path = path.to_owned();
+
+	// Open the database.
+	let database_path = path.join("database");
+	let mut env_builder = lmdb::Environment::new();
+	env_builder.set_max_dbs(6);
+	env_builder.set_flags(lmdb::EnvironmentFlags::NO_SUB_DIR);
+	let env = env_builder
+		.open(&database_path)
+		.wrap_err("Failed to open the database.")?;
+
+	// Create the package statuses database.
+	env.create_db("package_statuses".into(), lmdb::DatabaseFlags::empty())
+		.wrap_err("Failed to create the package statuses database.")?;
+
+	Ok(())
+}
+
+async fn migration_0004(path: &Path) -> Result<()> {
+	let path = path.to_owned();
+
+	// Open the database.
+	let database_path = path.join("database");
+	let mut env_builder = lmdb::Environment::new();
+	env_builder.set_max_dbs(7);
+	env_builder.set_flags(lmdb::EnvironmentFlags::NO_SUB_DIR);
+	let env = env_builder
+		.open(&database_path)
+		.wrap_err("Failed to open the database.")?;
+
+	// Create the package signatures database.
+	env.create_db("package_signatures".into(), lmdb::DatabaseFlags::empty())
+		.wrap_err("Failed to create the package signatures database.")?;
+
+	Ok(())
+}
+
+async fn migration_0005(path: &Path) -> Result<()> {
+	let path = path.to_owned();
+
+	// Open the database.
+	let database_path = path.join("database");
+	let mut env_builder = lmdb::Environment::new();
+	env_builder.set_max_dbs(8);
+	env_builder.set_flags(lmdb::EnvironmentFlags::NO_SUB_DIR);
+	let env = env_builder
+		.open(&database_path)
+		.wrap_err("Failed to open the database.")?;
+
+	// Create the package owners database.
+	env.create_db("package_owners".into(), lmdb::DatabaseFlags::empty())
+		.wrap_err("Failed to create the package owners database.")?;
+
+	Ok(())
+}
+
+async fn migration_0006(path: &Path) -> Result<()> {
+	let path = path.to_owned();
+
+	// Open the database.
+	let database_path = path.join("database");
+	let mut env_builder = lmdb::Environment::new();
+	env_builder.set_max_dbs(9);
+	env_builder.set_flags(lmdb::EnvironmentFlags::NO_SUB_DIR);
+	let env = env_builder
+		.open(&database_path)
+		.wrap_err("Failed to open the database.")?;
+
+	// Create the package metadata database.
+	env.create_db("package_metadata".into(), lmdb::DatabaseFlags::empty())
+		.wrap_err("Failed to create the package metadata database.")?;
+
+	Ok(())
+}
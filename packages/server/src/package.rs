@@ -1,16 +1,45 @@
-use crate::Server;
+use crate::{database::Database, RegistryOptions, Server};
+use std::collections::{BTreeMap, BTreeSet};
 use tangram_client as tg;
-use tangram_error::{Result, WrapErr};
+use tangram_error::{return_error, Result, WrapErr};
 use tg::Handle;
 
 impl Server {
-	pub async fn search_packages(&self, query: &str) -> Result<Vec<String>> {
-		self.inner
-			.remote
-			.as_ref()
-			.wrap_err("The server does not have a remote.")?
-			.search_packages(query)
-			.await
+	pub async fn search_packages(&self, query: &str) -> Result<Vec<tg::package::SearchResult>> {
+		// If the server is not a registry, then search the remote.
+		if self.inner.registry.is_none() {
+			return self
+				.inner
+				.remote
+				.as_ref()
+				.wrap_err("The server does not have a remote.")?
+				.search_packages(query)
+				.await;
+		}
+
+		// Search the registry.
+		let mut results = search_registry(&self.inner.database)?
+			.into_iter()
+			.map(|result| (result.name.clone(), result))
+			.collect::<BTreeMap<_, _>>();
+
+		// Search the upstream. Packages in the registry take precedence.
+		if let Some(remote) = self.upstream() {
+			for result in remote.search_packages(query).await? {
+				results.entry(result.name.clone()).or_insert(result);
+			}
+		}
+
+		// Rank the results.
+		let mut results = results
+			.into_values()
+			.filter_map(|result| Some((search_score(query, &result)?, result)))
+			.collect::<Vec<_>>();
+		results.sort_by(|(a, a_result), (b, b_result)| {
+			b.cmp(a).then_with(|| a_result.name.cmp(&b_result.name))
+		});
+
+		Ok(results.into_iter().map(|(_, result)| result).collect())
 	}
 
 	pub async fn try_get_package(
@@ -30,10 +59,34 @@ impl Server {
 			}
 		}
 
-		// If the dependency has a name, then attempt to get it from the remote.
+		// If the dependency has a name, then attempt to get it from the registry. Yanked versions are skipped unless the dependency names the version exactly, so that lockfiles which use them keep working.
+		if let Some(name) = dependency.name.as_ref() {
+			if let Some(versions) = self.try_get_registry_package_versions(name)? {
+				let exact = dependency
+					.version
+					.as_ref()
+					.is_some_and(|version| versions.contains_key(version));
+				let yanked = self
+					.inner
+					.database
+					.try_get_package_status(name)?
+					.unwrap_or_default()
+					.yanked;
+				let package = matching_versions(&versions, dependency.version.as_deref())?
+					.into_iter()
+					.filter(|(version, _)| exact || !yanked.contains(&version.to_string()))
+					.last()
+					.map(|(_, package)| package.clone());
+				return Ok(package);
+			}
+		}
+
+		// If the dependency has a name, then attempt to get it from the upstream.
 		if dependency.name.is_some() {
-			if let Some(remote) = self.inner.remote.as_ref() {
+			if let Some(remote) = self.upstream() {
 				if let Some(package) = remote.try_get_package(dependency).await.ok().flatten() {
+					self.verify_package_signature(remote, dependency, &package)
+						.await?;
 					return Ok(Some(package));
 				}
 			}
@@ -61,12 +114,28 @@ impl Server {
 		&self,
 		dependency: &tg::Dependency,
 	) -> Result<Option<Vec<String>>> {
-		self.inner
-			.remote
-			.as_ref()
-			.wrap_err("The server does not have a remote.")?
-			.try_get_package_versions(dependency)
-			.await
+		// Attempt to get the versions from the registry.
+		if let Some(name) = dependency.name.as_ref() {
+			if self.inner.registry.is_some() {
+				let versions = registry_package_versions(
+					&self.inner.database,
+					name,
+					dependency.version.as_deref(),
+				)?;
+				if versions.is_some() {
+					return Ok(versions);
+				}
+			}
+		}
+
+		// Otherwise, get the versions from the upstream.
+		let Some(remote) = self.upstream() else {
+			if self.inner.registry.is_some() {
+				return Ok(None);
+			}
+			return Err(tangram_error::error!("The server does not have a remote."));
+		};
+		remote.try_get_package_versions(dependency).await
 	}
 
 	pub async fn try_get_package_metadata(
@@ -92,6 +161,33 @@ impl Server {
 		user: Option<&tg::User>,
 		id: &tg::directory::Id,
 	) -> Result<()> {
+		// If the server is a registry, then add the package to the database.
+		if self.inner.registry.is_some() {
+			let user = user.wrap_err("You must be logged in to publish a package.")?;
+
+			// Get the package's name and version.
+			let package = tg::Directory::with_id(id.clone());
+			let metadata = tangram_package::metadata(self, &package).await?;
+			let name = metadata
+				.name
+				.clone()
+				.wrap_err("The package must have a name.")?;
+			let version = metadata
+				.version
+				.clone()
+				.wrap_err("The package must have a version.")?;
+			version.parse::<semver::Version>().wrap_err_with(|| {
+				format!(r#"The version "{version}" of the package "{name}" is not valid semver."#)
+			})?;
+
+			// Add the version and its metadata.
+			self.inner
+				.database
+				.add_package_version(&name, &version, id, &metadata, &user.id)?;
+
+			return Ok(());
+		}
+
 		// Push the package.
 		self.push_object(&id.clone().into()).await?;
 
@@ -105,4 +201,616 @@ impl Server {
 
 		Ok(())
 	}
+
+	pub async fn try_get_package_status(
+		&self,
+		dependency: &tg::Dependency,
+	) -> Result<Option<tg::package::Status>> {
+		// Attempt to get the status from the registry.
+		let name = dependency
+			.name
+			.as_ref()
+			.wrap_err("The dependency must have a name.")?;
+		if self.try_get_registry_package_versions(name)?.is_some() {
+			let status = self
+				.inner
+				.database
+				.try_get_package_status(name)?
+				.unwrap_or_default();
+			return Ok(Some(status));
+		}
+
+		// Otherwise, get the status from the upstream.
+		let Some(remote) = self.upstream() else {
+			if self.inner.registry.is_some() {
+				return Ok(None);
+			}
+			return Err(tangram_error::error!("The server does not have a remote."));
+		};
+		remote.try_get_package_status(dependency).await
+	}
+
+	pub async fn yank_package(
+		&self,
+		user: Option<&tg::User>,
+		dependency: &tg::Dependency,
+		yanked: bool,
+	) -> Result<()> {
+		// If the server is not a registry, then yank the package on the remote.
+		if self.inner.registry.is_none() {
+			let remote = self
+				.inner
+				.remote
+				.as_ref()
+				.wrap_err("The server does not have a remote.")?;
+			return remote.yank_package(user, dependency, yanked).await;
+		}
+
+		// Verify that the version exists.
+		let (Some(name), Some(version)) = (dependency.name.as_ref(), dependency.version.as_ref())
+		else {
+			return_error!("The dependency must have a name and a version.");
+		};
+		self.authorize_package_owner(user, name)?;
+		let versions = self
+			.inner
+			.database
+			.try_get_package_versions(name)?
+			.wrap_err_with(|| format!(r#"The package "{name}" does not exist."#))?;
+		if !versions.contains_key(version) {
+			return_error!(r#"Version "{version}" of the package "{name}" does not exist."#);
+		}
+
+		// Update the package's status.
+		let mut status = self
+			.inner
+			.database
+			.try_get_package_status(name)?
+			.unwrap_or_default();
+		status.yanked.retain(|yanked| yanked != version);
+		if yanked {
+			status.yanked.push(version.clone());
+			status.yanked.sort();
+		}
+		self.inner.database.set_package_status(name, &status)?;
+
+		Ok(())
+	}
+
+	pub async fn deprecate_package(
+		&self,
+		user: Option<&tg::User>,
+		dependency: &tg::Dependency,
+		message: Option<&str>,
+	) -> Result<()> {
+		// If the server is not a registry, then deprecate the package on the remote.
+		if self.inner.registry.is_none() {
+			let remote = self
+				.inner
+				.remote
+				.as_ref()
+				.wrap_err("The server does not have a remote.")?;
+			return remote.deprecate_package(user, dependency, message).await;
+		}
+
+		// Verify that the package exists.
+		let name = dependency
+			.name
+			.as_ref()
+			.wrap_err("The dependency must have a name.")?;
+		self.authorize_package_owner(user, name)?;
+		if self
+			.inner
+			.database
+			.try_get_package_versions(name)?
+			.is_none()
+		{
+			return_error!(r#"The package "{name}" does not exist."#);
+		}
+
+		// Update the package's status.
+		let mut status = self
+			.inner
+			.database
+			.try_get_package_status(name)?
+			.unwrap_or_default();
+		status.deprecated = message.map(ToOwned::to_owned);
+		self.inner.database.set_package_status(name, &status)?;
+
+		Ok(())
+	}
+
+	pub async fn try_get_package_signatures(
+		&self,
+		dependency: &tg::Dependency,
+	) -> Result<Option<Vec<tg::package::Signature>>> {
+		// Attempt to get the signatures from the registry.
+		let (Some(name), Some(version)) = (dependency.name.as_ref(), dependency.version.as_ref())
+		else {
+			return_error!("The dependency must have a name and a version.");
+		};
+		if let Some(versions) = self.try_get_registry_package_versions(name)? {
+			if !versions.contains_key(version) {
+				return Ok(None);
+			}
+			let signatures = self.inner.database.get_package_signatures(name, version)?;
+			return Ok(Some(signatures));
+		}
+
+		// Otherwise, get the signatures from the upstream.
+		let Some(remote) = self.upstream() else {
+			if self.inner.registry.is_some() {
+				return Ok(None);
+			}
+			return Err(tangram_error::error!("The server does not have a remote."));
+		};
+		remote.try_get_package_signatures(dependency).await
+	}
+
+	pub async fn add_package_signature(
+		&self,
+		user: Option<&tg::User>,
+		dependency: &tg::Dependency,
+		signature: &tg::package::Signature,
+	) -> Result<()> {
+		// If the server is not a registry, then add the signature on the remote.
+		if self.inner.registry.is_none() {
+			let remote = self
+				.inner
+				.remote
+				.as_ref()
+				.wrap_err("The server does not have a remote.")?;
+			return remote
+				.add_package_signature(user, dependency, signature)
+				.await;
+		}
+
+		// Get the package.
+		let (Some(name), Some(version)) = (dependency.name.as_ref(), dependency.version.as_ref())
+		else {
+			return_error!("The dependency must have a name and a version.");
+		};
+		self.authorize_package_owner(user, name)?;
+		let package = self
+			.inner
+			.database
+			.try_get_package_versions(name)?
+			.and_then(|mut versions| versions.remove(version))
+			.wrap_err_with(|| {
+				format!(r#"Version "{version}" of the package "{name}" does not exist."#)
+			})?;
+
+		// Verify the signature.
+		signature.verify(name, version, &package)?;
+
+		// Add the signature.
+		self.inner
+			.database
+			.add_package_signature(name, version, signature)?;
+
+		Ok(())
+	}
+
+	/// Verify that a package from the remote is signed by a trusted key, unless unsigned packages are allowed.
+	async fn verify_package_signature(
+		&self,
+		remote: &dyn tg::Handle,
+		dependency: &tg::Dependency,
+		package: &tg::directory::Id,
+	) -> Result<()> {
+		if self.inner.package.allow_unsigned {
+			return Ok(());
+		}
+
+		// Get the package's name and version from its contents rather than from the remote.
+		let metadata =
+			tangram_package::metadata(self, &tg::Directory::with_id(package.clone())).await?;
+		let (Some(name), Some(version)) = (metadata.name, metadata.version) else {
+			return_error!(r#"The package "{package}" does not have a name and a version."#);
+		};
+
+		// Verify that the package is the one that was requested.
+		if dependency.name.as_ref() != Some(&name) {
+			return_error!(
+				r#"The remote returned the package "{name}@{version}" for "{dependency}"."#
+			);
+		}
+		if let Some(req) = dependency.version.as_ref() {
+			let matches = req == &version || {
+				let req: semver::VersionReq = req
+					.parse()
+					.wrap_err("Failed to parse the version requirement.")?;
+				let parsed: semver::Version = version
+					.parse()
+					.wrap_err_with(|| format!(r#"Invalid version "{version}"."#))?;
+				req.matches(&parsed)
+			};
+			if !matches {
+				return_error!(
+					r#"The remote returned the package "{name}@{version}" for "{dependency}"."#
+				);
+			}
+		}
+
+		// Get the signatures.
+		let dependency = tg::Dependency::with_name_and_version(name.clone(), version.clone());
+		let signatures = remote
+			.try_get_package_signatures(&dependency)
+			.await?
+			.unwrap_or_default();
+		if signatures.is_empty() {
+			return_error!(r#"The package "{name}@{version}" is not signed."#);
+		}
+
+		// Verify that a trusted key signed the package.
+		let trusted_keys = self.get_trusted_keys().await?;
+		let verified = signatures.iter().any(|signature| {
+			trusted_keys.contains(&signature.public_key)
+				&& signature.verify(&name, &version, package).is_ok()
+		});
+		if !verified {
+			return_error!(
+				r#"The package "{name}@{version}" does not have a valid signature from a trusted key."#
+			);
+		}
+
+		Ok(())
+	}
+
+	/// Read the trusted keys file, which is a JSON array of hex encoded ed25519 public keys.
+	async fn get_trusted_keys(&self) -> Result<BTreeSet<String>> {
+		let path = self.trusted_keys_path();
+		let exists = tokio::fs::try_exists(&path)
+			.await
+			.wrap_err("Failed to check if the trusted keys file exists.")?;
+		if !exists {
+			return Ok(BTreeSet::new());
+		}
+		let trusted_keys = tokio::fs::read_to_string(&path)
+			.await
+			.wrap_err("Failed to read the trusted keys file.")?;
+		let trusted_keys = serde_json::from_str(&trusted_keys)
+			.wrap_err("Failed to deserialize the trusted keys.")?;
+		Ok(trusted_keys)
+	}
+
+	/// Get the versions of a package in the registry. This returns `None` if the server is not a registry or the registry does not have the package. A registry that has a package never falls through to the upstream for it.
+	fn try_get_registry_package_versions(
+		&self,
+		name: &str,
+	) -> Result<Option<BTreeMap<String, tg::directory::Id>>> {
+		if self.inner.registry.is_none() {
+			return Ok(None);
+		}
+		self.inner.database.try_get_package_versions(name)
+	}
+
+	/// Verify that a user owns a package in the registry.
+	fn authorize_package_owner(&self, user: Option<&tg::User>, name: &str) -> Result<()> {
+		let user = user.wrap_err("You must be logged in to modify a package.")?;
+		let owner = self.inner.database.try_get_package_owner(name)?;
+		if owner.as_ref() != Some(&user.id) {
+			return_error!(r#"You are not the owner of the package "{name}"."#);
+		}
+		Ok(())
+	}
+
+	/// Get the handle to use for packages that are not in the registry.
+	fn upstream(&self) -> Option<&dyn tg::Handle> {
+		upstream(self.inner.registry.as_ref(), self.inner.remote.as_deref())
+	}
+}
+
+/// Get the handle to use for packages that are not in the registry. A server that is not a registry always uses its remote, and a registry only uses it if it falls through to the upstream.
+fn upstream<'a>(
+	registry: Option<&RegistryOptions>,
+	remote: Option<&'a dyn tg::Handle>,
+) -> Option<&'a dyn tg::Handle> {
+	match registry {
+		Some(registry) if !registry.upstream => None,
+		_ => remote,
+	}
+}
+
+/// Get the versions of a package in the registry that match a version requirement, sorted in ascending order. This returns `None` if the registry does not have the package.
+fn registry_package_versions(
+	database: &Database,
+	name: &str,
+	req: Option<&str>,
+) -> Result<Option<Vec<String>>> {
+	let Some(versions) = database.try_get_package_versions(name)? else {
+		return Ok(None);
+	};
+	let versions = matching_versions(&versions, req)?
+		.into_iter()
+		.map(|(version, _)| version.to_string())
+		.collect();
+	Ok(Some(versions))
+}
+
+/// Get a search result for each package in the registry, using the metadata stored when its latest version that is not yanked was published. Packages whose versions are all yanked are skipped.
+fn search_registry(database: &Database) -> Result<Vec<tg::package::SearchResult>> {
+	let mut results = Vec::new();
+	for name in database.get_package_names()? {
+		let versions = database
+			.try_get_package_versions(&name)?
+			.unwrap_or_default();
+		let yanked = database
+			.try_get_package_status(&name)?
+			.unwrap_or_default()
+			.yanked;
+		let Some(version) = matching_versions(&versions, None)?
+			.into_iter()
+			.map(|(version, _)| version.to_string())
+			.filter(|version| !yanked.contains(version))
+			.last()
+		else {
+			continue;
+		};
+		let metadata = match database.try_get_package_metadata(&name, &version) {
+			Ok(metadata) => metadata.unwrap_or_default(),
+			Err(error) => {
+				tracing::warn!(
+					?name,
+					?version,
+					?error,
+					"Failed to get the package metadata."
+				);
+				continue;
+			},
+		};
+		results.push(tg::package::SearchResult {
+			name,
+			version: Some(version),
+			description: metadata.description,
+			keywords: metadata.keywords.unwrap_or_default(),
+		});
+	}
+	Ok(results)
+}
+
+/// Get the versions that match a version requirement sorted in ascending order. A version that exactly matches the requirement is always selected.
+fn matching_versions<'a>(
+	versions: &'a BTreeMap<String, tg::directory::Id>,
+	req: Option<&str>,
+) -> Result<Vec<(semver::Version, &'a tg::directory::Id)>> {
+	if let Some(package) = req.and_then(|req| versions.get(req)) {
+		let version = req.unwrap().parse().wrap_err("Invalid version.")?;
+		return Ok(vec![(version, package)]);
+	}
+	let req: Option<semver::VersionReq> = req
+		.map(str::parse)
+		.transpose()
+		.wrap_err("Failed to parse the version requirement.")?;
+	let mut versions = versions
+		.iter()
+		.filter_map(|(version, package)| Some((version.parse().ok()?, package)))
+		.filter(|(version, _)| req.as_ref().map_or(true, |req| req.matches(version)))
+		.collect::<Vec<(semver::Version, _)>>();
+	versions.sort_by(|a, b| a.0.cmp(&b.0));
+	Ok(versions)
+}
+
+/// Score how well a search result matches a query, or return `None` if it does not match. Names that contain the query score highest, followed by names that contain the query's characters in order, then keywords, then descriptions.
+fn search_score(query: &str, result: &tg::package::SearchResult) -> Option<u64> {
+	let query = query.to_lowercase();
+	let name = result.name.to_lowercase();
+	if query.is_empty() || name == query {
+		return Some(u64::MAX);
+	}
+	if let Some(position) = name.find(&query) {
+		let penalty = (position * 1000 + name.len()) as u64;
+		return Some(3_000_000u64.saturating_sub(penalty).max(2_100_000));
+	}
+	if let Some(score) = fuzzy_score(&query, &name) {
+		return Some(2_000_000 + score);
+	}
+	let keyword = result
+		.keywords
+		.iter()
+		.any(|keyword| keyword.to_lowercase().contains(&query));
+	if keyword {
+		return Some(1_000_000);
+	}
+	let description = result
+		.description
+		.as_ref()
+		.is_some_and(|description| description.to_lowercase().contains(&query));
+	if description {
+		return Some(1);
+	}
+	None
+}
+
+/// Score a subsequence match of a query against a string. Consecutive characters and matches at the start of words score higher.
+fn fuzzy_score(query: &str, string: &str) -> Option<u64> {
+	let mut score = 0;
+	let mut chars = string.char_indices();
+	let mut previous_end = None;
+	for query_char in query.chars() {
+		let (index, char) = chars.find(|(_, char)| *char == query_char)?;
+		let consecutive = previous_end == Some(index);
+		let word_start = string[..index]
+			.chars()
+			.last()
+			.map_or(true, |char| !char.is_alphanumeric());
+		score += 1 + u64::from(consecutive) * 4 + u64::from(word_start) * 2;
+		previous_end = Some(index + char.len_utf8());
+	}
+	Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{fuzzy_score, registry_package_versions, search_registry, search_score, upstream};
+	use crate::{database::Database, RegistryOptions, Server};
+	use tangram_client as tg;
+
+	async fn database(temp: &tempfile::TempDir) -> Database {
+		Server::migrate(temp.path()).await.unwrap();
+		Database::open(&temp.path().join("database")).unwrap()
+	}
+
+	fn publish(database: &Database, name: &str, version: &str, description: &str) {
+		let owner = tg::Id::new_hashed(tg::id::Kind::User, b"owner");
+		let package: tg::directory::Id = tg::Id::new_hashed(
+			tg::id::Kind::Directory,
+			format!("{name}@{version}").as_bytes(),
+		)
+		.try_into()
+		.unwrap();
+		let metadata = tg::package::Metadata {
+			name: Some(name.to_owned()),
+			version: Some(version.to_owned()),
+			description: Some(description.to_owned()),
+			keywords: Some(vec![version.to_owned()]),
+			..Default::default()
+		};
+		database
+			.add_package_version(name, version, &package, &metadata, &owner)
+			.unwrap();
+	}
+
+	fn yank(database: &Database, name: &str, versions: &[&str]) {
+		let status = tg::package::Status {
+			deprecated: None,
+			yanked: versions
+				.iter()
+				.map(|version| (*version).to_owned())
+				.collect(),
+		};
+		database.set_package_status(name, &status).unwrap();
+	}
+
+	fn result(
+		name: &str,
+		keywords: &[&str],
+		description: Option<&str>,
+	) -> tg::package::SearchResult {
+		tg::package::SearchResult {
+			name: name.to_owned(),
+			version: None,
+			description: description.map(ToOwned::to_owned),
+			keywords: keywords
+				.iter()
+				.map(|keyword| (*keyword).to_owned())
+				.collect(),
+		}
+	}
+
+	#[test]
+	fn search_ranking() {
+		let exact = result("json", &[], None);
+		let prefix = result("json-schema", &[], None);
+		let substring = result("tangram-json", &[], None);
+		let subsequence = result("jq-sort-on", &[], None);
+		let keyword = result("serde", &["JSON"], None);
+		let description = result("yaml", &[], Some("Parse YAML and JSON."));
+		let none = result("toml", &["config"], Some("Parse TOML."));
+		let scores = [
+			&exact,
+			&prefix,
+			&substring,
+			&subsequence,
+			&keyword,
+			&description,
+		]
+		.map(|result| search_score("JSON", result).unwrap());
+		assert!(scores.windows(2).all(|window| window[0] > window[1]));
+		assert_eq!(search_score("json", &none), None);
+	}
+
+	#[test]
+	fn upstream_fallthrough() {
+		let client = tg::client::Builder::new(tg::client::Addr::Unix("/dev/null".into())).build();
+		let remote: &dyn tg::Handle = &client;
+		let registry = |upstream| RegistryOptions {
+			upstream,
+			users: Vec::new(),
+		};
+
+		// A server that is not a registry always uses its remote.
+		assert!(upstream(None, Some(remote)).is_some());
+		assert!(upstream(None, None).is_none());
+
+		// A registry only uses its remote if it falls through to the upstream.
+		assert!(upstream(Some(&registry(true)), Some(remote)).is_some());
+		assert!(upstream(Some(&registry(true)), None).is_none());
+		assert!(upstream(Some(&registry(false)), Some(remote)).is_none());
+	}
+
+	#[tokio::test]
+	async fn registry_versions() {
+		let temp = tempfile::TempDir::new().unwrap();
+		let database = database(&temp).await;
+		publish(&database, "foo", "1.0.0", "");
+		publish(&database, "foo", "1.10.0", "");
+		publish(&database, "foo", "1.2.0", "");
+		publish(&database, "foo", "2.0.0", "");
+
+		// Versions are sorted by semver, not lexically.
+		assert_eq!(
+			registry_package_versions(&database, "foo", None).unwrap(),
+			Some(vec![
+				"1.0.0".to_owned(),
+				"1.2.0".to_owned(),
+				"1.10.0".to_owned(),
+				"2.0.0".to_owned(),
+			])
+		);
+		assert_eq!(
+			registry_package_versions(&database, "foo", Some("^1.1")).unwrap(),
+			Some(vec!["1.2.0".to_owned(), "1.10.0".to_owned()])
+		);
+		assert_eq!(
+			registry_package_versions(&database, "foo", Some("1.2.0")).unwrap(),
+			Some(vec!["1.2.0".to_owned()])
+		);
+		assert_eq!(
+			registry_package_versions(&database, "foo", Some("^3")).unwrap(),
+			Some(vec![])
+		);
+
+		// A package that is not in the registry has no versions, so that the server can fall through to the upstream.
+		assert_eq!(
+			registry_package_versions(&database, "bar", None).unwrap(),
+			None
+		);
+	}
+
+	#[tokio::test]
+	async fn registry_search_skips_yanked_versions() {
+		let temp = tempfile::TempDir::new().unwrap();
+		let database = database(&temp).await;
+		publish(&database, "foo", "1.0.0", "The first foo.");
+		publish(&database, "foo", "1.1.0", "The second foo.");
+		publish(&database, "bar", "1.0.0", "The only bar.");
+		yank(&database, "foo", &["1.1.0"]);
+		yank(&database, "bar", &["1.0.0"]);
+
+		// The result uses the metadata of the latest version that is not yanked, and packages whose versions are all yanked are skipped.
+		let results = search_registry(&database).unwrap();
+		assert_eq!(
+			results,
+			vec![tg::package::SearchResult {
+				name: "foo".to_owned(),
+				version: Some("1.0.0".to_owned()),
+				description: Some("The first foo.".to_owned()),
+				keywords: vec!["1.0.0".to_owned()],
+			}]
+		);
+	}
+
+	#[test]
+	fn fuzzy_ranking() {
+		assert_eq!(fuzzy_score("abc", "acb"), None);
+
+		// Consecutive characters score higher.
+		assert!(fuzzy_score("js", "json").unwrap() > fuzzy_score("js", "jaxs").unwrap());
+
+		// Characters at the start of words score higher.
+		assert!(
+			fuzzy_score("tj", "tangram-json").unwrap() > fuzzy_score("tj", "tangramjson").unwrap()
+		);
+	}
 }
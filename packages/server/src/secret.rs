@@ -0,0 +1,113 @@
+use super::Server;
+use std::{collections::BTreeMap, path::Path};
+use tangram_client as tg;
+use tangram_error::{return_error, Result, WrapErr};
+use tokio::io::AsyncWriteExt;
+
+impl Server {
+	/// Resolve the secrets a target references from the server's secret store. The secret store is a JSON object that maps secret names to values.
+	pub(crate) async fn get_secrets_for_target(
+		&self,
+		target: &tg::Target,
+	) -> Result<BTreeMap<String, String>> {
+		// Get the names of the target's secrets.
+		let names = target.secrets(self).await?;
+		if names.is_empty() {
+			return Ok(BTreeMap::new());
+		}
+
+		// Only allow targets with a checksum to use secrets, because the checksum guarantees that the secrets cannot affect the output.
+		if target.checksum(self).await?.is_none() {
+			return_error!("Only targets with a checksum may use secrets.");
+		}
+
+		// Read the secret store.
+		let mut store = read_secrets(&self.secrets_path()).await?;
+
+		// Resolve the secrets.
+		let mut secrets = BTreeMap::new();
+		for name in names {
+			let Some(value) = store.remove(name) else {
+				return_error!(r#"The secret "{name}" was not found."#);
+			};
+			secrets.insert(name.clone(), value);
+		}
+
+		Ok(secrets)
+	}
+
+	pub async fn list_secrets(&self) -> Result<Vec<String>> {
+		let secrets = read_secrets(&self.secrets_path()).await?;
+		Ok(secrets.into_keys().collect())
+	}
+
+	pub async fn set_secret(&self, name: &str, value: &str) -> Result<()> {
+		let _lock = self.inner.secrets_lock.lock().await;
+		let mut secrets = read_secrets(&self.secrets_path()).await?;
+		secrets.insert(name.to_owned(), value.to_owned());
+		write_secrets(&self.secrets_path(), &secrets).await?;
+		Ok(())
+	}
+
+	pub async fn remove_secret(&self, name: &str) -> Result<bool> {
+		let _lock = self.inner.secrets_lock.lock().await;
+		let mut secrets = read_secrets(&self.secrets_path()).await?;
+		if secrets.remove(name).is_none() {
+			return Ok(false);
+		}
+		write_secrets(&self.secrets_path(), &secrets).await?;
+		Ok(true)
+	}
+}
+
+async fn read_secrets(path: &Path) -> Result<BTreeMap<String, String>> {
+	let exists = tokio::fs::try_exists(path)
+		.await
+		.wrap_err("Failed to check if the secrets file exists.")?;
+	if !exists {
+		return Ok(BTreeMap::new());
+	}
+	let secrets = tokio::fs::read_to_string(path)
+		.await
+		.wrap_err("Failed to read the secrets file.")?;
+	serde_json::from_str(&secrets).wrap_err("Failed to deserialize the secrets.")
+}
+
+/// Write the secret store, which only the server's user may read.
+async fn write_secrets(path: &Path, secrets: &BTreeMap<String, String>) -> Result<()> {
+	let secrets =
+		serde_json::to_string_pretty(secrets).wrap_err("Failed to serialize the secrets.")?;
+	tokio::fs::OpenOptions::new()
+		.write(true)
+		.create(true)
+		.truncate(true)
+		.mode(0o600)
+		.open(path)
+		.await
+		.wrap_err("Failed to open the secrets file.")?
+		.write_all(secrets.as_bytes())
+		.await
+		.wrap_err("Failed to save the secrets.")?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::os::unix::fs::PermissionsExt;
+
+	#[tokio::test]
+	async fn secrets_round_trip() {
+		let temp = tempfile::TempDir::new().unwrap();
+		let path = temp.path().join("secrets.json");
+		assert_eq!(read_secrets(&path).await.unwrap(), BTreeMap::new());
+
+		let secrets = BTreeMap::from([("TOKEN".to_owned(), "value".to_owned())]);
+		write_secrets(&path, &secrets).await.unwrap();
+		assert_eq!(read_secrets(&path).await.unwrap(), secrets);
+
+		// Only the owner may read the secrets.
+		let metadata = tokio::fs::metadata(&path).await.unwrap();
+		assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+	}
+}
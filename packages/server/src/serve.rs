@@ -21,6 +21,10 @@ type Outgoing = http_body_util::combinators::UnsyncBoxBody<
 	Box<dyn std::error::Error + Send + Sync + 'static>,
 >;
 
+/// A request extension that marks requests from connections to the UNIX socket.
+#[derive(Clone, Copy)]
+struct LocalConnection;
+
 impl Server {
 	pub async fn serve(self, addr: tg::client::Addr) -> Result<()> {
 		// Create the listener.
@@ -37,6 +41,9 @@ impl Server {
 
 		tracing::info!("🚀 Serving on {addr:?}.");
 
+		// Connections to the UNIX socket come from local clients.
+		let local = matches!(listener, Either::Right(_));
+
 		// Loop forever, accepting connections.
 		loop {
 			// Accept a new connection.
@@ -60,8 +67,11 @@ impl Server {
 			// Create the service.
 			let service = hyper::service::service_fn({
 				let server = self.clone();
-				move |request| {
+				move |mut request: http::Request<Incoming>| {
 					let server = server.clone();
+					if local {
+						request.extensions_mut().insert(LocalConnection);
+					}
 					async move { Ok::<_, Infallible>(server.handle_request(request).await) }
 				}
 			});
@@ -182,6 +192,16 @@ impl Server {
 				.map(Some)
 				.boxed(),
 
+			// Blobs
+			(http::Method::GET, ["v1", "checksums", _, "blob"]) => self
+				.handle_get_blob_for_checksum_request(request)
+				.map(Some)
+				.boxed(),
+			(http::Method::PUT, ["v1", "checksums", _, "blob"]) => self
+				.handle_set_blob_for_checksum_request(request)
+				.map(Some)
+				.boxed(),
+
 			// Packages
 			(http::Method::GET, ["v1", "packages", "search"]) => self
 				.handle_search_packages_request(request)
@@ -206,6 +226,25 @@ impl Server {
 				.handle_publish_package_request(request)
 				.map(Some)
 				.boxed(),
+			(http::Method::GET, ["v1", "packages", _, "status"]) => self
+				.handle_get_package_status_request(request)
+				.map(Some)
+				.boxed(),
+			(http::Method::POST, ["v1", "packages", _, "yank"]) => {
+				self.handle_yank_package_request(request).map(Some).boxed()
+			},
+			(http::Method::POST, ["v1", "packages", _, "deprecate"]) => self
+				.handle_deprecate_package_request(request)
+				.map(Some)
+				.boxed(),
+			(http::Method::GET, ["v1", "packages", _, "signatures"]) => self
+				.handle_get_package_signatures_request(request)
+				.map(Some)
+				.boxed(),
+			(http::Method::POST, ["v1", "packages", _, "signatures"]) => self
+				.handle_add_package_signature_request(request)
+				.map(Some)
+				.boxed(),
 
 			// Users
 			(http::Method::POST, ["v1", "logins"]) => {
@@ -219,6 +258,17 @@ impl Server {
 				.map(Some)
 				.boxed(),
 
+			// Secrets
+			(http::Method::GET, ["v1", "secrets"]) => {
+				self.handle_list_secrets_request(request).map(Some).boxed()
+			},
+			(http::Method::PUT, ["v1", "secrets", _]) => {
+				self.handle_set_secret_request(request).map(Some).boxed()
+			},
+			(http::Method::DELETE, ["v1", "secrets", _]) => {
+				self.handle_remove_secret_request(request).map(Some).boxed()
+			},
+
 			(_, _) => future::ready(None).boxed(),
 		}
 		.await;
@@ -344,13 +394,22 @@ impl Server {
 			serde_urlencoded::from_str(query).wrap_err("Failed to parse the search params.")?;
 		let depth = search_params.depth;
 		let retry = search_params.retry;
+		let inspect = search_params.inspect;
+		let profile = search_params.profile.then_some(tg::build::ProfileOptions {
+			heap_snapshot: search_params.heap_snapshot,
+		});
+
+		// The inspector gives a debugger full control of the build, so only allow local clients to request it.
+		if inspect.is_some() && request.extensions().get::<LocalConnection>().is_none() {
+			return Ok(forbidden());
+		}
 
 		// Get the user.
 		let user = self.try_get_user_from_request(&request).await?;
 
 		// Get or create the build for the target.
 		let build_id = self
-			.get_or_create_build_for_target(user.as_ref(), &id, depth, retry)
+			.get_or_create_build_for_target(user.as_ref(), &id, depth, retry, inspect, profile)
 			.await?;
 
 		// Create the response.
@@ -732,6 +791,58 @@ impl Server {
 		Ok(ok())
 	}
 
+	async fn handle_get_blob_for_checksum_request(
+		&self,
+		request: http::Request<Incoming>,
+	) -> Result<http::Response<Outgoing>> {
+		// Get the path params.
+		let path_components: Vec<&str> = request.uri().path().split('/').skip(1).collect();
+		let ["v1", "checksums", checksum, "blob"] = path_components.as_slice() else {
+			return_error!("Unexpected path.");
+		};
+		let Ok(checksum) = checksum.parse() else {
+			return Ok(bad_request());
+		};
+
+		// Attempt to get the blob for the checksum.
+		let Some(blob_id) = self.try_get_blob_for_checksum(&checksum).await? else {
+			return Ok(not_found());
+		};
+
+		// Create the response.
+		let body = serde_json::to_vec(&blob_id).wrap_err("Failed to serialize the response.")?;
+		let response = http::Response::builder().body(full(body)).unwrap();
+		Ok(response)
+	}
+
+	async fn handle_set_blob_for_checksum_request(
+		&self,
+		request: http::Request<Incoming>,
+	) -> Result<http::Response<Outgoing>> {
+		// Get the path params.
+		let path_components: Vec<&str> = request.uri().path().split('/').skip(1).collect();
+		let ["v1", "checksums", checksum, "blob"] = path_components.as_slice() else {
+			return_error!("Unexpected path.");
+		};
+		let Ok(checksum) = checksum.parse() else {
+			return Ok(bad_request());
+		};
+
+		// Read the body.
+		let bytes = request
+			.into_body()
+			.collect()
+			.await
+			.wrap_err("Failed to read the body.")?
+			.to_bytes();
+		let blob_id = serde_json::from_slice(&bytes).wrap_err("Failed to deserialize the body.")?;
+
+		// Set the blob for the checksum.
+		self.set_blob_for_checksum(&checksum, &blob_id).await?;
+
+		Ok(ok())
+	}
+
 	async fn handle_push_object_request(
 		&self,
 		request: http::Request<Incoming>,
@@ -952,6 +1063,168 @@ impl Server {
 		Ok(ok())
 	}
 
+	async fn handle_get_package_status_request(
+		&self,
+		request: http::Request<Incoming>,
+	) -> Result<http::Response<Outgoing>> {
+		// Get the path params.
+		let path_components: Vec<&str> = request.uri().path().split('/').skip(1).collect();
+		let [_, "packages", dependency, "status"] = path_components.as_slice() else {
+			return_error!("Unexpected path.");
+		};
+		let dependency =
+			urlencoding::decode(dependency).wrap_err("Failed to decode the dependency.")?;
+		let dependency = dependency
+			.parse()
+			.wrap_err("Failed to parse the dependency.")?;
+
+		// Get the package status.
+		let Some(status) = self.try_get_package_status(&dependency).await? else {
+			return Ok(not_found());
+		};
+
+		// Create the body.
+		let body = serde_json::to_vec(&status).wrap_err("Failed to serialize the status.")?;
+
+		// Create the response.
+		let response = http::Response::builder().body(full(body)).unwrap();
+
+		Ok(response)
+	}
+
+	async fn handle_yank_package_request(
+		&self,
+		request: http::Request<Incoming>,
+	) -> Result<http::Response<Outgoing>> {
+		// Get the user.
+		let user = self.try_get_user_from_request(&request).await?;
+
+		// Get the path params.
+		let path_components: Vec<&str> = request.uri().path().split('/').skip(1).collect();
+		let [_, "packages", dependency, "yank"] = path_components.as_slice() else {
+			return_error!("Unexpected path.");
+		};
+		let dependency =
+			urlencoding::decode(dependency).wrap_err("Failed to decode the dependency.")?;
+		let dependency: tg::Dependency = dependency
+			.parse()
+			.wrap_err("Failed to parse the dependency.")?;
+
+		// Read the body.
+		let bytes = request
+			.into_body()
+			.collect()
+			.await
+			.wrap_err("Failed to read the body.")?
+			.to_bytes();
+		let yanked = serde_json::from_slice(&bytes).wrap_err("Invalid request.")?;
+
+		// Yank the package.
+		self.yank_package(user.as_ref(), &dependency, yanked)
+			.await?;
+
+		Ok(ok())
+	}
+
+	async fn handle_deprecate_package_request(
+		&self,
+		request: http::Request<Incoming>,
+	) -> Result<http::Response<Outgoing>> {
+		// Get the user.
+		let user = self.try_get_user_from_request(&request).await?;
+
+		// Get the path params.
+		let path_components: Vec<&str> = request.uri().path().split('/').skip(1).collect();
+		let [_, "packages", dependency, "deprecate"] = path_components.as_slice() else {
+			return_error!("Unexpected path.");
+		};
+		let dependency =
+			urlencoding::decode(dependency).wrap_err("Failed to decode the dependency.")?;
+		let dependency: tg::Dependency = dependency
+			.parse()
+			.wrap_err("Failed to parse the dependency.")?;
+
+		// Read the body.
+		let bytes = request
+			.into_body()
+			.collect()
+			.await
+			.wrap_err("Failed to read the body.")?
+			.to_bytes();
+		let message: Option<String> =
+			serde_json::from_slice(&bytes).wrap_err("Invalid request.")?;
+
+		// Deprecate the package.
+		self.deprecate_package(user.as_ref(), &dependency, message.as_deref())
+			.await?;
+
+		Ok(ok())
+	}
+
+	async fn handle_get_package_signatures_request(
+		&self,
+		request: http::Request<Incoming>,
+	) -> Result<http::Response<Outgoing>> {
+		// Get the path params.
+		let path_components: Vec<&str> = request.uri().path().split('/').skip(1).collect();
+		let [_, "packages", dependency, "signatures"] = path_components.as_slice() else {
+			return_error!("Unexpected path.");
+		};
+		let dependency =
+			urlencoding::decode(dependency).wrap_err("Failed to decode the dependency.")?;
+		let dependency = dependency
+			.parse()
+			.wrap_err("Failed to parse the dependency.")?;
+
+		// Get the package signatures.
+		let Some(signatures) = self.try_get_package_signatures(&dependency).await? else {
+			return Ok(not_found());
+		};
+
+		// Create the body.
+		let body =
+			serde_json::to_vec(&signatures).wrap_err("Failed to serialize the signatures.")?;
+
+		// Create the response.
+		let response = http::Response::builder().body(full(body)).unwrap();
+
+		Ok(response)
+	}
+
+	async fn handle_add_package_signature_request(
+		&self,
+		request: http::Request<Incoming>,
+	) -> Result<http::Response<Outgoing>> {
+		// Get the user.
+		let user = self.try_get_user_from_request(&request).await?;
+
+		// Get the path params.
+		let path_components: Vec<&str> = request.uri().path().split('/').skip(1).collect();
+		let [_, "packages", dependency, "signatures"] = path_components.as_slice() else {
+			return_error!("Unexpected path.");
+		};
+		let dependency =
+			urlencoding::decode(dependency).wrap_err("Failed to decode the dependency.")?;
+		let dependency: tg::Dependency = dependency
+			.parse()
+			.wrap_err("Failed to parse the dependency.")?;
+
+		// Read the body.
+		let bytes = request
+			.into_body()
+			.collect()
+			.await
+			.wrap_err("Failed to read the body.")?
+			.to_bytes();
+		let signature = serde_json::from_slice(&bytes).wrap_err("Invalid request.")?;
+
+		// Add the signature.
+		self.add_package_signature(user.as_ref(), &dependency, &signature)
+			.await?;
+
+		Ok(ok())
+	}
+
 	async fn handle_create_login_request(
 		&self,
 		_request: http::Request<Incoming>,
@@ -1016,6 +1289,81 @@ impl Server {
 			.unwrap();
 		Ok(response)
 	}
+
+	async fn handle_list_secrets_request(
+		&self,
+		request: http::Request<Incoming>,
+	) -> Result<http::Response<Outgoing>> {
+		// Only allow local clients to manage secrets.
+		if request.extensions().get::<LocalConnection>().is_none() {
+			return Ok(forbidden());
+		}
+
+		// List the secrets.
+		let names = self.list_secrets().await?;
+
+		// Create the response.
+		let body = serde_json::to_vec(&names).wrap_err("Failed to serialize the response.")?;
+		let response = http::Response::builder().body(full(body)).unwrap();
+		Ok(response)
+	}
+
+	async fn handle_set_secret_request(
+		&self,
+		request: http::Request<Incoming>,
+	) -> Result<http::Response<Outgoing>> {
+		// Only allow local clients to manage secrets.
+		if request.extensions().get::<LocalConnection>().is_none() {
+			return Ok(forbidden());
+		}
+
+		// Get the path params.
+		let path_components: Vec<&str> = request.uri().path().split('/').skip(1).collect();
+		let ["v1", "secrets", name] = path_components.as_slice() else {
+			return_error!("Unexpected path.");
+		};
+		let name = urlencoding::decode(name)
+			.wrap_err("Failed to decode the name.")?
+			.into_owned();
+
+		// Read the body.
+		let bytes = request
+			.into_body()
+			.collect()
+			.await
+			.wrap_err("Failed to read the body.")?
+			.to_bytes();
+		let value: String = serde_json::from_slice(&bytes).wrap_err("Invalid request.")?;
+
+		// Set the secret.
+		self.set_secret(&name, &value).await?;
+
+		Ok(ok())
+	}
+
+	async fn handle_remove_secret_request(
+		&self,
+		request: http::Request<Incoming>,
+	) -> Result<http::Response<Outgoing>> {
+		// Only allow local clients to manage secrets.
+		if request.extensions().get::<LocalConnection>().is_none() {
+			return Ok(forbidden());
+		}
+
+		// Get the path params.
+		let path_components: Vec<&str> = request.uri().path().split('/').skip(1).collect();
+		let ["v1", "secrets", name] = path_components.as_slice() else {
+			return_error!("Unexpected path.");
+		};
+		let name = urlencoding::decode(name).wrap_err("Failed to decode the name.")?;
+
+		// Remove the secret.
+		if !self.remove_secret(&name).await? {
+			return Ok(not_found());
+		}
+
+		Ok(ok())
+	}
 }
 
 #[must_use]
@@ -1107,6 +1455,15 @@ fn unauthorized() -> http::Response<Outgoing> {
 		.unwrap()
 }
 
+/// 403
+#[must_use]
+fn forbidden() -> http::Response<Outgoing> {
+	http::Response::builder()
+		.status(http::StatusCode::FORBIDDEN)
+		.body(full("Forbidden."))
+		.unwrap()
+}
+
 /// 404
 #[must_use]
 fn not_found() -> http::Response<Outgoing> {
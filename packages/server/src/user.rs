@@ -0,0 +1,75 @@
+use super::{RegistryOptions, Server};
+use tangram_client as tg;
+use tangram_error::{Result, WrapErr};
+
+impl Server {
+	pub async fn get_user_for_token(&self, token: &str) -> Result<Option<tg::User>> {
+		// Authenticate the registry's users locally.
+		if let Some(registry) = self.inner.registry.as_ref() {
+			if let Some(user) = registry.user_for_token(token) {
+				return Ok(Some(user.clone()));
+			}
+
+			// A registry without a remote has no other users.
+			if self.inner.remote.is_none() {
+				return Ok(None);
+			}
+		}
+
+		// Otherwise, authenticate the user with the remote.
+		self.inner
+			.remote
+			.as_ref()
+			.wrap_err("The server does not have a remote.")?
+			.get_user_for_token(token)
+			.await
+	}
+}
+
+impl RegistryOptions {
+	/// Get the registry's user with a token.
+	fn user_for_token(&self, token: &str) -> Option<&tg::User> {
+		self.users.iter().find(|user| {
+			user.token
+				.as_ref()
+				.is_some_and(|user_token| user_token.to_string() == token)
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn user(email: &str) -> tg::User {
+		tg::User {
+			id: tg::Id::new_random(tg::id::Kind::User),
+			email: email.to_owned(),
+			token: Some(tg::Id::new_random(tg::id::Kind::Token)),
+		}
+	}
+
+	#[test]
+	fn registry_users_are_authenticated_by_their_tokens() {
+		let alice = user("alice@example.com");
+		let bob = user("bob@example.com");
+		let registry = RegistryOptions {
+			upstream: false,
+			users: vec![
+				alice.clone(),
+				bob.clone(),
+				tg::User {
+					token: None,
+					..user("carol@example.com")
+				},
+			],
+		};
+		let token = alice.token.as_ref().unwrap().to_string();
+		assert_eq!(registry.user_for_token(&token).unwrap().id, alice.id);
+		let token = bob.token.as_ref().unwrap().to_string();
+		assert_eq!(registry.user_for_token(&token).unwrap().id, bob.id);
+		let token = tg::Id::new_random(tg::id::Kind::Token).to_string();
+		assert!(registry.user_for_token(&token).is_none());
+		assert!(registry.user_for_token("").is_none());
+	}
+}